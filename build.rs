@@ -38,6 +38,7 @@ fn main() {
             "cross-small",
             "checkmark",
             "grid-filled",
+            "history",
             "menu-large",
             "pause",
             "play",
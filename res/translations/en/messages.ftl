@@ -40,8 +40,12 @@ loading_networking_stack_step_created_successfully = Networking stack created su
 loading_consensus_node_title = Initializing consensus node
 loading_consensus_node_step_creating = Creating consensus node...
 loading_consensus_node_step_created_successfully = Consensus node created successfully
+loading_external_node_title = Connecting to external node
+loading_external_node_step_connecting = Connecting to node RPC at {$url}...
+loading_external_node_step_connected = Connected to external node successfully
 loading_farmer_title = Instantiating farmer
 loading_farmer_step_initializing = Initializing farms {$index}/{$farms_total}...
+loading_farmer_step_resizing_farm = Resizing farm {$a_index}/{$b_farms_total} from {$c_old_size} to {$d_new_size}, this may take a while ({NUMBER($e_progress, minimumFractionDigits: 2, maximumFractionDigits: 2)}% estimated)...
 loading_farmer_step_created_successfully = Farmer created successfully
 loading_wiping_farmer_data_title = Wiping farmer data
 loading_wiping_farmer_data_step_wiping_farm = Wiping farm {$index}/{$farms_total} at {$path}...
@@ -49,6 +53,9 @@ loading_wiping_farmer_data_step_success = All farms wiped successfully
 loading_wiping_node_data_title = Wiping node data
 loading_wiping_node_data_step_wiping_node = Wiping node at {$path}...
 loading_wiping_node_data_step_success = Node data wiped successfully
+loading_wiping_scheduled_data_title = Wiping selected data
+loading_wiping_scheduled_data_step_wiping = Wiping {$path}...
+loading_wiping_scheduled_data_step_success = Selected data wiped successfully
 
 configuration_title = Configuration
 reconfiguration_title = Reconfiguration
@@ -67,6 +74,8 @@ configuration_farm_path_placeholder = Example: {$path}
 configuration_farm_path_tooltip = Absolute path where farm files will be stored, any SSD works, high endurance not necessary
 configuration_farm_path_button_select = Select
 configuration_farm_path_error_doesnt_exist_or_write_permissions = Folder doesn't exist or user is lacking write permissions
+configuration_farm_resize = Farm will be resized from {$a_old_sectors} to ~{$b_new_sectors} sectors, which may take up to ~{$c_minutes} minutes
+configuration_farm_resize_discards_sectors = Farm will be shrunk from {$a_old_sectors} to ~{$b_new_sectors} sectors, ~{$c_discarded_sectors} already plotted sectors will be discarded
 configuration_farm_size_kind_fixed = Fixed size
 configuration_farm_size_kind_free_percentage = % of free space
 configuration_farm_fixed_size_placeholder = Example: 4T, 2.5TB, 500GiB, etc.
@@ -74,10 +83,35 @@ configuration_farm_fixed_size_tooltip = Size of the farm in whichever units you
 configuration_farm_free_percentage_size_placeholder = Example: 100%, 1.1%, etc.
 configuration_farm_free_percentage_size_tooltip = Percentage of free disk space to occupy by this farm, anything above 0% works, but at least 2 GB of free space should remain on disk to avoid errors
 configuration_farm_delete = Delete this farm
+configuration_farm_advanced = Advanced farm options
+configuration_farm_cache_percentage = Piece cache percentage:
+configuration_farm_cache_percentage_tooltip = Percentage of farm space dedicated to piece cache that helps the network, default is {$percentage}%
+configuration_farm_read_mode = Read mode:
+configuration_farm_read_mode_tooltip = Concurrent chunks is best for SSDs, whole sector is best for HDDs
+configuration_farm_read_mode_concurrent_chunks = Concurrent chunks
+configuration_farm_read_mode_whole_sector = Whole sector
+configuration_farm_farming_threads = Farming threads:
+configuration_farm_farming_threads_tooltip = Number of threads used for proving rewards, 0 means it is chosen automatically
+configuration_farm_plotting_concurrency = Sectors plotted concurrently:
+configuration_farm_plotting_concurrency_tooltip = Maximum number of sectors plotted concurrently for this farm, default is {$sectors}
+configuration_farm_reward_address = Reward address:
+configuration_farm_reward_address_placeholder = Global reward address
+configuration_farm_reward_address_tooltip = Reward address in SS58 format for this farm only, leave empty to use global reward address
 configuration_advanced = Advanced configuration
 configuration_advanced_farmer = Farmer configuration
 configuration_advanced_farmer_reduce_plotting_cpu_load = Reduce plotting CPU load
 configuration_advanced_farmer_reduce_plotting_cpu_load_tooltip = Initial plotting uses all CPU cores by default, while with this option it will start using half of the cores like replotting, improving system responsiveness for other tasks
+configuration_advanced_farmer_plotting_cpu_cores = Plotting CPU cores
+configuration_advanced_farmer_plotting_cpu_cores_tooltip = CPU cores used for plotting, for example "0,2-5 6-11": groups of cores are separated by spaces and each group gets its own thread pool. Leave empty to group cores by L3 cache automatically
+configuration_advanced_farmer_l3_cache_groups = Detected L3 cache groups: {$l3_cache_groups}
+configuration_advanced_node = Node configuration
+configuration_advanced_node_name = Node name:
+configuration_advanced_node_name_placeholder = Generated automatically
+configuration_advanced_node_name_tooltip = Name under which node is shown in telemetry, must not contain '.' and '@'
+configuration_advanced_node_telemetry = Telemetry:
+configuration_advanced_node_telemetry_tooltip = Send node telemetry to endpoints from chain specification, which helps to monitor the network
+configuration_advanced_node_full_sync = Full sync from genesis:
+configuration_advanced_node_full_sync_tooltip = Download and verify the whole blockchain history instead of much faster snap sync, only applies to a node that syncs from scratch
 configuration_advanced_network = Network configuration
 configuration_advanced_network_default_port_number_tooltip = Default port number is {$port}
 configuration_advanced_network_substrate_port = Substrate (blockchain) P2P port (TCP):
@@ -92,11 +126,17 @@ configuration_button_save = Save
 configuration_button_start = Start
 configuration_dialog_button_select = Select
 configuration_dialog_button_cancel = Cancel
+configuration_discard_sectors_title = Discard plotted sectors?
+configuration_discard_sectors_detail = New farm sizes are smaller than what is already plotted, ~{$sectors} plotted sectors will be discarded and can't be recovered without plotting them again
+configuration_discard_sectors_button_cancel = Cancel
+configuration_discard_sectors_button_confirm = Discard sectors
 
 running_title = Running
 running_node_title = {$chain_name} consensus node
 running_node_title_tooltip = Click to open in file manager
 running_node_free_disk_space_tooltip = Free disk space: {$size} remaining
+running_node_disk_usage_tooltip_pruned = Node data takes {$size}, state of last {$state_pruning} blocks and bodies of last {$blocks_pruning} finalized blocks are kept
+running_node_disk_usage_tooltip_archive = Node data takes {$size}, archive node keeps state and blocks of the whole chain
 running_node_connections_tooltip = {$connected_peers}/{$expected_peers} peers connected, click for details about required P2P ports
 running_node_status_connecting = Connecting to the network, best block #{$block_number}
 running_node_status_syncing_speed_no_eta = , {NUMBER($blocks_per_second, minimumFractionDigits: 2, maximumFractionDigits: 2)} blocks/s
@@ -109,12 +149,34 @@ running_node_status_syncing =
         [regular] Regular sync
         *[unknown] Unknown sync kind {$sync_kind}
     } #{$best_block_number}/{$target_block}{$sync_speed}
+running_node_status_syncing_full =
+    {$sync_kind ->
+        [dsn] Full sync from DSN
+        [regular] Full sync
+        *[unknown] Unknown full sync kind {$sync_kind}
+    } #{$best_block_number}/{$target_block}{$sync_speed}
 running_node_status_synced = Synced, best block #{$best_block_number}
+running_node_status_external_syncing = Waiting for external node {$url} to sync
+running_node_status_external_synced = External node {$url} is synced
 running_farmer_title = Farmer
 running_farmer_button_expand_details = Expand details about each farm
 running_farmer_button_pause_plotting = Pause plotting/replotting, note that currently encoding sectors will not be interrupted
 running_farmer_button_resume_plotting = Resume plotting
+running_farmer_plotting_paused_by_schedule = Plotting paused by schedule until {$paused_until}
+running_farmer_button_history = Farming history
+running_farmer_history_period = Period
+running_farmer_history_rewards = Rewards
+running_farmer_history_missed_rewards = Missed rewards
+running_farmer_history_sectors_plotted = Sectors plotted/replotted
+running_farmer_history_sector_plotting_time = Time per sector
+running_farmer_history_auditing_time = Auditing time
+running_farmer_history_proving_time = Proving time
+running_farmer_history_today = Today
+running_farmer_history_last_7_days = Last 7 days
+running_farmer_history_last_30_days = Last 30 days
+running_farmer_history_lifetime = Lifetime
 running_farmer_account_balance_tooltip = Total account balance and coins farmed since application started, click to see details in Astral
+running_farmer_farm_reward_address_balances_tooltip = Balances of reward addresses configured for individual farms, click an address to see details in Astral
 running_farmer_piece_cache_sync = Piece cache sync {NUMBER($percentage, minimumFractionDigits: 2, maximumFractionDigits: 2)}%
 running_farmer_next_reward_estimate =
     Next reward estimate: {$eta_string ->
@@ -126,11 +188,30 @@ running_farmer_next_reward_estimate =
         *[unknown] unknown
     }
 running_farmer_farm_tooltip = Click to open in file manager
+running_farmer_farm_button_pause_plotting = Pause plotting/replotting of this farm only, note that currently encoding sectors will not be interrupted
+running_farmer_farm_button_resume_plotting = Resume plotting of this farm
+running_farmer_farm_button_maintenance = Farm maintenance
+running_farmer_farm_button_scrub = Check and repair farm
+running_farmer_farm_button_scrub_tooltip = Farm will be stopped while its metadata, plotted sectors and piece cache are checked, which may take a long time for large farms
+running_farmer_farm_scrub_dry_run = Only check, do not repair anything
+running_farmer_farm_scrub_stopping_farm = Stopping farm to check it
+running_farmer_farm_scrub_waiting = Waiting for the check of another farm to finish
+running_farmer_farm_scrub_checking_plot = Checking metadata and plotted sectors
+running_farmer_farm_scrub_checking_cache = Checking piece cache
+running_farmer_farm_scrub_starting_farm = Starting farm after check
+running_farmer_farm_scrub_finished = Check finished, repaired {$a_plot_issues} problems with metadata and plotted sectors and {$b_cache_issues} problems with piece cache
+running_farmer_farm_scrub_finished_dry_run = Check finished, found {$a_plot_issues} problems with metadata and plotted sectors and {$b_cache_issues} problems with piece cache, nothing was changed
+running_farmer_farm_scrub_failed = Check failed: {$error}
+running_farmer_farm_scrub_more_issues = ...and {$count} more, see logs for details
+running_farmer_farm_scrub_dismiss = Dismiss
 running_farmer_farm_reward_signatures_tooltip = {$successful_signatures}/{$total_signatures} successful reward signatures, expand farm details to see more information
 running_farmer_farm_auditing_performance_tooltip = Auditing performance: average time {NUMBER($a_average_time, minimumFractionDigits: 2, maximumFractionDigits: 2)}s, time limit {NUMBER($b_time_timit, minimumFractionDigits: 2, maximumFractionDigits: 2)}s
 running_farmer_farm_proving_performance_tooltip = Proving performance: average time {NUMBER($a_average_time, minimumFractionDigits: 2, maximumFractionDigits: 2)}s, time limit {NUMBER($b_time_timit, minimumFractionDigits: 2, maximumFractionDigits: 2)}s
 running_farmer_farm_non_fatal_error_tooltip = Non-fatal farming error happened and was recovered, see logs for more details: {$error}
 running_farmer_farm_crashed = Farm crashed: {$error}
+running_farmer_farm_restart_scheduled = Farm crashed: {$a_error}, restarting in {$b_delay} s (attempt {$c_attempt} of {$d_max_attempts})
+running_farmer_farm_restarting = Restarting farm (attempt {$a_attempt} of {$b_max_attempts})
+running_farmer_farm_resizing = Resizing farm from {$a_old_size} to {$b_new_size}, {NUMBER($c_progress, minimumFractionDigits: 2, maximumFractionDigits: 2)}% (estimated)
 running_farmer_farm_plotting_speed =  ({NUMBER($a_sector_time, minimumFractionDigits: 2, maximumFractionDigits: 2)} m/sector, {NUMBER($b_sectors_per_hour, minimumFractionDigits: 2, maximumFractionDigits: 2)} sectors/h)
 running_farmer_farm_plotting_initial =
     {$pausing_state ->
@@ -174,6 +255,7 @@ error_title = Error
 error_message = Error: {$error}
 error_message_failed_to_send_config_to_backend = Failed to send config to backend: {$error}
 error_message_failed_to_send_pause_plotting_to_backend = Failed to send pause plotting to backend: {$error}
+error_message_failed_to_send_scrub_farm_to_backend = Failed to send farm check to backend: {$error}
 error_button_show_logs = Show logs
 error_button_help_from_community = Help from community
 
@@ -182,13 +264,16 @@ new_version_available_button_open = Open releases page
 
 main_menu_show_logs = Show logs in file manager
 main_menu_change_configuration = Change configuration
+main_menu_wipe_data = Wipe data
 main_menu_share_feedback = Share feedback
 main_menu_about = About
 main_menu_exit = Exit
 
 status_bar_message_configuration_is_invalid = Configuration is invalid: {$error}
+status_bar_message_configuration_applied = Configuration changes were applied
 status_bar_message_restart_is_needed_for_configuration = Application restart is needed for configuration changes to take effect
 status_bar_message_failed_to_save_configuration = Failed to save configuration changes: {$error}
+status_bar_message_failed_to_schedule_wipe = Failed to schedule wipe: {$error}
 status_bar_message_restarted_after_crash = Space Acres automatically restarted after crash, check application and system logs for details
 status_bar_button_restart = Restart
 status_bar_button_ok = Ok
@@ -197,6 +282,24 @@ about_system_information =
     Config directory: {$config_directory}
     Data directory (including logs): {$data_directory}
 
+wipe_dialog_title = Wipe data
+wipe_dialog_description = Selected data will be deleted permanently. Space Acres will restart and delete it before node and farms are started.
+wipe_dialog_target_farm = Farm
+wipe_dialog_target_piece_cache = Piece cache of farm
+wipe_dialog_target_node_database = Node database
+wipe_dialog_target_node_network = Network keypair and known peers
+wipe_dialog_effect_farm = Farm identity and all plotted sectors will be deleted, farm will be plotted from scratch if it remains in configuration
+wipe_dialog_effect_piece_cache = Cached pieces will be deleted and downloaded again, plotted sectors are preserved
+wipe_dialog_effect_node_database = Blockchain will be synced from scratch
+wipe_dialog_effect_node_network = Node will get a new network identity and will discover peers from scratch
+wipe_dialog_paths = Following files and directories will be deleted:
+wipe_dialog_calculating = Calculating...
+wipe_dialog_nothing_to_wipe = There is nothing to wipe
+wipe_dialog_failed = Failed to determine what to wipe: {$error}
+wipe_dialog_size = Disk space to be freed: {$size}
+wipe_dialog_button_cancel = Cancel
+wipe_dialog_button_wipe = Wipe and restart
+
 tray_icon_open = Open
 tray_icon_quit = Quit
 
@@ -206,6 +309,7 @@ notification_stopped_with_error = Space Acres stopped with error
     .body = An error happened and requires user intervention to resolve
 notification_farm_error = One of the farms failed in Space Acres
     .body = An error happened and requires user intervention to resolve
+notification_farm_scrub_finished = Farm check finished in Space Acres
 notification_signed_reward_successfully = Signed new reward successfully 🥳
     .body = Thank you for securing the network 🙌
 notification_missed_reward = Reward signing failed 😞
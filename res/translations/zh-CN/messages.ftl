@@ -40,8 +40,12 @@ loading_networking_stack_step_created_successfully = 网络工作栈创建成功
 loading_consensus_node_title = 初始化共识节点
 loading_consensus_node_step_creating = 创建共识节点...
 loading_consensus_node_step_created_successfully = 共识节点创建成功
+loading_external_node_title = 连接外部节点
+loading_external_node_step_connecting = 正在连接节点 RPC {$url}...
+loading_external_node_step_connected = 成功连接到外部节点
 loading_farmer_title = 实例化农民
 loading_farmer_step_initializing = 初始化农场 {$index}/{$farms_total}...
+loading_farmer_step_resizing_farm = 正在将农场 {$a_index}/{$b_farms_total} 从 {$c_old_size} 调整为 {$d_new_size}，这可能需要一段时间（预计已完成 {NUMBER($e_progress, minimumFractionDigits: 2, maximumFractionDigits: 2)}%）...
 loading_farmer_step_created_successfully = 农民创建成功
 loading_wiping_farmer_data_title = 擦除农民数据
 loading_wiping_farmer_data_step_wiping_farm = 擦除农场 {$index}/{$farms_total} at {$path}...
@@ -49,6 +53,9 @@ loading_wiping_farmer_data_step_success = 所有农场数据擦除成功
 loading_wiping_node_data_title = 擦除节点数据
 loading_wiping_node_data_step_wiping_node = 擦除该目录的节点数据 {$path}...
 loading_wiping_node_data_step_success = 节点数据擦除成功
+loading_wiping_scheduled_data_title = 擦除所选数据
+loading_wiping_scheduled_data_step_wiping = 擦除 {$path}...
+loading_wiping_scheduled_data_step_success = 所选数据擦除成功
 
 configuration_title = 配置
 reconfiguration_title = 重新配置
@@ -67,6 +74,8 @@ configuration_farm_path_placeholder = 示例: {$path}
 configuration_farm_path_tooltip = 存储农场数据文件的绝对路径，可使用任何类型的 SSD
 configuration_farm_path_button_select = 选择
 configuration_farm_path_error_doesnt_exist_or_write_permissions = 文件目录不存在或当前用户无写入权限
+configuration_farm_resize = 农场将从 {$a_old_sectors} 个扇区调整为约 {$b_new_sectors} 个扇区，这可能需要最多约 {$c_minutes} 分钟
+configuration_farm_resize_discards_sectors = 农场将从 {$a_old_sectors} 个扇区缩小为约 {$b_new_sectors} 个扇区，约 {$c_discarded_sectors} 个已绘制的扇区将被丢弃
 configuration_farm_size_kind_fixed = 固定大小
 configuration_farm_size_kind_free_percentage = % 空闲磁盘大小
 configuration_farm_fixed_size_placeholder = 示例: 4T, 2.5TB, 500GiB, 等.
@@ -74,10 +83,35 @@ configuration_farm_fixed_size_tooltip = 农场单元的大小，可以使用任
 configuration_farm_free_percentage_size_placeholder = 示例: 100%, 1.1%, 等.
 configuration_farm_free_percentage_size_tooltip = 用于该农场的磁盘大小百分比，可以指定任意大小，但需保留最少2GB的剩余空间避免出现问题
 configuration_farm_delete = 删除这个农场
+configuration_farm_advanced = 农场高级选项
+configuration_farm_cache_percentage = 碎片缓存百分比:
+configuration_farm_cache_percentage_tooltip = 农场空间中用于帮助网络的碎片缓存百分比，默认值为 {$percentage}%
+configuration_farm_read_mode = 读取模式:
+configuration_farm_read_mode_tooltip = 并发分块读取适用于 SSD，整个扇区读取适用于 HDD
+configuration_farm_read_mode_concurrent_chunks = 并发分块
+configuration_farm_read_mode_whole_sector = 整个扇区
+configuration_farm_farming_threads = 耕种线程数:
+configuration_farm_farming_threads_tooltip = 用于奖励证明的线程数，0 表示自动选择
+configuration_farm_plotting_concurrency = 并发绘图扇区数:
+configuration_farm_plotting_concurrency_tooltip = 该农场同时绘图的最大扇区数，默认值为 {$sectors}
+configuration_farm_reward_address = 奖励地址：
+configuration_farm_reward_address_placeholder = 全局奖励地址
+configuration_farm_reward_address_tooltip = 仅用于此农场的 SS58 格式奖励地址，留空则使用全局奖励地址
 configuration_advanced = 高级配置
 configuration_advanced_farmer = 农民配置
 configuration_advanced_farmer_reduce_plotting_cpu_load = 降低绘图时CPU负载
 configuration_advanced_farmer_reduce_plotting_cpu_load_tooltip = 初次绘图默认会使用所有CPU核心，这个选项可以在重新绘图时只占用50%的CPU来使系统响应其他任务更加流畅
+configuration_advanced_farmer_plotting_cpu_cores = 绘图CPU核心
+configuration_advanced_farmer_plotting_cpu_cores_tooltip = 用于绘图的CPU核心，例如 "0,2-5 6-11"：核心组之间用空格分隔，每组使用独立的线程池。留空则按L3缓存自动分组
+configuration_advanced_farmer_l3_cache_groups = 检测到的L3缓存分组：{$l3_cache_groups}
+configuration_advanced_node = 节点配置
+configuration_advanced_node_name = 节点名称:
+configuration_advanced_node_name_placeholder = 自动生成
+configuration_advanced_node_name_tooltip = 节点在遥测中显示的名称，不能包含 '.' 和 '@'
+configuration_advanced_node_telemetry = 遥测:
+configuration_advanced_node_telemetry_tooltip = 向链规范中的遥测端点发送节点遥测数据，有助于监控网络
+configuration_advanced_node_full_sync = 从创世区块完整同步:
+configuration_advanced_node_full_sync_tooltip = 下载并验证完整的区块链历史，而不是更快的快照同步，仅对从头开始同步的节点生效
 configuration_advanced_network = 网络配置
 configuration_advanced_network_default_port_number_tooltip = 默认端口是 {$port}
 configuration_advanced_network_substrate_port = Substrate (blockchain) P2P 端口 (TCP):
@@ -92,12 +126,18 @@ configuration_button_save = 保存
 configuration_button_start = 开始
 configuration_dialog_button_select = 选择
 configuration_dialog_button_cancel = 取消
+configuration_discard_sectors_title = 丢弃已绘制的扇区？
+configuration_discard_sectors_detail = 新的农场大小小于已绘制的大小，约 {$sectors} 个已绘制的扇区将被丢弃，只有重新绘制才能恢复
+configuration_discard_sectors_button_cancel = 取消
+configuration_discard_sectors_button_confirm = 丢弃扇区
 
 running_title = 运行中
 running_node_title = {$chain_name} 共识节点
 running_node_title_tooltip = 在文件管理器中打开
 running_node_connections_tooltip = {$connected_peers}/{$expected_peers} 节点已连接, 点击查看所需 P2P 端口
 running_node_free_disk_space_tooltip = 空闲磁盘大小: {$size}
+running_node_disk_usage_tooltip_pruned = 节点数据占用 {$size}, 保留最近 {$state_pruning} 个区块的状态和最近 {$blocks_pruning} 个已确认区块的区块体
+running_node_disk_usage_tooltip_archive = 节点数据占用 {$size}, 归档节点保留整条链的状态和区块
 running_node_status_connecting = 连接网络中，最新区块 #{$block_number}
 running_node_status_syncing_speed_no_eta = , {NUMBER($blocks_per_second, minimumFractionDigits: 2, maximumFractionDigits: 2)} blocks/s
 running_node_status_syncing_speed_hours_eta = , {NUMBER($a_blocks_per_second, minimumFractionDigits: 2, maximumFractionDigits: 2)} blocks/s (预计 ~{NUMBER($b_hours_remaining, minimumFractionDigits: 2, maximumFractionDigits: 2)} 小时)
@@ -109,12 +149,34 @@ running_node_status_syncing =
         [regular] 常规同步
         *[unknown] 未知同步类型 {$sync_kind}
     } #{$best_block_number}/{$target_block}{$sync_speed}
+running_node_status_syncing_full =
+    {$sync_kind ->
+        [dsn] DSN 网络完整同步
+        [regular] 完整同步
+        *[unknown] 未知完整同步类型 {$sync_kind}
+    } #{$best_block_number}/{$target_block}{$sync_speed}
 running_node_status_synced = 同步完成, 最新区块 #{$best_block_number}
+running_node_status_external_syncing = 等待外部节点 {$url} 同步
+running_node_status_external_synced = 外部节点 {$url} 已同步
 running_farmer_title = 农民
 running_farmer_button_expand_details = 各农场的详细信息
 running_farmer_button_pause_plotting = 暂停绘图/重新绘图，当前的编码扇区不会被中断
 running_farmer_button_resume_plotting = 继续绘图
+running_farmer_plotting_paused_by_schedule = 根据计划暂停绘图，直到 {$paused_until}
+running_farmer_button_history = 耕种历史
+running_farmer_history_period = 时间段
+running_farmer_history_rewards = 奖励
+running_farmer_history_missed_rewards = 错过的奖励
+running_farmer_history_sectors_plotted = 已绘制/重新绘制的扇区
+running_farmer_history_sector_plotting_time = 每个扇区用时
+running_farmer_history_auditing_time = 审计用时
+running_farmer_history_proving_time = 证明用时
+running_farmer_history_today = 今天
+running_farmer_history_last_7_days = 最近 7 天
+running_farmer_history_last_30_days = 最近 30 天
+running_farmer_history_lifetime = 全部
 running_farmer_account_balance_tooltip = 自启动以来耕种到的总奖励币，点击在 Astral 中查看更多详细信息
+running_farmer_farm_reward_address_balances_tooltip = 为单个农场配置的奖励地址余额，点击地址在 Astral 中查看更多详细信息
 running_farmer_piece_cache_sync = Piece缓存同步 {NUMBER($percentage, minimumFractionDigits: 2, maximumFractionDigits: 2)}%
 running_farmer_next_reward_estimate =
     下次奖励预计: {$eta_string ->
@@ -126,11 +188,30 @@ running_farmer_next_reward_estimate =
         *[unknown] 未知
     }
 running_farmer_farm_tooltip = 在文件管理器中打开
+running_farmer_farm_button_pause_plotting = 仅暂停此农场的绘图/重新绘图，当前的编码扇区不会被中断
+running_farmer_farm_button_resume_plotting = 继续此农场的绘图
+running_farmer_farm_button_maintenance = 农场维护
+running_farmer_farm_button_scrub = 检查并修复农场
+running_farmer_farm_button_scrub_tooltip = 检查元数据、已绘制扇区和碎片缓存期间农场将停止运行，大型农场可能需要很长时间
+running_farmer_farm_scrub_dry_run = 仅检查，不做任何修复
+running_farmer_farm_scrub_stopping_farm = 正在停止农场以进行检查
+running_farmer_farm_scrub_waiting = 等待其他农场检查完成
+running_farmer_farm_scrub_checking_plot = 正在检查元数据和已绘制扇区
+running_farmer_farm_scrub_checking_cache = 正在检查碎片缓存
+running_farmer_farm_scrub_starting_farm = 检查完成，正在启动农场
+running_farmer_farm_scrub_finished = 检查完成，修复了 {$a_plot_issues} 个元数据和已绘制扇区问题，以及 {$b_cache_issues} 个碎片缓存问题
+running_farmer_farm_scrub_finished_dry_run = 检查完成，发现 {$a_plot_issues} 个元数据和已绘制扇区问题，以及 {$b_cache_issues} 个碎片缓存问题，未做任何更改
+running_farmer_farm_scrub_failed = 检查失败: {$error}
+running_farmer_farm_scrub_more_issues = ……以及另外 {$count} 个，详情请查看日志
+running_farmer_farm_scrub_dismiss = 关闭
 running_farmer_farm_reward_signatures_tooltip = {$successful_signatures}/{$total_signatures} 奖励签名成功，打开农场查看更多信息
 running_farmer_farm_auditing_performance_tooltip = 审计性能: 平均时长 {NUMBER($a_average_time, minimumFractionDigits: 2, maximumFractionDigits: 2)}秒, 时间限制 {NUMBER($b_time_timit, minimumFractionDigits: 2, maximumFractionDigits: 2)}秒
 running_farmer_farm_proving_performance_tooltip = 证明性能: 平均时长 {NUMBER($a_average_time, minimumFractionDigits: 2, maximumFractionDigits: 2)}秒, 时间限制 {NUMBER($b_time_timit, minimumFractionDigits: 2, maximumFractionDigits: 2)}秒
 running_farmer_farm_non_fatal_error_tooltip = 非致命错误发生并已经恢复，在日志中查看更多信息: {$error}
 running_farmer_farm_crashed = 农场崩溃: {$error}
+running_farmer_farm_restart_scheduled = 农场崩溃: {$a_error}，将在 {$b_delay} 秒后重启（第 {$c_attempt} 次，共 {$d_max_attempts} 次）
+running_farmer_farm_restarting = 正在重启农场（第 {$a_attempt} 次，共 {$b_max_attempts} 次）
+running_farmer_farm_resizing = 正在将农场从 {$a_old_size} 调整为 {$b_new_size}，预计已完成 {NUMBER($c_progress, minimumFractionDigits: 2, maximumFractionDigits: 2)}%
 running_farmer_farm_plotting_speed =  ({NUMBER($a_sector_time, minimumFractionDigits: 2, maximumFractionDigits: 2)} 分钟/扇区, {NUMBER($b_sectors_per_hour, minimumFractionDigits: 2, maximumFractionDigits: 2)} 扇区/小时)
 running_farmer_farm_plotting_initial =
     {$pausing_state ->
@@ -174,6 +255,7 @@ error_title = 错误
 error_message = 错误: {$error}
 error_message_failed_to_send_config_to_backend = 发送到后端过程出错: {$error}
 error_message_failed_to_send_pause_plotting_to_backend = 发送暂停任务到后端出错: {$error}
+error_message_failed_to_send_scrub_farm_to_backend = 发送农场检查任务到后端出错: {$error}
 error_button_show_logs = 打开日志
 error_button_help_from_community = 获得社区支持
 
@@ -182,13 +264,16 @@ new_version_available_button_open = 打开版本发布页面
 
 main_menu_show_logs = 在文件管理器中打开日志
 main_menu_change_configuration = 修改配置
+main_menu_wipe_data = 擦除数据
 main_menu_share_feedback = 分享反馈
 main_menu_about = 关于
 main_menu_exit = 退出
 
 status_bar_message_configuration_is_invalid = 配置不可用: {$error}
+status_bar_message_configuration_applied = 配置更改已生效
 status_bar_message_restart_is_needed_for_configuration = 重启以使配置修改生效
 status_bar_message_failed_to_save_configuration = 保存配置修改失败: {$error}
+status_bar_message_failed_to_schedule_wipe = 计划擦除失败: {$error}
 status_bar_message_restarted_after_crash = Space Acres在崩溃后自动重启，请在日志中查看详细信息
 status_bar_button_restart = 重启
 status_bar_button_ok = 正常
@@ -197,6 +282,24 @@ about_system_information =
     配置目录: {$config_directory}
     数据目录 (包括日志): {$data_directory}
 
+wipe_dialog_title = 擦除数据
+wipe_dialog_description = 所选数据将被永久删除。Space Acres 将重启，并在启动节点和农场之前删除这些数据。
+wipe_dialog_target_farm = 农场
+wipe_dialog_target_piece_cache = 农场的片段缓存
+wipe_dialog_target_node_database = 节点数据库
+wipe_dialog_target_node_network = 网络密钥对和已知节点
+wipe_dialog_effect_farm = 农场身份和所有已绘制扇区将被删除，如果农场仍在配置中，将从头开始绘制
+wipe_dialog_effect_piece_cache = 缓存的片段将被删除并重新下载，已绘制扇区将被保留
+wipe_dialog_effect_node_database = 区块链将从头开始同步
+wipe_dialog_effect_node_network = 节点将获得新的网络身份，并从头开始发现节点
+wipe_dialog_paths = 以下文件和目录将被删除:
+wipe_dialog_calculating = 计算中...
+wipe_dialog_nothing_to_wipe = 没有需要擦除的内容
+wipe_dialog_failed = 无法确定要擦除的内容: {$error}
+wipe_dialog_size = 将释放的磁盘空间: {$size}
+wipe_dialog_button_cancel = 取消
+wipe_dialog_button_wipe = 擦除并重启
+
 tray_icon_open = 打开
 tray_icon_quit = 退出
 
@@ -206,6 +309,7 @@ notification_stopped_with_error = Space Acres由于错误暂停
     .body = 出现一个错误，需要手动解决
 notification_farm_error = 一个Space Acres农场出错
     .body = 出现一个错误，需要手动解决
+notification_farm_scrub_finished = Space Acres农场检查完成
 notification_signed_reward_successfully = 成功签署一份奖励 🥳
     .body = 感谢参与 🙌
 notification_missed_reward = 签署奖励失败 😞
@@ -0,0 +1,806 @@
+//! Local control API that allows monitoring and automation scripts to talk to the running
+//! application.
+//!
+//! API listens on a loopback TCP address and uses newline-delimited JSON in both directions: every
+//! connected client receives backend notifications as JSON objects with `type` field and can send
+//! actions as JSON objects with `action` field, for example
+//! `{"action":"pausePlotting","pause":true}`.
+//!
+//! Any local process, including web pages opened in a browser, can connect to a loopback address,
+//! so the first line sent by client must be `{"token":"<token>"}` with token from `api-token` file
+//! next to configuration file, which is generated on the first start. Nothing is sent to client and
+//! nothing is processed before that, connection is closed on invalid token, on anything that looks
+//! like HTTP and on the first request that is not understood.
+
+use crate::backend::config::RawConfig;
+use crate::backend::farmer::scrub::ScrubStep;
+use crate::backend::farmer::{FarmRestartStep, FarmerAction, FarmerNotification};
+use crate::backend::node::SyncState;
+use crate::backend::{
+    BackendAction, BackendNotification, ConfigSaveOutcome, FarmIndex, NodeNotification,
+};
+use futures::channel::mpsc;
+use futures::{FutureExt, SinkExt, StreamExt, select};
+use serde::{Deserialize, Serialize};
+use sp_core::crypto::{AccountId32, Ss58Codec};
+use std::borrow::Cow;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::pin::pin;
+use std::sync::{Arc, Mutex};
+use std::{io, net};
+use subspace_core_primitives::sectors::SectorIndex;
+use subspace_core_primitives::{BlockNumber, PublicKey};
+use subspace_farmer::farm::{FarmingNotification, SectorPlottingDetails, SectorUpdate};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use tracing::{debug, error, info, warn};
+
+/// Number of events buffered for each client, slow clients will miss events beyond this
+const EVENTS_BUFFER: usize = 1000;
+/// File next to configuration file with token clients must authenticate with
+const API_TOKEN_FILE: &str = "api-token";
+/// Size of generated token in bytes
+const API_TOKEN_SIZE: usize = 32;
+/// Methods HTTP requests start with, such requests are never processed
+const HTTP_METHODS: &[&str] = &[
+    "GET ", "HEAD ", "POST ", "PUT ", "DELETE ", "CONNECT ", "OPTIONS ", "TRACE ", "PATCH ",
+];
+
+/// Events sent to API clients
+#[derive(Debug, Serialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+enum ApiEvent<'a> {
+    Loading {
+        step: String,
+        progress: f32,
+    },
+    ConfigurationFound {
+        raw_config: &'a RawConfig,
+    },
+    IncompatibleChain {
+        compatible_chain: &'a str,
+    },
+    NotConfigured,
+    ConfigurationIsInvalid {
+        error: String,
+    },
+    ConfigSaveResult {
+        outcome: Option<ConfigSaveOutcome>,
+        error: Option<String>,
+    },
+    Running {
+        chain_name: &'a str,
+        token_symbol: &'a str,
+        /// Omitted when connected to external node
+        #[serde(skip_serializing_if = "Option::is_none")]
+        best_block_number: Option<BlockNumber>,
+        reward_address: &'a str,
+        /// String because JSON numbers can't represent balances precisely, omitted when connected
+        /// to external node
+        #[serde(skip_serializing_if = "Option::is_none")]
+        reward_address_balance: Option<String>,
+        farms: Vec<ApiFarm<'a>>,
+    },
+    SyncStateUpdate {
+        syncing: bool,
+        synced: bool,
+        target: Option<BlockNumber>,
+    },
+    ConnectedPeersUpdate {
+        connected_peers: u32,
+    },
+    BlockImported {
+        block_number: BlockNumber,
+        /// String because JSON numbers can't represent balances precisely
+        reward_address_balance: String,
+    },
+    SectorPlotted {
+        farm_index: FarmIndex,
+        sector_index: SectorIndex,
+        replotted: bool,
+        time_ms: u64,
+    },
+    SectorPlottingFailed {
+        farm_index: FarmIndex,
+        sector_index: SectorIndex,
+        error: &'a str,
+    },
+    SectorUpdate {
+        farm_index: FarmIndex,
+        sector_index: SectorIndex,
+        update: String,
+    },
+    Proving {
+        farm_index: FarmIndex,
+        result: String,
+        time_ms: u64,
+    },
+    FarmingNotification {
+        farm_index: FarmIndex,
+        notification: String,
+    },
+    FarmerCacheSyncProgress {
+        progress: f32,
+    },
+    FarmError {
+        farm_index: FarmIndex,
+        error: String,
+    },
+    FarmAdded {
+        #[serde(flatten)]
+        farm: ApiFarm<'a>,
+        plotting_paused: bool,
+    },
+    FarmRemoved {
+        farm_index: FarmIndex,
+    },
+    PlottingScheduleUpdate {
+        /// Local time when plotting will be resumed, `null` if schedule doesn't pause plotting
+        paused_until: Option<String>,
+    },
+    FarmScrubProgress {
+        farm_index: FarmIndex,
+        step: &'static str,
+    },
+    FarmScrubFinished {
+        farm_index: FarmIndex,
+        dry_run: bool,
+        plot_issues: &'a [String],
+        cache_issues: &'a [String],
+    },
+    FarmScrubFailed {
+        farm_index: FarmIndex,
+        error: String,
+    },
+    FarmRestartScheduled {
+        farm_index: FarmIndex,
+        error: String,
+        attempt: u32,
+        max_attempts: u32,
+        delay_secs: u64,
+    },
+    FarmRestarting {
+        farm_index: FarmIndex,
+        attempt: u32,
+        max_attempts: u32,
+    },
+    FarmResizing {
+        farm_index: FarmIndex,
+        old_allocated_space: u64,
+        new_allocated_space: u64,
+        /// Estimated progress so far in %
+        progress: f32,
+    },
+    Stopped {
+        error: Option<String>,
+    },
+    IrrecoverableError {
+        error: String,
+    },
+    /// Action sent by client was forwarded to backend
+    ActionAccepted,
+    /// Request sent by client was not understood or could not be processed, connection is closed
+    /// afterwards unless request was understood
+    InvalidRequest {
+        error: String,
+    },
+    /// Client was too slow and some events were skipped
+    Lagged {
+        skipped: u64,
+    },
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiFarm<'a> {
+    farm_index: FarmIndex,
+    directory: &'a Path,
+    total_sectors: SectorIndex,
+    plotted_sectors: SectorIndex,
+    /// Reward address of the farm if it overrides global reward address
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reward_address: Option<Cow<'a, str>>,
+}
+
+impl<'a> ApiEvent<'a> {
+    fn from_notification(notification: &'a BackendNotification) -> Self {
+        match notification {
+            BackendNotification::Loading(step) => Self::Loading {
+                step: format!("{step:?}"),
+                progress: step.progress(),
+            },
+            BackendNotification::ConfigurationFound { raw_config } => {
+                Self::ConfigurationFound { raw_config }
+            }
+            BackendNotification::IncompatibleChain { compatible_chain } => {
+                Self::IncompatibleChain { compatible_chain }
+            }
+            BackendNotification::NotConfigured => Self::NotConfigured,
+            BackendNotification::ConfigurationIsInvalid { error } => Self::ConfigurationIsInvalid {
+                error: error.to_string(),
+            },
+            BackendNotification::ConfigSaveResult(result) => match result {
+                Ok(outcome) => Self::ConfigSaveResult {
+                    outcome: Some(*outcome),
+                    error: None,
+                },
+                Err(error) => Self::ConfigSaveResult {
+                    outcome: None,
+                    error: Some(error.to_string()),
+                },
+            },
+            BackendNotification::Running {
+                config,
+                raw_config,
+                best_block_number,
+                reward_address_balance,
+                initial_farm_states,
+                chain_info,
+                ..
+            } => Self::Running {
+                chain_name: &chain_info.chain_name,
+                token_symbol: &chain_info.token_symbol,
+                best_block_number: *best_block_number,
+                reward_address: raw_config.reward_address(),
+                reward_address_balance: reward_address_balance
+                    .map(|reward_address_balance| reward_address_balance.to_string()),
+                farms: config
+                    .farms
+                    .iter()
+                    .zip(initial_farm_states)
+                    .enumerate()
+                    .map(|(farm_index, (farm, initial_farm_state))| ApiFarm {
+                        farm_index: farm_index as FarmIndex,
+                        directory: &farm.directory,
+                        total_sectors: initial_farm_state.total_sectors_count,
+                        plotted_sectors: initial_farm_state.plotted_sectors_count,
+                        reward_address: raw_config
+                            .farms()
+                            .get(farm_index)
+                            .and_then(|farm| farm.reward_address.as_deref())
+                            .map(Cow::Borrowed),
+                    })
+                    .collect(),
+            },
+            BackendNotification::Node(node_notification) => match node_notification {
+                NodeNotification::SyncStateUpdate(sync_state) => Self::SyncStateUpdate {
+                    syncing: matches!(sync_state, SyncState::Syncing { .. }),
+                    synced: sync_state.is_synced(),
+                    target: match sync_state {
+                        SyncState::Syncing { target, .. } => Some(*target),
+                        SyncState::Unknown | SyncState::Idle => None,
+                    },
+                },
+                NodeNotification::ConnectedPeersUpdate(connected_peers) => {
+                    Self::ConnectedPeersUpdate {
+                        connected_peers: *connected_peers,
+                    }
+                }
+                NodeNotification::BlockImported(imported_block) => Self::BlockImported {
+                    block_number: imported_block.number,
+                    reward_address_balance: imported_block.reward_address_balance.to_string(),
+                },
+            },
+            BackendNotification::Farmer(farmer_notification) => {
+                Self::from_farmer_notification(farmer_notification)
+            }
+            BackendNotification::Stopped { error } => Self::Stopped {
+                error: error.as_ref().map(|error| error.to_string()),
+            },
+            BackendNotification::IrrecoverableError { error } => Self::IrrecoverableError {
+                error: error.to_string(),
+            },
+        }
+    }
+
+    fn from_farmer_notification(farmer_notification: &'a FarmerNotification<FarmIndex>) -> Self {
+        match farmer_notification {
+            FarmerNotification::SectorUpdate {
+                farm_index,
+                sector_index,
+                update,
+            } => match update {
+                SectorUpdate::Plotting(SectorPlottingDetails::Finished {
+                    old_plotted_sector,
+                    time,
+                    ..
+                }) => Self::SectorPlotted {
+                    farm_index: *farm_index,
+                    sector_index: *sector_index,
+                    replotted: old_plotted_sector.is_some(),
+                    time_ms: time.as_millis() as u64,
+                },
+                SectorUpdate::Plotting(SectorPlottingDetails::Error(error)) => {
+                    Self::SectorPlottingFailed {
+                        farm_index: *farm_index,
+                        sector_index: *sector_index,
+                        error,
+                    }
+                }
+                update => Self::SectorUpdate {
+                    farm_index: *farm_index,
+                    sector_index: *sector_index,
+                    update: format!("{update:?}"),
+                },
+            },
+            FarmerNotification::FarmingNotification {
+                farm_index,
+                notification,
+            } => match notification {
+                FarmingNotification::Proving(proving_details) => Self::Proving {
+                    farm_index: *farm_index,
+                    result: format!("{:?}", proving_details.result),
+                    time_ms: proving_details.time.as_millis() as u64,
+                },
+                notification => Self::FarmingNotification {
+                    farm_index: *farm_index,
+                    notification: format!("{notification:?}"),
+                },
+            },
+            FarmerNotification::FarmerCacheSyncProgress { progress } => {
+                Self::FarmerCacheSyncProgress {
+                    progress: *progress,
+                }
+            }
+            FarmerNotification::FarmError { farm_index, error } => Self::FarmError {
+                farm_index: *farm_index,
+                error: error.to_string(),
+            },
+            FarmerNotification::FarmAdded {
+                farm_index,
+                farm,
+                initial_farm_state,
+                plotting_paused,
+            } => Self::FarmAdded {
+                farm: ApiFarm {
+                    farm_index: *farm_index,
+                    directory: &farm.directory,
+                    total_sectors: initial_farm_state.total_sectors_count,
+                    plotted_sectors: initial_farm_state.plotted_sectors_count,
+                    reward_address: farm
+                        .reward_address
+                        .as_ref()
+                        .map(|reward_address| Cow::Owned(ss58_reward_address(reward_address))),
+                },
+                plotting_paused: *plotting_paused,
+            },
+            FarmerNotification::FarmRemoved { farm_index } => Self::FarmRemoved {
+                farm_index: *farm_index,
+            },
+            FarmerNotification::PlottingScheduleUpdate { paused_until } => {
+                Self::PlottingScheduleUpdate {
+                    paused_until: paused_until.map(|paused_until| paused_until.to_string()),
+                }
+            }
+            FarmerNotification::FarmScrub { farm_index, step } => {
+                let farm_index = *farm_index;
+                let step = match step {
+                    ScrubStep::StoppingFarm => "stoppingFarm",
+                    ScrubStep::Waiting => "waiting",
+                    ScrubStep::CheckingPlot => "checkingPlot",
+                    ScrubStep::CheckingCache => "checkingCache",
+                    ScrubStep::StartingFarm => "startingFarm",
+                    ScrubStep::Finished { summary } => {
+                        return Self::FarmScrubFinished {
+                            farm_index,
+                            dry_run: summary.dry_run,
+                            plot_issues: &summary.plot_issues,
+                            cache_issues: &summary.cache_issues,
+                        };
+                    }
+                    ScrubStep::Failed { error } => {
+                        return Self::FarmScrubFailed {
+                            farm_index,
+                            error: error.to_string(),
+                        };
+                    }
+                };
+
+                Self::FarmScrubProgress { farm_index, step }
+            }
+            FarmerNotification::FarmRestart { farm_index, step } => match step {
+                FarmRestartStep::Scheduled {
+                    error,
+                    attempt,
+                    max_attempts,
+                    delay,
+                } => Self::FarmRestartScheduled {
+                    farm_index: *farm_index,
+                    error: error.to_string(),
+                    attempt: *attempt,
+                    max_attempts: *max_attempts,
+                    delay_secs: delay.as_secs(),
+                },
+                FarmRestartStep::Restarting {
+                    attempt,
+                    max_attempts,
+                } => Self::FarmRestarting {
+                    farm_index: *farm_index,
+                    attempt: *attempt,
+                    max_attempts: *max_attempts,
+                },
+            },
+            FarmerNotification::FarmResize {
+                farm_index,
+                farm_resize,
+                progress,
+            } => Self::FarmResizing {
+                farm_index: *farm_index,
+                old_allocated_space: farm_resize.old_allocated_space,
+                new_allocated_space: farm_resize.new_allocated_space,
+                progress: *progress,
+            },
+        }
+    }
+
+    /// Whether event describes overall application status, the latest such event is sent to newly
+    /// connected clients so they don't need to wait for the next change
+    fn is_status(&self) -> bool {
+        matches!(
+            self,
+            Self::Loading { .. }
+                | Self::IncompatibleChain { .. }
+                | Self::NotConfigured
+                | Self::ConfigurationIsInvalid { .. }
+                | Self::Running { .. }
+                | Self::Stopped { .. }
+                | Self::IrrecoverableError { .. }
+        )
+    }
+
+    fn to_json_line(&self) -> String {
+        let mut line = serde_json::to_string(self).expect("Event serialization never fails; qed");
+        line.push('\n');
+        line
+    }
+}
+
+/// The first line client must send before anything else
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ApiAuthentication {
+    token: String,
+}
+
+/// Requests accepted from API clients
+#[derive(Debug, Deserialize)]
+#[serde(
+    tag = "action",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+enum ApiRequest {
+    /// Pause (or resume) plotting
+    PausePlotting { pause: bool },
+    /// Pause (or resume) plotting of a single farm
+    PauseFarmPlotting { farm_index: FarmIndex, pause: bool },
+    /// Check farm integrity and repair it unless `dry_run` is `true`, farm is stopped meanwhile
+    ScrubFarm {
+        farm_index: FarmIndex,
+        dry_run: bool,
+    },
+    /// Save new configuration and apply it if possible, same as saving configuration in GUI
+    NewConfig { raw_config: RawConfig },
+}
+
+impl From<ApiRequest> for BackendAction {
+    fn from(request: ApiRequest) -> Self {
+        match request {
+            ApiRequest::PausePlotting { pause } => {
+                BackendAction::Farmer(FarmerAction::PausePlotting(pause))
+            }
+            ApiRequest::PauseFarmPlotting { farm_index, pause } => {
+                BackendAction::Farmer(FarmerAction::PauseFarmPlotting {
+                    farm_index,
+                    paused: pause,
+                })
+            }
+            ApiRequest::ScrubFarm {
+                farm_index,
+                dry_run,
+            } => BackendAction::Farmer(FarmerAction::ScrubFarm {
+                farm_index,
+                dry_run,
+            }),
+            ApiRequest::NewConfig { raw_config } => BackendAction::NewConfig {
+                raw_config: raw_config.upgrade(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ApiState {
+    events_sender: broadcast::Sender<Arc<str>>,
+    last_status: Arc<Mutex<Option<Arc<str>>>>,
+    backend_action_sender: mpsc::Sender<BackendAction>,
+    token: Arc<str>,
+}
+
+/// Starts local control API on specified address if provided.
+///
+/// Returns receiver that the rest of the application should use for backend notifications instead
+/// of the original one, API server stops once backend notifications stop or returned receiver is
+/// dropped. Failure to start API is logged, but is not fatal for the application.
+pub(super) fn maybe_start(
+    api_address: Option<SocketAddr>,
+    backend_action_sender: &mpsc::Sender<BackendAction>,
+    backend_notification_receiver: mpsc::Receiver<BackendNotification>,
+) -> mpsc::Receiver<BackendNotification> {
+    let Some(api_address) = api_address else {
+        return backend_notification_receiver;
+    };
+
+    let listener = match net::TcpListener::bind(api_address)
+        .and_then(|listener| listener.set_nonblocking(true).map(|()| listener))
+    {
+        Ok(listener) => listener,
+        Err(error) => {
+            error!(%error, %api_address, "Failed to start local control API");
+            return backend_notification_receiver;
+        }
+    };
+
+    info!(%api_address, "Local control API started");
+
+    let (notification_sender, notification_receiver) = mpsc::channel(100);
+
+    tokio::spawn(run(
+        listener,
+        backend_action_sender.clone(),
+        backend_notification_receiver,
+        notification_sender,
+    ));
+
+    notification_receiver
+}
+
+async fn run(
+    listener: net::TcpListener,
+    backend_action_sender: mpsc::Sender<BackendAction>,
+    mut backend_notification_receiver: mpsc::Receiver<BackendNotification>,
+    mut notification_sender: mpsc::Sender<BackendNotification>,
+) {
+    let result: anyhow::Result<(TcpListener, Arc<str>)> = try {
+        let listener = TcpListener::from_std(listener)?;
+        let token = read_or_create_token().await?;
+
+        (listener, token)
+    };
+    let (listener, token) = match result {
+        Ok(result) => result,
+        Err(error) => {
+            error!(%error, "Failed to start local control API");
+            // Still need to forward notifications to the rest of the application
+            while let Some(notification) = backend_notification_receiver.next().await {
+                if notification_sender.send(notification).await.is_err() {
+                    break;
+                }
+            }
+            return;
+        }
+    };
+
+    let (events_sender, _) = broadcast::channel(EVENTS_BUFFER);
+    let state = ApiState {
+        events_sender,
+        last_status: Arc::default(),
+        backend_action_sender,
+        token,
+    };
+
+    let forward_notifications_fut = {
+        let state = state.clone();
+
+        async move {
+            while let Some(notification) = backend_notification_receiver.next().await {
+                let event = ApiEvent::from_notification(&notification);
+                let line = Arc::<str>::from(event.to_json_line());
+                if event.is_status() {
+                    state
+                        .last_status
+                        .lock()
+                        .expect("Must not panic, crash if it does; qed")
+                        .replace(Arc::clone(&line));
+                }
+                // No subscribers is not an error
+                let _ = state.events_sender.send(line);
+
+                if notification_sender.send(notification).await.is_err() {
+                    break;
+                }
+            }
+        }
+    };
+
+    let accept_connections_fut = async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, address)) => {
+                    debug!(%address, "Local control API client connected");
+                    tokio::spawn(process_client(stream, state.clone()));
+                }
+                Err(error) => {
+                    warn!(%error, "Failed to accept local control API connection");
+                }
+            }
+        }
+    };
+
+    select! {
+        _ = pin!(forward_notifications_fut).fuse() => {},
+        _ = pin!(accept_connections_fut).fuse() => {},
+    }
+
+    debug!("Local control API stopped");
+}
+
+async fn process_client(stream: TcpStream, mut state: ApiState) {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    let result: io::Result<()> = try {
+        let Some(line) = lines.next_line().await? else {
+            return;
+        };
+        // Browsers can send requests to loopback addresses from any web page
+        if is_http_request(&line) {
+            debug!("Local control API received HTTP request, closing connection");
+            return;
+        }
+        let authenticated = serde_json::from_str::<ApiAuthentication>(&line)
+            .is_ok_and(|authentication| is_token_valid(&authentication.token, &state.token));
+        if !authenticated {
+            debug!("Local control API client failed to authenticate, closing connection");
+            let response = ApiEvent::InvalidRequest {
+                error: "Invalid token".to_string(),
+            };
+            writer.write_all(response.to_json_line().as_bytes()).await?;
+            return;
+        }
+
+        let mut events_receiver = state.events_sender.subscribe();
+        let maybe_last_status = state
+            .last_status
+            .lock()
+            .expect("Must not panic, crash if it does; qed")
+            .clone();
+        if let Some(last_status) = maybe_last_status {
+            writer.write_all(last_status.as_bytes()).await?;
+        }
+
+        loop {
+            select! {
+                maybe_event = events_receiver.recv().fuse() => {
+                    match maybe_event {
+                        Ok(event) => {
+                            writer.write_all(event.as_bytes()).await?;
+                        }
+                        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                            writer
+                                .write_all(ApiEvent::Lagged { skipped }.to_json_line().as_bytes())
+                                .await?;
+                        }
+                        Err(broadcast::error::RecvError::Closed) => {
+                            break;
+                        }
+                    }
+                }
+                maybe_line = lines.next_line().fuse() => {
+                    let Some(line) = maybe_line? else {
+                        break;
+                    };
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+
+                    let request = match serde_json::from_str::<ApiRequest>(&line) {
+                        Ok(request) => request,
+                        Err(error) => {
+                            let response = ApiEvent::InvalidRequest {
+                                error: error.to_string(),
+                            };
+                            writer.write_all(response.to_json_line().as_bytes()).await?;
+                            break;
+                        }
+                    };
+                    debug!(?request, "Local control API request");
+
+                    let response = match state.backend_action_sender.send(request.into()).await {
+                        Ok(()) => ApiEvent::ActionAccepted,
+                        Err(_error) => ApiEvent::InvalidRequest {
+                            error: "Backend is not running".to_string(),
+                        },
+                    };
+                    writer.write_all(response.to_json_line().as_bytes()).await?;
+                }
+            }
+        }
+    };
+
+    if let Err(error) = result {
+        debug!(%error, "Local control API client disconnected with error");
+    } else {
+        debug!("Local control API client disconnected");
+    }
+}
+
+/// Read token clients must authenticate with, new random token is generated if there is none yet
+async fn read_or_create_token() -> anyhow::Result<Arc<str>> {
+    let token_file_path = RawConfig::default_path()
+        .await?
+        .with_file_name(API_TOKEN_FILE);
+
+    match tokio::fs::read_to_string(&token_file_path).await {
+        Ok(token) => {
+            let token = token.trim();
+            if token.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "Token file \"{}\" is empty",
+                    token_file_path.display()
+                ));
+            }
+
+            return Ok(Arc::from(token));
+        }
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            // Generate below
+        }
+        Err(error) => {
+            return Err(anyhow::anyhow!(
+                "Failed to read token from \"{}\": {error}",
+                token_file_path.display()
+            ));
+        }
+    }
+
+    let token = hex::encode(rand::random::<[u8; API_TOKEN_SIZE]>());
+    let mut options = tokio::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    // Token gives full control over the application
+    #[cfg(unix)]
+    options.mode(0o600);
+    let result: io::Result<()> = try {
+        options
+            .open(&token_file_path)
+            .await?
+            .write_all(token.as_bytes())
+            .await?;
+    };
+    result.map_err(|error| {
+        anyhow::anyhow!(
+            "Failed to write token to \"{}\": {error}",
+            token_file_path.display()
+        )
+    })?;
+
+    info!(path = %token_file_path.display(), "Generated local control API token");
+
+    Ok(Arc::from(token))
+}
+
+fn is_http_request(line: &str) -> bool {
+    HTTP_METHODS.iter().any(|method| line.starts_with(method)) || line.contains(" HTTP/")
+}
+
+/// Compare in constant time, such that token can't be guessed byte by byte
+fn is_token_valid(token: &str, expected_token: &str) -> bool {
+    token.len() == expected_token.len()
+        && token
+            .bytes()
+            .zip(expected_token.bytes())
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+/// Encodes reward address with SS58 format of the chain application is running on
+fn ss58_reward_address(reward_address: &PublicKey) -> String {
+    let public_key: [u8; 32] = **reward_address;
+    AccountId32::new(public_key).to_ss58check()
+}
@@ -1,30 +1,43 @@
 // TODO: Make these modules private
 pub mod config;
+mod external_node;
 pub mod farmer;
+pub mod history;
+mod metrics;
 mod networking;
 pub mod node;
-mod utils;
+pub mod selective_wipe;
+pub mod utils;
 
-use crate::backend::config::{Config, ConfigError, RawConfig};
+use crate::backend::config::{
+    Config, ConfigError, NetworkConfiguration, NodeConfiguration, PlottingCpuConfiguration,
+    PlottingSchedule, RawConfig, RpcConfiguration, chain_data_path,
+};
+use crate::backend::external_node::ExternalNode;
 use crate::backend::farmer::maybe_node_client::MaybeNodeClient;
 use crate::backend::farmer::{
-    CACHE_PERCENTAGE, DiskFarm, Farmer, FarmerAction, FarmerNotification, FarmerOptions,
-    InitialFarmState,
+    DiskFarm, FarmResize, Farmer, FarmerAction, FarmerNotification, FarmerOptions, InitialFarmState,
 };
+use crate::backend::history::FarmingHistory;
+use crate::backend::metrics::{AppMetrics, MetricsRegistries, RegistryGuard};
 use crate::backend::networking::{NetworkOptions, create_network};
 use crate::backend::node::{
     BlockImportedNotification, ChainInfo, ChainSpec, ConsensusNode, ConsensusNodeCreationError,
     GENESIS_HASH, SyncState, dsn_bootstrap_nodes,
 };
+use crate::backend::selective_wipe::{WipePlan, wipe_farm, wipe_path};
+use crate::backend::utils::HandlerFn;
 use async_lock::{RwLock as AsyncRwLock, Semaphore};
 use backoff::ExponentialBackoff;
 use future::FutureExt;
-use futures::channel::mpsc;
+use futures::channel::{mpsc, oneshot};
 use futures::{SinkExt, StreamExt, future, select};
+use prometheus_client::registry::Registry;
 use sc_subspace_chain_specs::MAINNET_CHAIN_SPEC;
+use serde::Serialize;
 use sp_consensus_subspace::ChainConstants;
-use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
-use std::num::NonZeroU8;
+use std::collections::BTreeSet;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::path::{Path, PathBuf};
 use std::pin::pin;
 use std::sync::{Arc, Weak};
@@ -48,7 +61,7 @@ use tokio::fs;
 use tokio::fs::OpenOptions;
 use tokio::io::AsyncWriteExt;
 use tokio::runtime::Handle;
-use tracing::{Instrument, error, info_span, warn};
+use tracing::{Instrument, error, info, info_span, warn};
 
 pub type FarmIndex = u8;
 
@@ -85,6 +98,11 @@ pub enum LoadingStep {
     NetworkingStackCreatedSuccessfully,
     CreatingConsensusNode,
     ConsensusNodeCreatedSuccessfully,
+    /// Farmer-only mode, embedded node is not created
+    ConnectingToExternalNode {
+        url: String,
+    },
+    ConnectedToExternalNode,
     InitializingFarms {
         farms_total: u16,
     },
@@ -92,6 +110,14 @@ pub enum LoadingStep {
         farm_index: FarmIndex,
         farms_total: u16,
     },
+    /// Existing farm is being resized, which might take a while
+    ResizingFarm {
+        farm_index: FarmIndex,
+        farms_total: u16,
+        farm_resize: FarmResize,
+        /// Estimated progress so far in %
+        progress: f32,
+    },
     FarmerCreatedSuccessfully,
     WipingFarm {
         farm_index: FarmIndex,
@@ -103,6 +129,10 @@ pub enum LoadingStep {
         path: PathBuf,
     },
     WipedNodeSuccessfully,
+    WipingScheduledData {
+        path: PathBuf,
+    },
+    WipedScheduledDataSuccessfully,
 }
 
 impl LoadingStep {
@@ -127,11 +157,19 @@ impl LoadingStep {
             LoadingStep::NetworkingStackCreatedSuccessfully => 20.0,
             LoadingStep::CreatingConsensusNode => 20.0,
             LoadingStep::ConsensusNodeCreatedSuccessfully => 40.0,
+            LoadingStep::ConnectingToExternalNode { .. } => 20.0,
+            LoadingStep::ConnectedToExternalNode => 40.0,
             LoadingStep::InitializingFarms { .. } => 40.0,
             LoadingStep::FarmInitialized {
                 farm_index,
                 farms_total,
             } => 40.0 + 60.0 / *farms_total as f32 * (f32::from(*farm_index) + 1.0),
+            LoadingStep::ResizingFarm {
+                farm_index,
+                farms_total,
+                progress,
+                ..
+            } => 40.0 + 60.0 / *farms_total as f32 * (f32::from(*farm_index) + *progress / 100.0),
             LoadingStep::FarmerCreatedSuccessfully => 100.0,
             LoadingStep::WipingFarm {
                 farm_index,
@@ -141,6 +179,8 @@ impl LoadingStep {
             LoadingStep::WipedFarmsSuccessfully => 50.0,
             LoadingStep::WipingNode { .. } => 80.0,
             LoadingStep::WipedNodeSuccessfully => 100.0,
+            LoadingStep::WipingScheduledData { .. } => 2.0,
+            LoadingStep::WipedScheduledDataSuccessfully => 2.0,
         }
     }
 }
@@ -152,6 +192,26 @@ enum LoadedConsensusChainNode {
     Incompatible { compatible_chain: String },
 }
 
+/// Node farms are connected to
+#[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
+enum ConnectedNode {
+    /// Node embedded into the application
+    Embedded(ConsensusNode),
+    /// External node connected to over farmer RPC
+    External(ExternalNode),
+}
+
+/// What happened to configuration after it was saved
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConfigSaveOutcome {
+    /// Changes were applied to the running application
+    Applied,
+    /// Application restart is needed for changes to take effect
+    RestartRequired,
+}
+
 #[derive(Debug, Clone)]
 pub enum NodeNotification {
     SyncStateUpdate(SyncState),
@@ -175,16 +235,23 @@ pub enum BackendNotification {
     ConfigurationIsInvalid {
         error: ConfigError,
     },
-    ConfigSaveResult(anyhow::Result<()>),
+    ConfigSaveResult(anyhow::Result<ConfigSaveOutcome>),
     Running {
         config: Config,
         raw_config: RawConfig,
-        best_block_number: BlockNumber,
-        reward_address_balance: Balance,
+        /// `None` when connected to external node, farmer RPC doesn't expose it
+        best_block_number: Option<BlockNumber>,
+        /// `None` when connected to external node, farmer RPC doesn't expose balances
+        reward_address_balance: Option<Balance>,
+        /// Balances of reward addresses of individual farms that differ from global reward address,
+        /// `None` when connected to external node
+        farm_reward_address_balances: Option<Vec<(PublicKey, Balance)>>,
         initial_farm_states: Vec<InitialFarmState>,
-        cache_percentage: NonZeroU8,
         chain_info: ChainInfo,
-        chain_constants: ChainConstants,
+        /// `None` when connected to external node, farmer RPC doesn't expose chain constants
+        chain_constants: Option<ChainConstants>,
+        /// Farming history that is recorded by backend
+        history: FarmingHistory,
     },
     Node(NodeNotification),
     Farmer(FarmerNotification<FarmIndex>),
@@ -204,16 +271,25 @@ pub enum BackendAction {
     /// Config was created or updated
     NewConfig { raw_config: RawConfig },
     /// Farmer action
-    Farmer(FarmerAction),
+    Farmer(FarmerAction<FarmIndex>),
+}
+
+struct BackendMetrics {
+    listen_on: SocketAddr,
+    registries: MetricsRegistries,
+    app_metrics: AppMetrics,
+    _registry_guard: RegistryGuard,
 }
 
 struct LoadedBackend {
     config: Config,
     raw_config: RawConfig,
     config_file_path: PathBuf,
-    consensus_node: ConsensusNode,
+    genesis_hash: String,
+    connected_node: ConnectedNode,
     farmer: Farmer<FarmIndex>,
     node_runner: NodeRunner,
+    metrics: Option<BackendMetrics>,
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -224,9 +300,11 @@ enum BackendLoadingResult {
 
 // NOTE: this is an async function, but it might do blocking operations and should be running on a
 // dedicated CPU core
+/// Farming history is persisted in `app_data_dir` if provided.
 pub async fn create(
     mut backend_action_receiver: mpsc::Receiver<BackendAction>,
     mut notifications_sender: mpsc::Sender<BackendNotification>,
+    app_data_dir: Option<PathBuf>,
 ) {
     let loading_result = try {
         'load: loop {
@@ -308,6 +386,7 @@ pub async fn create(
 
     let run_fut = run(
         loaded_backend,
+        app_data_dir.as_deref(),
         &mut backend_action_receiver,
         &mut notifications_sender,
     );
@@ -323,20 +402,32 @@ pub async fn create(
 async fn load(
     notifications_sender: &mut mpsc::Sender<BackendNotification>,
 ) -> anyhow::Result<Option<BackendLoadingResult>> {
-    let (config_file_path, Some(raw_config)) = load_configuration(notifications_sender).await?
+    let (config_file_path, Some(mut raw_config)) = load_configuration(notifications_sender).await?
     else {
         return Ok(None);
     };
 
-    let Some(config) = check_configuration(&raw_config, notifications_sender).await? else {
+    wipe_scheduled_data(&config_file_path, notifications_sender).await?;
+
+    let Some(mut config) = check_configuration(&raw_config, notifications_sender).await? else {
         return Ok(None);
     };
 
-    let chain_spec = load_chain_specification(notifications_sender).await?;
+    // Only valid configuration is persisted, such that invalid one is not rewritten on disk
+    persist_node_name(&config_file_path, &mut raw_config, &mut config).await?;
+
+    let chain_spec =
+        load_chain_specification(config.chain.chain_spec.as_deref(), notifications_sender).await?;
+    let genesis_hash = chain_genesis_hash(&config, &chain_spec)?;
 
     preparing_node_path(&config.node_path, notifications_sender).await?;
 
     let plotted_pieces = Arc::new(AsyncRwLock::new(PlottedPieces::default()));
+    let mut prometheus_registry = config
+        .metrics
+        .prometheus_listen_on
+        .is_some()
+        .then(Registry::default);
 
     let (
         maybe_node_client,
@@ -348,9 +439,10 @@ async fn load(
         out_connections,
     ) = create_networking_stack(
         &config,
-        GENESIS_HASH.to_string(),
+        genesis_hash.clone(),
         &chain_spec,
         Arc::downgrade(&plotted_pieces),
+        prometheus_registry.as_mut(),
         notifications_sender,
     )
     .await?;
@@ -382,25 +474,48 @@ async fn load(
         },
     );
 
-    let create_consensus_node_fut = create_consensus_node(
-        &network_keypair,
-        config.node_path.clone(),
-        config.network.substrate_port,
-        chain_spec,
-        Arc::new(piece_getter.clone()),
-        node.clone(),
-        &maybe_node_client,
-        notifications_sender,
-    );
-    let consensus_node = match create_consensus_node_fut.await? {
-        LoadedConsensusChainNode::Compatible(consensus_node) => consensus_node,
-        LoadedConsensusChainNode::Incompatible { compatible_chain } => {
-            return Ok(Some(BackendLoadingResult::IncompatibleChain {
-                compatible_chain,
-            }));
+    let connected_node = if let Some(url) = &config.node.external_rpc_url {
+        let external_node = connect_to_external_node(
+            url,
+            &chain_spec,
+            &genesis_hash,
+            &maybe_node_client,
+            notifications_sender,
+        )
+        .await?;
+
+        ConnectedNode::External(external_node)
+    } else {
+        let create_consensus_node_fut = create_consensus_node(
+            &network_keypair,
+            config.node_path.clone(),
+            &config.network,
+            &config.rpc,
+            &config.node,
+            chain_spec,
+            &genesis_hash,
+            Arc::new(piece_getter.clone()),
+            node.clone(),
+            &maybe_node_client,
+            prometheus_registry.as_mut(),
+            notifications_sender,
+        );
+        match create_consensus_node_fut.await? {
+            LoadedConsensusChainNode::Compatible(consensus_node) => {
+                ConnectedNode::Embedded(consensus_node)
+            }
+            LoadedConsensusChainNode::Incompatible { compatible_chain } => {
+                return Ok(Some(BackendLoadingResult::IncompatibleChain {
+                    compatible_chain,
+                }));
+            }
         }
     };
 
+    let metrics_registries = prometheus_registry
+        .is_some()
+        .then(MetricsRegistries::default);
+
     let farmer = create_farmer(
         config.reward_address,
         config.farms.clone(),
@@ -410,23 +525,48 @@ async fn load(
         maybe_node_client,
         kzg,
         config.reduce_plotting_cpu_load,
+        config.plotting_cpu.clone(),
+        config.plotting_schedule.clone(),
         piece_getter,
+        metrics_registries.clone(),
         notifications_sender,
     )
     .await?;
 
+    let metrics = config
+        .metrics
+        .prometheus_listen_on
+        .zip(prometheus_registry)
+        .zip(metrics_registries)
+        .map(|((listen_on, mut registry), registries)| {
+            let app_metrics = AppMetrics::new(
+                &mut registry,
+                matches!(connected_node, ConnectedNode::External(_)),
+            );
+
+            BackendMetrics {
+                listen_on,
+                _registry_guard: registries.add(registry),
+                registries,
+                app_metrics,
+            }
+        });
+
     Ok(Some(BackendLoadingResult::Success(LoadedBackend {
         config,
         raw_config,
         config_file_path,
-        consensus_node,
+        genesis_hash,
+        connected_node,
         farmer,
         node_runner,
+        metrics,
     })))
 }
 
 async fn run(
     loaded_backend: LoadedBackend,
+    app_data_dir: Option<&Path>,
     backend_action_receiver: &mut mpsc::Receiver<BackendAction>,
     notifications_sender: &mut mpsc::Sender<BackendNotification>,
 ) -> anyhow::Result<()> {
@@ -434,9 +574,11 @@ async fn run(
         config,
         raw_config,
         config_file_path,
-        consensus_node,
+        genesis_hash,
+        connected_node,
         farmer,
         mut node_runner,
+        metrics,
     } = loaded_backend;
     let networking_fut = run_future_in_dedicated_thread(
         {
@@ -449,25 +591,79 @@ async fn run(
     )?;
 
     let reward_address = config.reward_address;
+    let farm_reward_addresses = config.farm_reward_addresses();
+    let running_raw_config = raw_config.clone();
+    let app_metrics = metrics.as_ref().map(|metrics| metrics.app_metrics.clone());
+    let (
+        best_block_number,
+        reward_address_balance,
+        farm_reward_address_balances,
+        chain_info,
+        chain_constants,
+    ) = match &connected_node {
+        ConnectedNode::Embedded(consensus_node) => (
+            Some(consensus_node.best_block_number()),
+            Some(consensus_node.account_balance(&reward_address)),
+            Some(
+                farm_reward_addresses
+                    .iter()
+                    .map(|farm_reward_address| {
+                        (
+                            *farm_reward_address,
+                            consensus_node.account_balance(farm_reward_address),
+                        )
+                    })
+                    .collect(),
+            ),
+            consensus_node.chain_info().clone(),
+            Some(*consensus_node.chain_constants()),
+        ),
+        // Not exposed by farmer RPC
+        ConnectedNode::External(external_node) => {
+            (None, None, None, external_node.chain_info().clone(), None)
+        }
+    };
+    if let Some(app_metrics) = &app_metrics {
+        app_metrics.initialize(
+            best_block_number,
+            reward_address_balance,
+            farmer.initial_farm_states(),
+        );
+    }
+    let history = FarmingHistory::load(
+        app_data_dir,
+        genesis_hash,
+        &config.farms,
+        reward_address_balance,
+        farm_reward_address_balances.as_deref().unwrap_or_default(),
+    );
     notifications_sender
         .send(BackendNotification::Running {
             config,
             raw_config,
-            best_block_number: consensus_node.best_block_number(),
-            reward_address_balance: consensus_node.account_balance(&reward_address),
+            best_block_number,
+            reward_address_balance,
+            farm_reward_address_balances,
             initial_farm_states: farmer.initial_farm_states().to_vec(),
-            cache_percentage: CACHE_PERCENTAGE,
-            chain_info: consensus_node.chain_info().clone(),
-            chain_constants: *consensus_node.chain_constants(),
+            chain_info,
+            chain_constants,
+            history: history.clone(),
         })
         .await?;
 
-    let _on_sync_state_change_handler_id = consensus_node.on_sync_state_change({
+    let on_sync_state_change: HandlerFn<SyncState> = {
         let notifications_sender = notifications_sender.clone();
+        let app_metrics = app_metrics.clone();
+        let history = history.clone();
 
         Arc::new(move |&sync_state| {
             let notification = NodeNotification::SyncStateUpdate(sync_state);
 
+            if let Some(app_metrics) = &app_metrics {
+                app_metrics.on_node_notification(&notification);
+            }
+            history.on_node_notification(&notification);
+
             let mut notifications_sender = notifications_sender.clone();
 
             if let Err(error) = notifications_sender
@@ -481,51 +677,80 @@ async fn run(
                 warn!(%error, "Failed to send sync state backend notification");
             }
         })
-    });
-    let _on_connected_peers_change_handler_id = consensus_node.on_connected_peers_change({
-        let notifications_sender = notifications_sender.clone();
-
-        Arc::new(move |&connected_peers| {
-            let notification = NodeNotification::ConnectedPeersUpdate(connected_peers);
+    };
+    let _on_node_notification_handler_ids = match &connected_node {
+        ConnectedNode::Embedded(consensus_node) => vec![
+            consensus_node.on_sync_state_change(on_sync_state_change),
+            consensus_node.on_connected_peers_change({
+                let notifications_sender = notifications_sender.clone();
+                let app_metrics = app_metrics.clone();
+
+                Arc::new(move |&connected_peers| {
+                    let notification = NodeNotification::ConnectedPeersUpdate(connected_peers);
+
+                    if let Some(app_metrics) = &app_metrics {
+                        app_metrics.on_node_notification(&notification);
+                    }
 
-            let mut notifications_sender = notifications_sender.clone();
+                    let mut notifications_sender = notifications_sender.clone();
 
-            if let Err(error) = notifications_sender
-                .try_send(BackendNotification::Node(notification))
-                .or_else(|error| {
-                    tokio::task::block_in_place(|| {
-                        Handle::current().block_on(notifications_sender.send(error.into_inner()))
-                    })
+                    if let Err(error) = notifications_sender
+                        .try_send(BackendNotification::Node(notification))
+                        .or_else(|error| {
+                            tokio::task::block_in_place(|| {
+                                Handle::current()
+                                    .block_on(notifications_sender.send(error.into_inner()))
+                            })
+                        })
+                    {
+                        warn!(%error, "Failed to send sync state backend notification");
+                    }
                 })
-            {
-                warn!(%error, "Failed to send sync state backend notification");
-            }
-        })
-    });
-    let _on_imported_block_handler_id = consensus_node.on_block_imported({
-        let notifications_sender = notifications_sender.clone();
+            }),
+            consensus_node.on_block_imported({
+                let notifications_sender = notifications_sender.clone();
+                let app_metrics = app_metrics.clone();
+                let history = history.clone();
 
-        Arc::new(move |&block_imported| {
-            let notification = NodeNotification::BlockImported(block_imported);
+                Arc::new(move |block_imported| {
+                    let notification = NodeNotification::BlockImported(block_imported.clone());
 
-            let mut notifications_sender = notifications_sender.clone();
+                    if let Some(app_metrics) = &app_metrics {
+                        app_metrics.on_node_notification(&notification);
+                    }
+                    history.on_node_notification(&notification);
 
-            if let Err(error) = notifications_sender
-                .try_send(BackendNotification::Node(notification))
-                .or_else(|error| {
-                    tokio::task::block_in_place(|| {
-                        Handle::current().block_on(notifications_sender.send(error.into_inner()))
-                    })
+                    let mut notifications_sender = notifications_sender.clone();
+
+                    if let Err(error) = notifications_sender
+                        .try_send(BackendNotification::Node(notification))
+                        .or_else(|error| {
+                            tokio::task::block_in_place(|| {
+                                Handle::current()
+                                    .block_on(notifications_sender.send(error.into_inner()))
+                            })
+                        })
+                    {
+                        warn!(%error, "Failed to send imported block backend notification");
+                    }
                 })
-            {
-                warn!(%error, "Failed to send imported block backend notification");
-            }
-        })
-    });
+            }),
+        ],
+        ConnectedNode::External(external_node) => {
+            vec![external_node.on_sync_state_change(on_sync_state_change)]
+        }
+    };
     let _on_farmer_notification_handler_id = farmer.on_notification({
         let notifications_sender = notifications_sender.clone();
+        let app_metrics = app_metrics.clone();
+        let history = history.clone();
 
         Arc::new(move |notification| {
+            if let Some(app_metrics) = &app_metrics {
+                app_metrics.on_farmer_notification(notification);
+            }
+            history.on_farmer_notification(notification);
+
             let mut notifications_sender = notifications_sender.clone();
 
             if let Err(error) = notifications_sender
@@ -543,10 +768,44 @@ async fn run(
 
     let mut farmer_action_sender = farmer.action_sender();
 
+    let metrics_fut = {
+        let maybe_metrics_endpoint = metrics
+            .as_ref()
+            .map(|metrics| (metrics.listen_on, metrics.registries.clone()));
+        let substrate_registry = match &connected_node {
+            ConnectedNode::Embedded(consensus_node) => {
+                consensus_node.substrate_prometheus_registry().cloned()
+            }
+            ConnectedNode::External(_external_node) => None,
+        };
+
+        async move {
+            if let Some((listen_on, registries)) = maybe_metrics_endpoint
+                && let Err(error) = metrics::serve(listen_on, registries, substrate_registry).await
+            {
+                error!(%error, %listen_on, "Prometheus metrics endpoint failed");
+            }
+
+            // Failure to serve metrics is not fatal for the rest of the application
+            future::pending::<()>().await
+        }
+    };
+
     // Order is important here, we want to destroy dependents first and only then corresponding
     // dependencies to avoid unnecessary errors and warnings in logs
     let networking_fut = networking_fut;
-    let consensus_node_fut = consensus_node.run(&reward_address);
+    let node_fut = async move {
+        match connected_node {
+            ConnectedNode::Embedded(consensus_node) => consensus_node
+                .run(&reward_address, &farm_reward_addresses)
+                .await
+                .map_err(|error| anyhow::anyhow!("Consensus node exited: {error}")),
+            ConnectedNode::External(external_node) => external_node
+                .run()
+                .await
+                .map_err(|error| anyhow::anyhow!("External node exited: {error}")),
+        }
+    };
     let farmer_fut = farmer.run();
     let process_backend_actions_fut = {
         let mut notifications_sender = notifications_sender.clone();
@@ -554,6 +813,7 @@ async fn run(
         async move {
             process_backend_actions(
                 &config_file_path,
+                &running_raw_config,
                 backend_action_receiver,
                 &mut farmer_action_sender,
                 &mut notifications_sender,
@@ -563,16 +823,18 @@ async fn run(
     };
 
     let networking_fut = pin!(networking_fut);
-    let consensus_node_fut = pin!(consensus_node_fut);
+    let node_fut = pin!(node_fut);
     let farmer_fut = pin!(farmer_fut);
     let process_backend_actions_fut = pin!(process_backend_actions_fut);
+    let metrics_fut = pin!(metrics_fut);
+    let history_fut = pin!(history.run_saving());
 
     let result: anyhow::Result<()> = select! {
         result = networking_fut.fuse() => {
             result.map_err(|error| anyhow::anyhow!("Networking exited: {error}"))
         }
-        result = consensus_node_fut.fuse() => {
-            result.map_err(|error| anyhow::anyhow!("Consensus node exited: {error}"))
+        result = node_fut.fuse() => {
+            result
         }
         result = farmer_fut.fuse() => {
             result.map_err(|error| anyhow::anyhow!("Farm exited: {error}"))
@@ -580,8 +842,16 @@ async fn run(
         _ = process_backend_actions_fut.fuse() => {
             Ok(())
         }
+        _ = metrics_fut.fuse() => {
+            unreachable!("Metrics future never exits; qed");
+        }
+        _ = history_fut.fuse() => {
+            unreachable!("History saving future never exits; qed");
+        }
     };
 
+    history.save().await;
+
     notifications_sender
         .send(BackendNotification::Stopped {
             error: result.err(),
@@ -621,6 +891,92 @@ async fn load_configuration(
     Ok((config_file_path, maybe_raw_config))
 }
 
+/// Wipe data user has scheduled for wiping before anything is opened
+async fn wipe_scheduled_data(
+    config_file_path: &Path,
+    notifications_sender: &mut mpsc::Sender<BackendNotification>,
+) -> anyhow::Result<()> {
+    let Some(wipe_plan) = WipePlan::take_scheduled(config_file_path).await? else {
+        return Ok(());
+    };
+
+    info!(target = ?wipe_plan.target, "Wiping scheduled data");
+
+    if let Some(farm_directory) = &wipe_plan.farm_directory {
+        notifications_sender
+            .send(BackendNotification::Loading(
+                LoadingStep::WipingScheduledData {
+                    path: farm_directory.clone(),
+                },
+            ))
+            .await?;
+
+        wipe_farm(farm_directory, &wipe_plan.paths)
+            .await
+            .map_err(|error| {
+                anyhow::anyhow!(
+                    "Failed to wipe farm at \"{}\": {error}",
+                    farm_directory.display()
+                )
+            })?;
+    } else {
+        for path in &wipe_plan.paths {
+            notifications_sender
+                .send(BackendNotification::Loading(
+                    LoadingStep::WipingScheduledData { path: path.clone() },
+                ))
+                .await?;
+
+            wipe_path(path).await.map_err(|error| {
+                anyhow::anyhow!("Failed to wipe \"{}\": {error}", path.display())
+            })?;
+        }
+    }
+
+    notifications_sender
+        .send(BackendNotification::Loading(
+            LoadingStep::WipedScheduledDataSuccessfully,
+        ))
+        .await?;
+
+    Ok(())
+}
+
+/// Generate node name and store it in configuration if there isn't one yet, such that node shows
+/// up under the same name in telemetry after restarts
+async fn persist_node_name(
+    config_file_path: &Path,
+    raw_config: &mut RawConfig,
+    config: &mut Config,
+) -> anyhow::Result<()> {
+    let RawConfig::V1 { node, .. } = raw_config else {
+        // Config is upgraded to the latest version when read, legacy version has nowhere to store
+        // node name, so a new one is generated on every start instead
+        warn!("Legacy configuration version, node name is not persisted");
+        return Ok(());
+    };
+
+    if node.name.is_some() {
+        return Ok(());
+    }
+
+    let node_name = node::generate_node_name();
+    info!(%node_name, "Generated node name");
+    node.name.replace(node_name.clone());
+    config.node.name.replace(node_name);
+
+    raw_config
+        .write_to_path(config_file_path)
+        .await
+        .map_err(|error| {
+            anyhow::anyhow!(
+                "Failed to write config to \"{}\": {}",
+                config_file_path.display(),
+                error
+            )
+        })
+}
+
 /// Returns `Ok(None)` if configuration failed validation
 async fn check_configuration(
     raw_config: &RawConfig,
@@ -657,7 +1013,9 @@ async fn check_configuration(
     }
 }
 
+/// Loads custom chain specification from specified path or mainnet chain specification otherwise
 async fn load_chain_specification(
+    chain_spec_path: Option<&Path>,
     notifications_sender: &mut mpsc::Sender<BackendNotification>,
 ) -> anyhow::Result<ChainSpec> {
     notifications_sender
@@ -666,9 +1024,7 @@ async fn load_chain_specification(
         ))
         .await?;
 
-    // TODO: Switch to non-devnet chain spec
-    let chain_spec = node::load_chain_specification(MAINNET_CHAIN_SPEC.as_bytes())
-        .map_err(|error| anyhow::anyhow!(error))?;
+    let chain_spec = read_chain_specification(chain_spec_path).await?;
 
     notifications_sender
         .send(BackendNotification::Loading(
@@ -679,6 +1035,44 @@ async fn load_chain_specification(
     Ok(chain_spec)
 }
 
+/// Same as [`load_chain_specification()`], but without progress notifications
+async fn read_chain_specification(chain_spec_path: Option<&Path>) -> anyhow::Result<ChainSpec> {
+    match chain_spec_path {
+        Some(chain_spec_path) => {
+            let chain_spec = fs::read(chain_spec_path).await.map_err(|error| {
+                anyhow::anyhow!(
+                    "Failed to read chain specification from \"{}\": {error}",
+                    chain_spec_path.display()
+                )
+            })?;
+            node::load_chain_specification(chain_spec)
+        }
+        None => node::load_chain_specification(MAINNET_CHAIN_SPEC.as_bytes()),
+    }
+    .map_err(|error| anyhow::anyhow!(error))
+}
+
+/// Genesis hash of the configured chain, it is derived from chain specification for custom chains
+fn chain_genesis_hash(config: &Config, chain_spec: &ChainSpec) -> anyhow::Result<String> {
+    if config.chain_id.is_some() {
+        node::genesis_hash(chain_spec).map_err(|error| {
+            anyhow::anyhow!("Failed to derive genesis hash from chain specification: {error}")
+        })
+    } else {
+        Ok(GENESIS_HASH.to_string())
+    }
+}
+
+/// Same as [`chain_genesis_hash()`], but without node running, used for checking farms offline
+pub async fn configured_genesis_hash(config: &Config) -> anyhow::Result<String> {
+    if config.chain_id.is_none() {
+        return Ok(GENESIS_HASH.to_string());
+    }
+
+    let chain_spec = read_chain_specification(config.chain.chain_spec.as_deref()).await?;
+    chain_genesis_hash(config, &chain_spec)
+}
+
 async fn preparing_node_path(
     node_path: &Path,
     notifications_sender: &mut mpsc::Sender<BackendNotification>,
@@ -699,7 +1093,8 @@ async fn preparing_node_path(
         .await?;
 
     if !node_path_exists {
-        fs::create_dir(node_path).await.map_err(|error| {
+        // Node path of custom chain is nested in configured node path
+        fs::create_dir_all(node_path).await.map_err(|error| {
             anyhow::anyhow!(
                 "Node path \"{}\" didn't exist and creation failed: {error:?}",
                 node_path.display()
@@ -719,6 +1114,7 @@ async fn create_networking_stack(
     protocol_prefix: String,
     chain_spec: &ChainSpec,
     weak_plotted_pieces: Weak<AsyncRwLock<PlottedPieces<FarmIndex>>>,
+    mut prometheus_registry: Option<&mut Registry>,
     notifications_sender: &mut mpsc::Sender<BackendNotification>,
 ) -> anyhow::Result<(
     MaybeNodeClient,
@@ -735,7 +1131,8 @@ async fn create_networking_stack(
         ))
         .await?;
 
-    let bootstrap_nodes = dsn_bootstrap_nodes(chain_spec)?;
+    let mut bootstrap_nodes = dsn_bootstrap_nodes(chain_spec)?;
+    bootstrap_nodes.extend(config.network.dsn_bootstrap_nodes.iter().cloned());
 
     let network_path = config.node_path.join("network");
     let keypair_path = network_path.join("secret_ed25519");
@@ -825,6 +1222,9 @@ async fn create_networking_stack(
             Multiaddr::from(IpAddr::V6(Ipv6Addr::UNSPECIFIED))
                 .with(Protocol::Tcp(config.network.subspace_port)),
         ],
+        enable_private_ips: config.network.allow_private_ips,
+        reserved_peers: config.network.dsn_reserved_peers.clone(),
+        external_addresses: config.network.dsn_external_addresses.clone(),
         ..NetworkOptions::default()
     };
     if config.network.faster_networking {
@@ -840,7 +1240,7 @@ async fn create_networking_stack(
         maybe_node_client.clone(),
         subspace_networking::libp2p::identity::PublicKey::from(network_keypair.public())
             .to_peer_id(),
-        None,
+        prometheus_registry.as_deref_mut(),
     );
 
     let (node, node_runner) = create_network(
@@ -850,6 +1250,7 @@ async fn create_networking_stack(
         weak_plotted_pieces,
         maybe_node_client.clone(),
         farmer_cache.clone(),
+        prometheus_registry,
     )?;
 
     notifications_sender
@@ -873,11 +1274,15 @@ async fn create_networking_stack(
 async fn create_consensus_node(
     network_keypair: &Keypair,
     node_path: PathBuf,
-    substrate_port: u16,
+    network: &NetworkConfiguration,
+    rpc: &RpcConfiguration,
+    node_config: &NodeConfiguration,
     chain_spec: ChainSpec,
+    genesis_hash: &str,
     piece_getter: Arc<dyn PieceGetter + Send + Sync + 'static>,
     node: Node,
     maybe_node_client: &MaybeNodeClient,
+    prometheus_registry: Option<&mut Registry>,
     notifications_sender: &mut mpsc::Sender<BackendNotification>,
 ) -> anyhow::Result<LoadedConsensusChainNode> {
     notifications_sender
@@ -889,11 +1294,15 @@ async fn create_consensus_node(
     let create_consensus_node_fut = node::create_consensus_node(
         network_keypair,
         node_path,
-        substrate_port,
+        network,
+        rpc,
+        node_config,
         chain_spec,
+        genesis_hash,
         piece_getter,
         node,
         maybe_node_client,
+        prometheus_registry,
     );
     let consensus_node = match create_consensus_node_fut.await {
         Ok(consensus_node) => consensus_node,
@@ -914,6 +1323,39 @@ async fn create_consensus_node(
     Ok(LoadedConsensusChainNode::Compatible(consensus_node))
 }
 
+/// Connects to external node instead of creating embedded one, farms use it through injected RPC
+/// node client
+async fn connect_to_external_node(
+    url: &str,
+    chain_spec: &ChainSpec,
+    genesis_hash: &str,
+    maybe_node_client: &MaybeNodeClient,
+    notifications_sender: &mut mpsc::Sender<BackendNotification>,
+) -> anyhow::Result<ExternalNode> {
+    notifications_sender
+        .send(BackendNotification::Loading(
+            LoadingStep::ConnectingToExternalNode {
+                url: url.to_string(),
+            },
+        ))
+        .await?;
+
+    // Embedded node does this on creation, addresses are displayed in chain's format
+    node::set_default_ss58_version(chain_spec);
+
+    let external_node =
+        ExternalNode::connect(url, genesis_hash, node::chain_info(chain_spec)).await?;
+    maybe_node_client.inject(Box::new(external_node.node_client().clone()));
+
+    notifications_sender
+        .send(BackendNotification::Loading(
+            LoadingStep::ConnectedToExternalNode,
+        ))
+        .await?;
+
+    Ok(external_node)
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn create_farmer(
     reward_address: PublicKey,
@@ -924,11 +1366,14 @@ async fn create_farmer(
     node_client: MaybeNodeClient,
     kzg: Kzg,
     reduce_plotting_cpu_load: bool,
+    plotting_cpu: PlottingCpuConfiguration,
+    plotting_schedule: PlottingSchedule,
     piece_getter: FarmerPieceGetter<
         FarmIndex,
         SegmentCommitmentPieceValidator<MaybeNodeClient>,
         MaybeNodeClient,
     >,
+    metrics_registries: Option<MetricsRegistries>,
     notifications_sender: &mut mpsc::Sender<BackendNotification>,
 ) -> anyhow::Result<Farmer<FarmIndex>> {
     let farms_total = disk_farms.len() as u16;
@@ -970,7 +1415,34 @@ async fn create_farmer(
                 }
             }
         },
+        on_farm_resize: {
+            let notifications_sender = &*notifications_sender;
+
+            move |farm_index, farm_resize, progress| {
+                let mut notifications_sender = notifications_sender.clone();
+
+                if let Err(error) = notifications_sender
+                    .try_send(BackendNotification::Loading(LoadingStep::ResizingFarm {
+                        farm_index,
+                        farms_total,
+                        farm_resize,
+                        progress,
+                    }))
+                    .or_else(|error| {
+                        tokio::task::block_in_place(|| {
+                            Handle::current()
+                                .block_on(notifications_sender.send(error.into_inner()))
+                        })
+                    })
+                {
+                    warn!(%error, "Failed to send resizing farm backend notification");
+                }
+            }
+        },
         reduce_plotting_cpu_load,
+        plotting_cpu,
+        plotting_schedule,
+        metrics_registries,
     };
 
     let farmer = farmer::create_farmer(farmer_options).await?;
@@ -986,46 +1458,122 @@ async fn create_farmer(
 
 async fn process_backend_actions(
     config_file_path: &Path,
+    running_raw_config: &RawConfig,
     backend_action_receiver: &mut mpsc::Receiver<BackendAction>,
-    farmer_action_sender: &mut mpsc::Sender<FarmerAction>,
+    farmer_action_sender: &mut mpsc::Sender<FarmerAction<FarmIndex>>,
     notifications_sender: &mut mpsc::Sender<BackendNotification>,
 ) {
-    while let Some(action) = backend_action_receiver.next().await {
-        match action {
-            BackendAction::NewConfig { raw_config } => {
-                let result = raw_config
-                    .write_to_path(config_file_path)
-                    .await
-                    .map_err(|error| {
-                        anyhow::anyhow!(
-                            "Failed to write config to \"{}\": {}",
-                            config_file_path.display(),
-                            error
-                        )
-                    });
-                if let Err(error) = notifications_sender
-                    .send(BackendNotification::ConfigSaveResult(result))
-                    .await
-                {
-                    error!(%error, "Failed to send config save result notification");
+    // Applying configuration waits for farms to start, which might take a while, so it is done
+    // separately from forwarding of other actions (one configuration at a time)
+    let (new_config_sender, mut new_config_receiver) = mpsc::unbounded();
+    let mut config_farmer_action_sender = farmer_action_sender.clone();
+
+    let forward_actions_fut = async move {
+        while let Some(action) = backend_action_receiver.next().await {
+            match action {
+                BackendAction::NewConfig { raw_config } => {
+                    // Receiver is only gone when backend is shutting down
+                    let _ = new_config_sender.unbounded_send(raw_config);
                 }
-            }
-            BackendAction::Farmer(farmer_action) => {
-                if let Err(error) = farmer_action_sender.send(farmer_action).await {
-                    error!(%error, "Failed to forward farmer action");
+                BackendAction::Farmer(farmer_action) => {
+                    if let Err(error) = farmer_action_sender.send(farmer_action).await {
+                        error!(%error, "Failed to forward farmer action");
+                    }
                 }
             }
         }
+    };
+    let apply_configs_fut = async move {
+        while let Some(raw_config) = new_config_receiver.next().await {
+            let result = save_and_apply_config(
+                config_file_path,
+                running_raw_config,
+                raw_config,
+                &mut config_farmer_action_sender,
+            )
+            .await;
+            if let Err(error) = notifications_sender
+                .send(BackendNotification::ConfigSaveResult(result))
+                .await
+            {
+                error!(%error, "Failed to send config save result notification");
+            }
+        }
+    };
+
+    future::join(forward_actions_fut, apply_configs_fut).await;
+}
+
+/// Saves new configuration and applies it to the running farmer when possible.
+///
+/// Farms and farmer settings are applied at runtime, changes to anything else require restart
+/// since consensus node and networking stack can't be reconfigured while running.
+async fn save_and_apply_config(
+    config_file_path: &Path,
+    running_raw_config: &RawConfig,
+    raw_config: RawConfig,
+    farmer_action_sender: &mut mpsc::Sender<FarmerAction<FarmIndex>>,
+) -> anyhow::Result<ConfigSaveOutcome> {
+    let config = Config::try_from_raw_config(&raw_config).await?;
+
+    raw_config
+        .write_to_path(config_file_path)
+        .await
+        .map_err(|error| {
+            anyhow::anyhow!(
+                "Failed to write config to \"{}\": {}",
+                config_file_path.display(),
+                error
+            )
+        })?;
+
+    if raw_config.reward_address() != running_raw_config.reward_address()
+        // Balances of farm reward addresses are tracked by consensus node
+        || farm_reward_addresses(&raw_config) != farm_reward_addresses(running_raw_config)
+        || raw_config.node_path() != running_raw_config.node_path()
+        || raw_config.network() != running_raw_config.network()
+        || raw_config.metrics() != running_raw_config.metrics()
+        || raw_config.chain() != running_raw_config.chain()
+        || raw_config.rpc() != running_raw_config.rpc()
+        || raw_config.node() != running_raw_config.node()
+    {
+        return Ok(ConfigSaveOutcome::RestartRequired);
     }
+
+    let (result_sender, result_receiver) = oneshot::channel();
+    farmer_action_sender
+        .send(FarmerAction::UpdateFarms {
+            disk_farms: config.farms,
+            reduce_plotting_cpu_load: config.reduce_plotting_cpu_load,
+            plotting_cpu: config.plotting_cpu,
+            plotting_schedule: config.plotting_schedule,
+            result_sender,
+        })
+        .await?;
+    result_receiver
+        .await
+        .map_err(|_error| anyhow::anyhow!("Farmer exited before applying configuration"))??;
+
+    Ok(ConfigSaveOutcome::Applied)
+}
+
+/// Reward addresses of individual farms as configured
+fn farm_reward_addresses(raw_config: &RawConfig) -> BTreeSet<&str> {
+    raw_config
+        .farms()
+        .iter()
+        .filter_map(|farm| farm.reward_address.as_deref())
+        .collect()
 }
 
 pub async fn wipe(
     raw_config: &RawConfig,
     notifications_sender: &mut mpsc::Sender<BackendNotification>,
 ) -> anyhow::Result<()> {
+    let chain_id = raw_config.chain().read_chain_id().await?;
     let farms = raw_config.farms();
     for (farm_index, farm) in farms.iter().enumerate() {
-        let path = &farm.path;
+        let path = &chain_data_path(&farm.path, chain_id.as_deref());
         notifications_sender
             .send(BackendNotification::Loading(LoadingStep::WipingFarm {
                 farm_index: farm_index as FarmIndex,
@@ -1072,7 +1620,7 @@ pub async fn wipe(
     }
 
     {
-        let path = &raw_config.node_path();
+        let path = &chain_data_path(raw_config.node_path(), chain_id.as_deref());
         notifications_sender
             .send(BackendNotification::Loading(LoadingStep::WipingNode {
                 path: path.to_path_buf(),
@@ -1,30 +1,111 @@
-use crate::backend::farmer::{CACHE_PERCENTAGE, DiskFarm};
+use crate::backend::farmer::{CACHE_PERCENTAGE, DiskFarm, MAX_PLOTTING_SECTORS_PER_FARM};
+use crate::backend::node::{
+    DEFAULT_BLOCKS_PRUNING, MIN_STATE_PRUNING, check_node_name, genesis_hash,
+    load_chain_specification,
+};
 use bytesize::ByteSize;
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
 use serde::{Deserialize, Serialize};
-use std::io;
+use std::net::SocketAddr;
+use std::num::{NonZeroU8, NonZeroU32, NonZeroUsize};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use subspace_core_primitives::PublicKey;
+use std::{fmt, io};
+use subspace_core_primitives::{BlockNumber, PublicKey};
 use subspace_farmer::single_disk_farm::SingleDiskFarm;
 use subspace_farmer::utils::ss58::{Ss58ParsingError, parse_ss58_reward_address};
+use subspace_farmer::utils::{
+    CpuCoreSet, parse_cpu_cores_sets, recommended_number_of_farming_threads,
+};
+use subspace_farmer_components::reading::ReadSectorRecordChunksMode;
+use subspace_networking::libp2p::Multiaddr;
+use subspace_networking::libp2p::multiaddr::Protocol;
+use thread_priority::ThreadPriority;
 use tokio::io::AsyncWriteExt;
 use tokio::task;
-use tracing::warn;
+use tracing::{info, warn};
 
 const DEFAULT_SUBSTRATE_PORT: u16 = 30333;
 const DEFAULT_SUBSPACE_PORT: u16 = 30433;
 pub const MIN_FARM_SIZE: u64 = ByteSize::gb(2).as_u64();
 /// Marginal difference in farm size that will not trigger resizing
-const FARM_SIZE_DIFF_MARGIN: u64 = ByteSize::gib(5).as_u64();
+pub const FARM_SIZE_DIFF_MARGIN: u64 = ByteSize::gib(5).as_u64();
 /// Margin for farm size allocation relatively to available space
-const FARM_SIZE_ALLOCATION_MARGIN: u64 = ByteSize::gib(2).as_u64();
+pub const FARM_SIZE_ALLOCATION_MARGIN: u64 = ByteSize::gib(2).as_u64();
 
-#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+/// Maximum piece cache percentage, the rest of the farm must be available for plotting
+pub const MAX_CACHE_PERCENTAGE: u8 = 99;
+/// Subdirectory of node and farm directories where data of custom chains is stored, such that
+/// different chains never share the same directories
+const CHAINS_DIRECTORY: &str = "chains";
+/// Number of hex characters of genesis hash appended to custom chain ID
+const CHAIN_ID_GENESIS_HASH_LENGTH: usize = 8;
+
+/// How sector record chunks are read during farming
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FarmReadMode {
+    /// Read individual chunks concurrently, best for SSDs
+    ConcurrentChunks,
+    /// Read the whole sector at once, best for HDDs
+    WholeSector,
+}
+
+impl From<FarmReadMode> for ReadSectorRecordChunksMode {
+    fn from(read_mode: FarmReadMode) -> Self {
+        match read_mode {
+            FarmReadMode::ConcurrentChunks => Self::ConcurrentChunks,
+            FarmReadMode::WholeSector => Self::WholeSector,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Farm {
     pub path: PathBuf,
     /// Could be absolute value or percentage of free disk space (when ends with `%`)
     pub size: String,
+    /// Percentage of allocated space dedicated to piece cache, default is used when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_percentage: Option<NonZeroU8>,
+    /// Mode of reading sector record chunks during farming, default is used when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_mode: Option<FarmReadMode>,
+    /// Number of threads used for farming, default is used when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub farming_threads: Option<NonZeroUsize>,
+    /// Maximum number of sectors plotted concurrently, default is used when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_plotting_sectors: Option<NonZeroUsize>,
+    /// Reward address in SS58 format for this farm, global reward address is used when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reward_address: Option<String>,
+}
+
+impl Farm {
+    /// Piece cache percentage with default applied
+    pub fn cache_percentage(&self) -> NonZeroU8 {
+        self.cache_percentage.unwrap_or(CACHE_PERCENTAGE)
+    }
+
+    /// Read mode with default applied
+    pub fn read_mode(&self) -> FarmReadMode {
+        self.read_mode.unwrap_or(FarmReadMode::ConcurrentChunks)
+    }
+
+    /// Number of farming threads with default applied
+    pub fn farming_threads(&self) -> usize {
+        self.farming_threads
+            .map(NonZeroUsize::get)
+            .unwrap_or_else(recommended_number_of_farming_threads)
+    }
+
+    /// Maximum number of concurrently plotted sectors with default applied
+    pub fn max_plotting_sectors(&self) -> NonZeroUsize {
+        self.max_plotting_sectors
+            .unwrap_or(MAX_PLOTTING_SECTORS_PER_FARM)
+    }
 }
 
 /// Configuration error
@@ -43,14 +124,56 @@ pub enum RawConfigError {
     /// Failed to deserialize configuration file
     #[error("Failed to deserialize configuration file: {0}")]
     FailedToDeserialize(serde_json::Error),
+    /// Failed to back up configuration file before upgrade
+    #[error("Failed to back up configuration file to \"{path}\" before upgrade: {error}")]
+    FailedToBackup { path: PathBuf, error: io::Error },
+    /// Failed to write upgraded configuration file
+    #[error("Failed to write upgraded configuration file: {0}")]
+    FailedToWriteUpgraded(io::Error),
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct NetworkConfiguration {
     pub substrate_port: u16,
     pub subspace_port: u16,
     #[serde(default)]
     pub faster_networking: bool,
+    /// Additional DSN bootstrap nodes, used together with those from chain specification
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dsn_bootstrap_nodes: Vec<Multiaddr>,
+    /// DSN peers to always maintain connections with, must include `/p2p/<peer-id>`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dsn_reserved_peers: Vec<Multiaddr>,
+    /// DSN addresses announced to other peers, like public IP with forwarded port
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dsn_external_addresses: Vec<Multiaddr>,
+    /// Substrate nodes to always maintain connections with, must include `/p2p/<peer-id>`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub substrate_reserved_nodes: Vec<Multiaddr>,
+    /// Substrate addresses announced to other peers, like public IP with forwarded port
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub substrate_public_addresses: Vec<Multiaddr>,
+    /// Allow connecting to peers on private IP addresses, useful for farms on the same LAN
+    #[serde(default)]
+    pub allow_private_ips: bool,
+}
+
+impl NetworkConfiguration {
+    fn check_reserved_peers(&self) -> Result<(), ConfigError> {
+        for address in self
+            .dsn_reserved_peers
+            .iter()
+            .chain(&self.substrate_reserved_nodes)
+        {
+            if !matches!(address.iter().last(), Some(Protocol::P2p(_))) {
+                return Err(ConfigError::InvalidReservedPeer {
+                    address: address.to_string(),
+                });
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl Default for NetworkConfiguration {
@@ -59,14 +182,580 @@ impl Default for NetworkConfiguration {
             substrate_port: DEFAULT_SUBSTRATE_PORT,
             subspace_port: DEFAULT_SUBSPACE_PORT,
             faster_networking: false,
+            dsn_bootstrap_nodes: Vec::new(),
+            dsn_reserved_peers: Vec::new(),
+            dsn_external_addresses: Vec::new(),
+            substrate_reserved_nodes: Vec::new(),
+            substrate_public_addresses: Vec::new(),
+            allow_private_ips: false,
+        }
+    }
+}
+
+/// Time of day window during which plotting is allowed
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlottingWindow {
+    /// Days of week on which window starts, every day if empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub days: Vec<Weekday>,
+    pub start: NaiveTime,
+    /// Window ends on the next day if end is not after start
+    pub end: NaiveTime,
+}
+
+impl fmt::Display for PlottingWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.days.is_empty() {
+            let days = self
+                .days
+                .iter()
+                .map(|day| day.to_string())
+                .collect::<Vec<_>>();
+            write!(f, "{} ", days.join(","))?;
+        }
+
+        write!(
+            f,
+            "{}-{}",
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M")
+        )
+    }
+}
+
+impl FromStr for PlottingWindow {
+    type Err = String;
+
+    /// Parses window in format `[days ]HH:MM-HH:MM`, where days are comma-separated weekdays or
+    /// ranges of weekdays, like `Mon-Fri 18:00-08:00` or `Sat,Sun 00:00-00:00`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (days, times) = match s.trim().rsplit_once(' ') {
+            Some((days, times)) => (Some(days.trim()), times),
+            None => (None, s.trim()),
+        };
+
+        let Some((start, end)) = times.split_once('-') else {
+            return Err(format!(
+                "Plotting window \"{s}\" must be in format `[days ]HH:MM-HH:MM`"
+            ));
+        };
+        let parse_time = |time: &str| {
+            NaiveTime::parse_from_str(time, "%H:%M")
+                .map_err(|error| format!("Invalid time \"{time}\" in plotting window: {error}"))
+        };
+        let parse_day = |day: &str| {
+            day.trim()
+                .parse::<Weekday>()
+                .map_err(|_error| format!("Invalid day of week \"{day}\" in plotting window"))
+        };
+
+        let mut parsed_days = Vec::new();
+        for days in days.into_iter().flat_map(|days| days.split(',')) {
+            match days.split_once('-') {
+                Some((first, last)) => {
+                    let last = parse_day(last)?;
+                    let mut day = parse_day(first)?;
+                    loop {
+                        parsed_days.push(day);
+                        if day == last {
+                            break;
+                        }
+                        day = day.succ();
+                    }
+                }
+                None => {
+                    parsed_days.push(parse_day(days)?);
+                }
+            }
+        }
+        parsed_days.dedup();
+
+        Ok(Self {
+            days: parsed_days,
+            start: parse_time(start)?,
+            end: parse_time(end)?,
+        })
+    }
+}
+
+impl PlottingWindow {
+    /// Time interval of the window starting on specified date, if window starts on that day
+    fn interval(&self, date: NaiveDate) -> Option<(NaiveDateTime, NaiveDateTime)> {
+        if !(self.days.is_empty() || self.days.contains(&date.weekday())) {
+            return None;
+        }
+
+        let end_date = if self.end > self.start {
+            date
+        } else {
+            date.succ_opt()?
+        };
+
+        Some((date.and_time(self.start), end_date.and_time(self.end)))
+    }
+}
+
+/// Plotting schedule in local time, plotting is not restricted if there are no windows
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PlottingSchedule {
+    pub windows: Vec<PlottingWindow>,
+}
+
+impl PlottingSchedule {
+    pub fn is_empty(&self) -> bool {
+        self.windows.is_empty()
+    }
+
+    /// Window intervals that can possibly contain or follow specified time within a week
+    fn intervals(&self, at: NaiveDateTime) -> impl Iterator<Item = (NaiveDateTime, NaiveDateTime)> {
+        let first_date = at.date().pred_opt().unwrap_or(at.date());
+
+        first_date.iter_days().take(9).flat_map(|date| {
+            self.windows
+                .iter()
+                .filter_map(move |window| window.interval(date))
+        })
+    }
+
+    pub fn is_plotting_allowed(&self, at: NaiveDateTime) -> bool {
+        self.is_empty()
+            || self
+                .intervals(at)
+                .any(|(start, end)| (start..end).contains(&at))
+    }
+
+    /// Closest time after specified time at which plotting is either paused or resumed, `None` if
+    /// schedule is not restricting plotting
+    pub fn next_change(&self, at: NaiveDateTime) -> Option<NaiveDateTime> {
+        let plotting_allowed = self.is_plotting_allowed(at);
+
+        let mut candidates = self
+            .intervals(at)
+            .flat_map(|(start, end)| [start, end])
+            .filter(|time| *time > at)
+            .collect::<Vec<_>>();
+        candidates.sort_unstable();
+
+        candidates
+            .into_iter()
+            .find(|time| self.is_plotting_allowed(*time) != plotting_allowed)
+    }
+
+    /// Time when plotting will be resumed, `None` if plotting is allowed at specified time
+    pub fn paused_until(&self, at: NaiveDateTime) -> Option<NaiveDateTime> {
+        if self.is_plotting_allowed(at) {
+            None
+        } else {
+            self.next_change(at)
+        }
+    }
+}
+
+/// Priority of plotting threads
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PlottingThreadPriority {
+    /// Lowest priority, such that plotting doesn't slow down other applications
+    #[default]
+    Min,
+    /// Default priority of the operating system
+    Default,
+    /// Highest priority
+    Max,
+}
+
+impl From<PlottingThreadPriority> for Option<ThreadPriority> {
+    fn from(thread_priority: PlottingThreadPriority) -> Self {
+        match thread_priority {
+            PlottingThreadPriority::Min => Some(ThreadPriority::Min),
+            PlottingThreadPriority::Default => None,
+            PlottingThreadPriority::Max => Some(ThreadPriority::Max),
         }
     }
 }
 
+/// Parse CPU cores in `0,2-5 6-11` format: groups of cores are separated by spaces and each group
+/// gets its own thread pool
+pub fn parse_cpu_cores(cpu_cores: &str) -> Result<Vec<CpuCoreSet>, ConfigError> {
+    let cpu_core_sets =
+        parse_cpu_cores_sets(cpu_cores.trim()).map_err(|error| ConfigError::InvalidCpuCores {
+            cpu_cores: cpu_cores.to_string(),
+            error: error.to_string(),
+        })?;
+
+    if cpu_core_sets.is_empty()
+        || cpu_core_sets
+            .iter()
+            .any(|cpu_core_set| cpu_core_set.cpu_cores().is_empty())
+    {
+        return Err(ConfigError::InvalidCpuCores {
+            cpu_cores: cpu_cores.to_string(),
+            error: "each group must contain at least one CPU core".to_string(),
+        });
+    }
+
+    Ok(cpu_core_sets)
+}
+
+/// Format CPU cores in the same format [`parse_cpu_cores()`] accepts
+pub fn format_cpu_cores(cpu_core_sets: &[CpuCoreSet]) -> String {
+    cpu_core_sets
+        .iter()
+        .map(|cpu_core_set| {
+            let mut ranges = Vec::<(usize, usize)>::new();
+            for &cpu_core in cpu_core_set.cpu_cores() {
+                match ranges.last_mut() {
+                    Some((_first, last)) if *last + 1 == cpu_core => {
+                        *last = cpu_core;
+                    }
+                    _ => {
+                        ranges.push((cpu_core, cpu_core));
+                    }
+                }
+            }
+
+            ranges
+                .into_iter()
+                .map(|(first, last)| {
+                    if first == last {
+                        first.to_string()
+                    } else {
+                        format!("{first}-{last}")
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(",")
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// CPU plotting options, defaults are derived from CPU topology (L3 cache groups) when not set
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlottingCpuConfiguration {
+    /// CPU cores used for plotting, see [`parse_cpu_cores()`] for format
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plotting_cpu_cores: Option<String>,
+    /// CPU cores used for replotting, plotting CPU cores are used if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replotting_cpu_cores: Option<String>,
+    /// Number of threads in each plotting thread pool, ignored if CPU cores are set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plotting_thread_pool_size: Option<NonZeroUsize>,
+    /// Number of threads in each replotting thread pool, ignored if CPU cores are set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replotting_thread_pool_size: Option<NonZeroUsize>,
+    /// Number of records encoded concurrently within a sector
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub record_encoding_concurrency: Option<NonZeroUsize>,
+    /// Number of sectors downloaded concurrently, number of thread pools + 1 by default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sector_downloading_concurrency: Option<NonZeroUsize>,
+    #[serde(default)]
+    pub thread_priority: PlottingThreadPriority,
+}
+
+impl PlottingCpuConfiguration {
+    /// Plotting and replotting CPU cores if set explicitly, `None` if they should be derived from
+    /// CPU topology
+    pub fn cpu_cores(&self) -> Result<Option<(Vec<CpuCoreSet>, Vec<CpuCoreSet>)>, ConfigError> {
+        let Some(plotting_cpu_cores) = &self.plotting_cpu_cores else {
+            if self.replotting_cpu_cores.is_some() {
+                return Err(ConfigError::ReplottingCpuCoresWithoutPlotting);
+            }
+
+            return Ok(None);
+        };
+
+        let plotting_cpu_core_sets = parse_cpu_cores(plotting_cpu_cores)?;
+        let replotting_cpu_core_sets = match &self.replotting_cpu_cores {
+            Some(replotting_cpu_cores) => parse_cpu_cores(replotting_cpu_cores)?,
+            None => plotting_cpu_core_sets.clone(),
+        };
+
+        if plotting_cpu_core_sets.len() != replotting_cpu_core_sets.len() {
+            return Err(ConfigError::CpuCoresGroupsMismatch {
+                plotting: plotting_cpu_core_sets.len(),
+                replotting: replotting_cpu_core_sets.len(),
+            });
+        }
+
+        Ok(Some((plotting_cpu_core_sets, replotting_cpu_core_sets)))
+    }
+}
+
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FarmerConfiguration {
+    /// Use replotting CPU cores (half of CPU cores by default) for initial plotting too
+    #[serde(default)]
+    pub reduce_plotting_cpu_load: bool,
+    #[serde(default)]
+    pub plotting_cpu: PlottingCpuConfiguration,
+    /// Plotting is paused automatically outside of scheduled windows
+    #[serde(default, skip_serializing_if = "PlottingSchedule::is_empty")]
+    pub plotting_schedule: PlottingSchedule,
+}
+
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricsConfiguration {
+    /// Address to serve Prometheus metrics on, metrics are disabled if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prometheus_listen_on: Option<SocketAddr>,
+}
+
+/// RPC methods exposed by the node
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcMethods {
+    /// Only methods that are safe to expose to untrusted clients
+    #[default]
+    Safe,
+    /// All methods, including those that allow to control the node
+    Unsafe,
+}
+
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcConfiguration {
+    /// Address to serve node RPC on, RPC is disabled if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub listen_on: Option<SocketAddr>,
+    /// Origins allowed to access RPC from browsers, localhost and polkadot.js are allowed if empty,
+    /// `all` allows any origin
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cors: Vec<String>,
+    #[serde(default)]
+    pub methods: RpcMethods,
+    /// Maximum number of calls per minute for each connection, unlimited if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<NonZeroU32>,
+}
+
+impl RpcConfiguration {
+    fn check(&self) -> Result<(), ConfigError> {
+        if let Some(listen_on) = self.listen_on
+            && !listen_on.ip().is_loopback()
+            && self.methods == RpcMethods::Unsafe
+        {
+            return Err(ConfigError::UnsafeRpcExposed { listen_on });
+        }
+
+        Ok(())
+    }
+}
+
+/// How much history node keeps, either pruned or archive
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum NodePruning {
+    /// State and block bodies of recent blocks are kept, older are pruned
+    Pruned {
+        state_pruning: BlockNumber,
+        blocks_pruning: BlockNumber,
+    },
+    /// State and block bodies of all blocks are kept
+    Archive,
+}
+
+/// How node syncs the chain when it starts with empty database
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncMode {
+    /// Download state of recent block and continue from there, much faster than full sync
+    #[default]
+    Snap,
+    /// Download and verify all blocks since genesis
+    Full,
+}
+
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeConfiguration {
+    /// Node name shown in telemetry, generated randomly and persisted on first start if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Don't send telemetry to endpoints from chain specification
+    #[serde(default)]
+    pub disable_telemetry: bool,
+    /// Keep state and block bodies of all blocks, useful for running indexers against the node.
+    ///
+    /// Node database can't be switched between archive and pruned modes, it needs to be synced
+    /// from scratch after that.
+    #[serde(default)]
+    pub archive: bool,
+    /// Number of recent blocks to keep state of, default is used when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_pruning: Option<BlockNumber>,
+    /// Number of recent finalized blocks to keep bodies of, default is used when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blocks_pruning: Option<BlockNumber>,
+    /// Sync mode, only applies when node is synced from scratch
+    #[serde(default)]
+    pub sync_mode: SyncMode,
+    /// WebSocket URL of farmer RPC of an external node, like `ws://192.168.1.10:9944`.
+    ///
+    /// Farms connect to that node instead of embedded node, which is not started at all, such that
+    /// multiple machines can share one node. Other node settings are ignored in this case.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_rpc_url: Option<String>,
+}
+
+impl NodeConfiguration {
+    /// Pruning with defaults applied
+    pub fn pruning(&self) -> NodePruning {
+        if self.archive {
+            NodePruning::Archive
+        } else {
+            NodePruning::Pruned {
+                state_pruning: self.state_pruning.unwrap_or(MIN_STATE_PRUNING),
+                blocks_pruning: self.blocks_pruning.unwrap_or(DEFAULT_BLOCKS_PRUNING),
+            }
+        }
+    }
+
+    /// Effective sync mode, archive node always uses full sync
+    pub fn sync_mode(&self) -> SyncMode {
+        if self.archive {
+            // Archive node must have all blocks since genesis, which Snap sync doesn't download
+            SyncMode::Full
+        } else {
+            self.sync_mode
+        }
+    }
+
+    fn check(&self) -> Result<(), ConfigError> {
+        if let Some(name) = &self.name
+            && let Err(error) = check_node_name(name)
+        {
+            return Err(ConfigError::InvalidNodeName {
+                name: name.clone(),
+                error,
+            });
+        }
+
+        if let Some(state_pruning) = self.state_pruning
+            && state_pruning < MIN_STATE_PRUNING
+        {
+            return Err(ConfigError::InvalidStatePruning { state_pruning });
+        }
+
+        if self.blocks_pruning == Some(0) {
+            return Err(ConfigError::InvalidBlocksPruning);
+        }
+
+        if let Some(url) = &self.external_rpc_url
+            && !is_external_rpc_url_valid(url)
+        {
+            return Err(ConfigError::InvalidExternalRpcUrl { url: url.clone() });
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainConfiguration {
+    /// Path to chain specification JSON file, mainnet is used if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain_spec: Option<PathBuf>,
+}
+
+impl ChainConfiguration {
+    /// Reads ID of the custom chain, which is used to namespace node and farm data, see
+    /// [`parse_chain_id()`] for details.
+    ///
+    /// Returns `Ok(None)` for mainnet.
+    pub async fn read_chain_id(&self) -> Result<Option<String>, ConfigError> {
+        let Some(chain_spec) = &self.chain_spec else {
+            return Ok(None);
+        };
+
+        let contents =
+            tokio::fs::read(chain_spec)
+                .await
+                .map_err(|error| ConfigError::PathError {
+                    path: chain_spec.display().to_string(),
+                    error,
+                })?;
+
+        let chain_id_fut = task::spawn_blocking({
+            let chain_spec = chain_spec.clone();
+
+            move || parse_chain_id(&chain_spec, &contents)
+        });
+
+        chain_id_fut
+            .await
+            .map_err(|error| ConfigError::InvalidChainSpec {
+                path: chain_spec.display().to_string(),
+                error: format!("Failed to spawn tokio task: {error}"),
+            })
+            .flatten()
+            .map(Some)
+    }
+}
+
+/// Parses ID of the custom chain from contents of chain specification file at `path`.
+///
+/// Chain specifications of different chains often have the same ID (like `dev` or `local`), so
+/// short genesis hash is appended to it, such that such chains don't share data. Deriving genesis
+/// hash might take a while, so this should not be called from async context.
+pub fn parse_chain_id(path: &Path, contents: &[u8]) -> Result<String, ConfigError> {
+    #[derive(Deserialize)]
+    struct ChainSpecId {
+        id: String,
+    }
+
+    let ChainSpecId { id } =
+        serde_json::from_slice(contents).map_err(|error| ConfigError::InvalidChainSpec {
+            path: path.display().to_string(),
+            error: error.to_string(),
+        })?;
+
+    // ID is used as directory name
+    if id.is_empty()
+        || id == "."
+        || id == ".."
+        || id.contains(|c: char| c == '/' || c == '\\' || c.is_control())
+    {
+        return Err(ConfigError::InvalidChainSpec {
+            path: path.display().to_string(),
+            error: format!("Chain ID \"{id}\" can't be used as directory name"),
+        });
+    }
+
+    let genesis_hash = load_chain_specification(contents.to_vec())
+        .and_then(|chain_spec| genesis_hash(&chain_spec))
+        .map_err(|error| ConfigError::InvalidChainSpec {
+            path: path.display().to_string(),
+            error: format!("Failed to derive genesis hash: {error}"),
+        })?;
+
+    Ok(format!(
+        "{id}-{}",
+        &genesis_hash[..CHAIN_ID_GENESIS_HASH_LENGTH]
+    ))
+}
+
+/// Path where data of specified chain is stored inside node or farm directory
+pub fn chain_data_path(path: &Path, chain_id: Option<&str>) -> PathBuf {
+    match chain_id {
+        Some(chain_id) => path.join(CHAINS_DIRECTORY).join(chain_id),
+        // Mainnet data is stored in the root for compatibility with existing installations
+        None => path.to_path_buf(),
+    }
+}
+
 // TODO: This config is not necessarily valid, probably combine with valid config
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "version")]
 pub enum RawConfig {
+    /// Legacy version, upgraded to the latest version automatically when read from disk
     #[serde(rename = "0", rename_all = "camelCase")]
     V0 {
         reward_address: String,
@@ -78,16 +767,41 @@ pub enum RawConfig {
         #[serde(default)]
         network: NetworkConfiguration,
     },
+    /// New settings are added to the nested data structures with `#[serde(default)]`, such that
+    /// they don't require a new version
+    #[serde(rename = "1", rename_all = "camelCase")]
+    V1 {
+        reward_address: String,
+        node_path: PathBuf,
+        // TODO: Use disk farm once it supports serde
+        farms: Vec<Farm>,
+        #[serde(default)]
+        farmer: FarmerConfiguration,
+        #[serde(default)]
+        network: NetworkConfiguration,
+        #[serde(default)]
+        metrics: MetricsConfiguration,
+        #[serde(default)]
+        chain: ChainConfiguration,
+        #[serde(default)]
+        rpc: RpcConfiguration,
+        #[serde(default)]
+        node: NodeConfiguration,
+    },
 }
 
 impl Default for RawConfig {
     fn default() -> Self {
-        Self::V0 {
+        Self::V1 {
             reward_address: String::new(),
             node_path: PathBuf::new(),
             farms: Vec::new(),
-            reduce_plotting_cpu_load: false,
+            farmer: FarmerConfiguration::default(),
             network: NetworkConfiguration::default(),
+            metrics: MetricsConfiguration::default(),
+            chain: ChainConfiguration::default(),
+            rpc: RpcConfiguration::default(),
+            node: NodeConfiguration::default(),
         }
     }
 }
@@ -113,19 +827,49 @@ impl RawConfig {
         Ok(config_file_path)
     }
 
+    /// Read config from disk.
+    ///
+    /// Outdated config is upgraded to the latest version and written back, the original file is
+    /// preserved next to it as a backup.
     pub async fn read_from_path(config_file_path: &Path) -> Result<Option<Self>, RawConfigError> {
-        match tokio::fs::read_to_string(config_file_path).await {
+        let raw_config = match tokio::fs::read_to_string(config_file_path).await {
             Ok(config) => serde_json::from_str::<Self>(&config)
-                .map(Some)
-                .map_err(RawConfigError::FailedToDeserialize),
+                .map_err(RawConfigError::FailedToDeserialize)?,
             Err(error) => {
-                if error.kind() == io::ErrorKind::NotFound {
+                return if error.kind() == io::ErrorKind::NotFound {
                     Ok(None)
                 } else {
                     Err(RawConfigError::FailedToOpen(error))
-                }
+                };
             }
+        };
+
+        if raw_config.is_latest_version() {
+            return Ok(Some(raw_config));
         }
+
+        let backup_file_path =
+            config_file_path.with_extension(format!("json.v{}.bak", raw_config.version()));
+        tokio::fs::copy(config_file_path, &backup_file_path)
+            .await
+            .map_err(|error| RawConfigError::FailedToBackup {
+                path: backup_file_path.clone(),
+                error,
+            })?;
+
+        let raw_config = raw_config.upgrade();
+        raw_config
+            .write_to_path(config_file_path)
+            .await
+            .map_err(RawConfigError::FailedToWriteUpgraded)?;
+
+        info!(
+            version = %raw_config.version(),
+            backup = %backup_file_path.display(),
+            "Configuration upgraded to the latest version"
+        );
+
+        Ok(Some(raw_config))
     }
 
     pub async fn write_to_path(&self, config_file_path: &Path) -> io::Result<()> {
@@ -144,32 +888,107 @@ impl RawConfig {
             .await
     }
 
+    fn version(&self) -> &'static str {
+        match self {
+            Self::V0 { .. } => "0",
+            Self::V1 { .. } => "1",
+        }
+    }
+
+    fn is_latest_version(&self) -> bool {
+        matches!(self, Self::V1 { .. })
+    }
+
+    /// Upgrade config to the latest version, config that is already the latest is returned as is
+    pub fn upgrade(self) -> Self {
+        match self {
+            Self::V0 {
+                reward_address,
+                node_path,
+                farms,
+                reduce_plotting_cpu_load,
+                network,
+            } => Self::V1 {
+                reward_address,
+                node_path,
+                farms,
+                farmer: FarmerConfiguration {
+                    reduce_plotting_cpu_load,
+                    ..FarmerConfiguration::default()
+                },
+                network,
+                metrics: MetricsConfiguration::default(),
+                chain: ChainConfiguration::default(),
+                rpc: RpcConfiguration::default(),
+                node: NodeConfiguration::default(),
+            },
+            Self::V1 { .. } => self,
+        }
+    }
+
     pub fn reward_address(&self) -> &str {
-        let Self::V0 { reward_address, .. } = self;
+        let (Self::V0 { reward_address, .. } | Self::V1 { reward_address, .. }) = self;
         reward_address
     }
 
     pub fn node_path(&self) -> &PathBuf {
-        let Self::V0 { node_path, .. } = self;
+        let (Self::V0 { node_path, .. } | Self::V1 { node_path, .. }) = self;
         node_path
     }
 
     pub fn farms(&self) -> &[Farm] {
-        let Self::V0 { farms, .. } = self;
+        let (Self::V0 { farms, .. } | Self::V1 { farms, .. }) = self;
         farms
     }
 
+    pub fn farmer(&self) -> FarmerConfiguration {
+        match self {
+            Self::V0 {
+                reduce_plotting_cpu_load,
+                ..
+            } => FarmerConfiguration {
+                reduce_plotting_cpu_load: *reduce_plotting_cpu_load,
+                ..FarmerConfiguration::default()
+            },
+            Self::V1 { farmer, .. } => farmer.clone(),
+        }
+    }
+
     pub fn reduce_plotting_cpu_load(&self) -> bool {
-        let Self::V0 {
-            reduce_plotting_cpu_load,
-            ..
-        } = self;
-        *reduce_plotting_cpu_load
+        self.farmer().reduce_plotting_cpu_load
     }
 
     pub fn network(&self) -> NetworkConfiguration {
-        let Self::V0 { network, .. } = self;
-        *network
+        let (Self::V0 { network, .. } | Self::V1 { network, .. }) = self;
+        network.clone()
+    }
+
+    pub fn metrics(&self) -> MetricsConfiguration {
+        match self {
+            Self::V0 { .. } => MetricsConfiguration::default(),
+            Self::V1 { metrics, .. } => *metrics,
+        }
+    }
+
+    pub fn chain(&self) -> ChainConfiguration {
+        match self {
+            Self::V0 { .. } => ChainConfiguration::default(),
+            Self::V1 { chain, .. } => chain.clone(),
+        }
+    }
+
+    pub fn rpc(&self) -> RpcConfiguration {
+        match self {
+            Self::V0 { .. } => RpcConfiguration::default(),
+            Self::V1 { rpc, .. } => rpc.clone(),
+        }
+    }
+
+    pub fn node(&self) -> NodeConfiguration {
+        match self {
+            Self::V0 { .. } => NodeConfiguration::default(),
+            Self::V1 { node, .. } => node.clone(),
+        }
     }
 }
 
@@ -191,6 +1010,49 @@ pub enum ConfigError {
     /// Invalid size format
     #[error("Invalid size format \"{size}\": {error}")]
     InvalidSizeFormat { size: String, error: String },
+    /// Invalid piece cache percentage
+    #[error(
+        "Invalid piece cache percentage {cache_percentage}% for farm \"{path}\", must not exceed \
+        {MAX_CACHE_PERCENTAGE}%"
+    )]
+    InvalidCachePercentage { path: String, cache_percentage: u8 },
+    /// Reserved peer address doesn't include peer ID
+    #[error("Reserved peer address \"{address}\" must end with `/p2p/<peer-id>`")]
+    InvalidReservedPeer { address: String },
+    /// Unsafe RPC methods are exposed on non-loopback address
+    #[error(
+        "Unsafe RPC methods can only be exposed on loopback address, but {listen_on} was specified"
+    )]
+    UnsafeRpcExposed { listen_on: SocketAddr },
+    /// Invalid node name
+    #[error("Invalid node name \"{name}\": {error}")]
+    InvalidNodeName { name: String, error: &'static str },
+    /// State pruning is too aggressive
+    #[error(
+        "State pruning {state_pruning} is invalid, at least {MIN_STATE_PRUNING} blocks must be kept"
+    )]
+    InvalidStatePruning { state_pruning: BlockNumber },
+    /// Blocks pruning is too aggressive
+    #[error("Blocks pruning is invalid, at least 1 block must be kept")]
+    InvalidBlocksPruning,
+    /// Invalid CPU cores
+    #[error("Invalid CPU cores \"{cpu_cores}\": {error}")]
+    InvalidCpuCores { cpu_cores: String, error: String },
+    /// Replotting CPU cores are set without plotting CPU cores
+    #[error("Replotting CPU cores can only be set together with plotting CPU cores")]
+    ReplottingCpuCoresWithoutPlotting,
+    /// Different number of CPU core groups for plotting and replotting
+    #[error(
+        "Number of plotting CPU core groups ({plotting}) must be the same as for replotting \
+        ({replotting})"
+    )]
+    CpuCoresGroupsMismatch { plotting: usize, replotting: usize },
+    /// Invalid chain specification
+    #[error("Invalid chain specification \"{path}\": {error}")]
+    InvalidChainSpec { path: String, error: String },
+    /// External node RPC URL is not a WebSocket URL
+    #[error("Invalid external node RPC URL \"{url}\", must start with `ws://` or `wss://`")]
+    InvalidExternalRpcUrl { url: String },
 }
 
 #[derive(Debug, Clone)]
@@ -199,7 +1061,15 @@ pub struct Config {
     pub node_path: PathBuf,
     pub farms: Vec<DiskFarm>,
     pub reduce_plotting_cpu_load: bool,
+    pub plotting_cpu: PlottingCpuConfiguration,
+    pub plotting_schedule: PlottingSchedule,
     pub network: NetworkConfiguration,
+    pub metrics: MetricsConfiguration,
+    pub chain: ChainConfiguration,
+    pub rpc: RpcConfiguration,
+    pub node: NodeConfiguration,
+    /// ID of the custom chain, `None` for mainnet
+    pub chain_id: Option<String>,
 }
 
 impl Config {
@@ -207,107 +1077,46 @@ impl Config {
     ///
     /// It will check that path exists or parent directory can be accesses.
     pub async fn try_from_raw_config(raw_config: &RawConfig) -> Result<Self, ConfigError> {
-        let reward_address = raw_config.reward_address();
-        let reward_address = parse_ss58_reward_address(reward_address).map_err(|error| {
-            ConfigError::InvalidSs58RewardAddress {
-                reward_address: reward_address.to_string(),
-                error,
-            }
-        })?;
+        let reward_address = parse_reward_address(raw_config.reward_address())?;
+
+        let network = raw_config.network();
+        network.check_reserved_peers()?;
+
+        let rpc = raw_config.rpc();
+        rpc.check()?;
+
+        let node = raw_config.node();
+        node.check()?;
+
+        let farmer = raw_config.farmer();
+        farmer.plotting_cpu.cpu_cores()?;
+
+        let chain = raw_config.chain();
+        let chain_id = chain.read_chain_id().await?;
 
         let node_path = raw_config.node_path().clone();
         check_path(node_path.clone()).await?;
+        let node_path = chain_data_path(&node_path, chain_id.as_deref());
 
         let mut farms = Vec::with_capacity(raw_config.farms().len());
 
         for farm in raw_config.farms() {
-            check_path(farm.path.clone()).await?;
-
-            let farm_details_fut = task::spawn_blocking({
-                let farm = farm.clone();
-
-                move || {
-                    let fs_stats = fs4::statvfs(&farm.path)?;
-                    let effective_disk_usage =
-                        SingleDiskFarm::effective_disk_usage(&farm.path, CACHE_PERCENTAGE.get())
-                            .map_err(|error| {
-                                io::Error::other(format!(
-                                    "Failed to check effective disk usage: {error}"
-                                ))
-                            })?;
-
-                    Ok((fs_stats, effective_disk_usage))
-                }
-            });
-            let farm_details_result = farm_details_fut
-                .await
-                .map_err(|error| io::Error::other(format!("Failed to spawn tokio task: {error}")))
-                .flatten();
-
-            let (fs_stats, effective_disk_usage) = match farm_details_result {
-                Ok(result) => result,
-                Err(error) => {
-                    return Err(ConfigError::PathError {
-                        path: farm.path.display().to_string(),
-                        error,
-                    });
-                }
-            };
-            // Includes "virtual" free space that corresponds to the space farm already occupies,
-            // which simplifies logic below when checking amount of space farm is able to occupy
-            let available_space = fs_stats.available_space() + effective_disk_usage;
-
-            let target_size = if farm.size.ends_with("%") {
-                let size_percentage =
-                    f64::from_str(farm.size.trim_end_matches('%')).map_err(|error| {
-                        ConfigError::InvalidSizeFormat {
-                            size: farm.size.clone(),
-                            error: error.to_string(),
-                        }
-                    })?;
-                if size_percentage <= 0.0 || size_percentage > 100.0 {
-                    return Err(ConfigError::InvalidSizeFormat {
-                        size: farm.size.clone(),
-                        error: "Size percentage should be above 0% and not exceed 100%".to_string(),
-                    });
-                }
-
-                let target_size = (available_space - FARM_SIZE_ALLOCATION_MARGIN) as f64
-                    * size_percentage
-                    / 100.0;
-                let target_size = MIN_FARM_SIZE.max(target_size.round() as u64);
-
-                if target_size.abs_diff(effective_disk_usage) <= FARM_SIZE_DIFF_MARGIN {
-                    effective_disk_usage
-                } else {
-                    target_size
-                }
-            } else {
-                ByteSize::from_str(&farm.size)
-                    .map_err(|error| ConfigError::InvalidSizeFormat {
-                        size: farm.size.clone(),
-                        error,
-                    })?
-                    .as_u64()
-            };
-
-            let size = if target_size > available_space {
-                let new_size = available_space - FARM_SIZE_ALLOCATION_MARGIN;
-                warn!(
-                    target_size,
-                    available_space,
-                    new_size,
-                    "Overriding farm size due to not enough available space"
-                );
+            let resolved_farm_size = resolve_farm_size(farm, chain_id.as_deref()).await?;
 
-                new_size
-            } else {
-                target_size
-            };
+            let farm_reward_address = farm
+                .reward_address
+                .as_deref()
+                .map(parse_reward_address)
+                .transpose()?;
 
             farms.push(DiskFarm {
-                directory: farm.path.clone(),
-                allocated_space: size,
+                directory: chain_data_path(&farm.path, chain_id.as_deref()),
+                allocated_space: resolved_farm_size.allocated_space,
+                cache_percentage: farm.cache_percentage(),
+                read_sector_record_chunks_mode: farm.read_mode().into(),
+                farming_thread_pool_size: farm.farming_threads(),
+                max_plotting_sectors: farm.max_plotting_sectors(),
+                reward_address: farm_reward_address,
             });
         }
 
@@ -315,10 +1124,167 @@ impl Config {
             reward_address,
             node_path,
             farms,
-            reduce_plotting_cpu_load: raw_config.reduce_plotting_cpu_load(),
-            network: raw_config.network(),
+            reduce_plotting_cpu_load: farmer.reduce_plotting_cpu_load,
+            plotting_cpu: farmer.plotting_cpu,
+            plotting_schedule: farmer.plotting_schedule,
+            network,
+            metrics: raw_config.metrics(),
+            chain,
+            rpc,
+            node,
+            chain_id,
         })
     }
+
+    /// Distinct reward addresses of individual farms that differ from global reward address
+    pub fn farm_reward_addresses(&self) -> Vec<PublicKey> {
+        let mut farm_reward_addresses = Vec::new();
+        for reward_address in self.farms.iter().filter_map(|farm| farm.reward_address) {
+            if reward_address != self.reward_address
+                && !farm_reward_addresses.contains(&reward_address)
+            {
+                farm_reward_addresses.push(reward_address);
+            }
+        }
+
+        farm_reward_addresses
+    }
+}
+
+fn parse_reward_address(reward_address: &str) -> Result<PublicKey, ConfigError> {
+    parse_ss58_reward_address(reward_address).map_err(|error| {
+        ConfigError::InvalidSs58RewardAddress {
+            reward_address: reward_address.to_string(),
+            error,
+        }
+    })
+}
+
+/// Whether external node RPC URL looks like a WebSocket URL, connection is only checked on start
+pub fn is_external_rpc_url_valid(url: &str) -> bool {
+    ["ws://", "wss://"].iter().any(|scheme| {
+        url.strip_prefix(scheme)
+            .is_some_and(|rest| !rest.is_empty() && !rest.contains(char::is_whitespace))
+    })
+}
+
+/// Details about how farm size was resolved from its configured value
+#[derive(Debug, Copy, Clone)]
+pub struct ResolvedFarmSize {
+    /// Free space on the disk, including space that farm already occupies
+    pub available_space: u64,
+    /// Space that farm already occupies on the disk
+    pub effective_disk_usage: u64,
+    /// Size derived from configured value, before checking against available space
+    pub target_size: u64,
+    /// Space that will be allocated to the farm
+    pub allocated_space: u64,
+}
+
+/// Resolve farm size from its configured value, which might be a percentage of free disk space.
+///
+/// It will check that path exists or parent directory can be accesses.
+pub async fn resolve_farm_size(
+    farm: &Farm,
+    chain_id: Option<&str>,
+) -> Result<ResolvedFarmSize, ConfigError> {
+    check_path(farm.path.clone()).await?;
+
+    let cache_percentage = farm.cache_percentage();
+    if cache_percentage.get() > MAX_CACHE_PERCENTAGE {
+        return Err(ConfigError::InvalidCachePercentage {
+            path: farm.path.display().to_string(),
+            cache_percentage: cache_percentage.get(),
+        });
+    }
+
+    let farm_details_fut = task::spawn_blocking({
+        let farm = farm.clone();
+        let farm_data_path = chain_data_path(&farm.path, chain_id);
+
+        move || {
+            let fs_stats = fs4::statvfs(&farm.path)?;
+            // Directory of the custom chain is only created when farm is initialized
+            let effective_disk_usage = if farm_data_path.try_exists()? {
+                SingleDiskFarm::effective_disk_usage(&farm_data_path, cache_percentage.get())
+                    .map_err(|error| {
+                        io::Error::other(format!("Failed to check effective disk usage: {error}"))
+                    })?
+            } else {
+                0
+            };
+
+            Ok((fs_stats, effective_disk_usage))
+        }
+    });
+    let farm_details_result = farm_details_fut
+        .await
+        .map_err(|error| io::Error::other(format!("Failed to spawn tokio task: {error}")))
+        .flatten();
+
+    let (fs_stats, effective_disk_usage) = match farm_details_result {
+        Ok(result) => result,
+        Err(error) => {
+            return Err(ConfigError::PathError {
+                path: farm.path.display().to_string(),
+                error,
+            });
+        }
+    };
+    // Includes "virtual" free space that corresponds to the space farm already occupies,
+    // which simplifies logic below when checking amount of space farm is able to occupy
+    let available_space = fs_stats.available_space() + effective_disk_usage;
+
+    let target_size = if farm.size.ends_with("%") {
+        let size_percentage = f64::from_str(farm.size.trim_end_matches('%')).map_err(|error| {
+            ConfigError::InvalidSizeFormat {
+                size: farm.size.clone(),
+                error: error.to_string(),
+            }
+        })?;
+        if size_percentage <= 0.0 || size_percentage > 100.0 {
+            return Err(ConfigError::InvalidSizeFormat {
+                size: farm.size.clone(),
+                error: "Size percentage should be above 0% and not exceed 100%".to_string(),
+            });
+        }
+
+        let target_size =
+            (available_space - FARM_SIZE_ALLOCATION_MARGIN) as f64 * size_percentage / 100.0;
+        let target_size = MIN_FARM_SIZE.max(target_size.round() as u64);
+
+        if target_size.abs_diff(effective_disk_usage) <= FARM_SIZE_DIFF_MARGIN {
+            effective_disk_usage
+        } else {
+            target_size
+        }
+    } else {
+        ByteSize::from_str(&farm.size)
+            .map_err(|error| ConfigError::InvalidSizeFormat {
+                size: farm.size.clone(),
+                error,
+            })?
+            .as_u64()
+    };
+
+    let allocated_space = if target_size > available_space {
+        let new_size = available_space - FARM_SIZE_ALLOCATION_MARGIN;
+        warn!(
+            target_size,
+            available_space, new_size, "Overriding farm size due to not enough available space"
+        );
+
+        new_size
+    } else {
+        target_size
+    };
+
+    Ok(ResolvedFarmSize {
+        available_space,
+        effective_disk_usage,
+        target_size,
+        allocated_space,
+    })
 }
 
 async fn check_path(path: PathBuf) -> Result<(), ConfigError> {
@@ -377,3 +1343,184 @@ async fn check_path(path: PathBuf) -> Result<(), ConfigError> {
         error: io::Error::other(format!("Failed to spawn tokio task: {error}")),
     })?
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Config file as written by the first releases of the application
+    const V0_CONFIG: &str = r#"{
+  "version": "0",
+  "rewardAddress": "stB4S14whneyomiEa22Fu2PzVoibMB7n5PvBFUwafbCbRkC1K",
+  "nodePath": "/home/user/.local/share/space-acres/node",
+  "farms": [
+    {
+      "path": "/mnt/disk1/space-acres",
+      "size": "2T"
+    },
+    {
+      "path": "/mnt/disk2/space-acres",
+      "size": "50%"
+    }
+  ],
+  "reducePlottingCpuLoad": false,
+  "network": {
+    "substrate_port": 31333,
+    "subspace_port": 31433,
+    "faster_networking": true
+  }
+}"#;
+
+    /// Config file of the first releases with reduced plotting CPU load enabled
+    const V0_CONFIG_REDUCE_PLOTTING_CPU_LOAD: &str = r#"{
+  "version": "0",
+  "rewardAddress": "stB4S14whneyomiEa22Fu2PzVoibMB7n5PvBFUwafbCbRkC1K",
+  "nodePath": "/home/user/.local/share/space-acres/node",
+  "farms": [
+    {
+      "path": "/mnt/disk1/space-acres",
+      "size": "2T"
+    }
+  ],
+  "reducePlottingCpuLoad": true
+}"#;
+
+    fn assert_upgraded(raw_config: &RawConfig) {
+        let RawConfig::V1 {
+            reward_address,
+            node_path,
+            farms,
+            farmer,
+            network,
+            metrics,
+            chain,
+            rpc,
+            node,
+        } = raw_config
+        else {
+            panic!("Config must be upgraded to V1: {raw_config:?}");
+        };
+
+        assert_eq!(
+            reward_address,
+            "stB4S14whneyomiEa22Fu2PzVoibMB7n5PvBFUwafbCbRkC1K"
+        );
+        assert_eq!(
+            node_path,
+            Path::new("/home/user/.local/share/space-acres/node")
+        );
+        assert_eq!(
+            farms,
+            &[
+                Farm {
+                    path: PathBuf::from("/mnt/disk1/space-acres"),
+                    size: "2T".to_string(),
+                    ..Farm::default()
+                },
+                Farm {
+                    path: PathBuf::from("/mnt/disk2/space-acres"),
+                    size: "50%".to_string(),
+                    ..Farm::default()
+                },
+            ]
+        );
+        assert_eq!(
+            network,
+            &NetworkConfiguration {
+                substrate_port: 31333,
+                subspace_port: 31433,
+                faster_networking: true,
+                ..NetworkConfiguration::default()
+            }
+        );
+        assert_eq!(farmer, &FarmerConfiguration::default());
+        assert_eq!(metrics, &MetricsConfiguration::default());
+        assert_eq!(chain, &ChainConfiguration::default());
+        assert_eq!(rpc, &RpcConfiguration::default());
+        assert_eq!(node, &NodeConfiguration::default());
+    }
+
+    #[test]
+    fn upgrade_v0() {
+        let raw_config = serde_json::from_str::<RawConfig>(V0_CONFIG).unwrap();
+        assert!(matches!(raw_config, RawConfig::V0 { .. }));
+
+        let raw_config = raw_config.upgrade();
+        assert_upgraded(&raw_config);
+
+        let serialized = serde_json::to_string_pretty(&raw_config).unwrap();
+        assert_eq!(
+            serde_json::from_str::<RawConfig>(&serialized).unwrap(),
+            raw_config
+        );
+    }
+
+    #[test]
+    fn read_from_path_upgrades_v0() {
+        let directory = TempDir::new().unwrap();
+        let config_file_path = directory.path().join("config.json");
+        std::fs::write(&config_file_path, V0_CONFIG).unwrap();
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let raw_config = runtime
+            .block_on(RawConfig::read_from_path(&config_file_path))
+            .unwrap()
+            .unwrap();
+        assert_upgraded(&raw_config);
+
+        assert_eq!(
+            std::fs::read_to_string(directory.path().join("config.json.v0.bak")).unwrap(),
+            V0_CONFIG
+        );
+
+        // Upgraded config is written back and read as is
+        let written_raw_config =
+            serde_json::from_str::<RawConfig>(&std::fs::read_to_string(&config_file_path).unwrap())
+                .unwrap();
+        assert_eq!(written_raw_config, raw_config);
+        assert_eq!(
+            runtime
+                .block_on(RawConfig::read_from_path(&config_file_path))
+                .unwrap(),
+            Some(raw_config)
+        );
+    }
+
+    #[test]
+    fn upgrade_v0_reduce_plotting_cpu_load() {
+        let raw_config =
+            serde_json::from_str::<RawConfig>(V0_CONFIG_REDUCE_PLOTTING_CPU_LOAD).unwrap();
+        assert!(matches!(
+            raw_config,
+            RawConfig::V0 {
+                reduce_plotting_cpu_load: true,
+                ..
+            }
+        ));
+
+        let raw_config = raw_config.upgrade();
+        let RawConfig::V1 {
+            farmer, network, ..
+        } = &raw_config
+        else {
+            panic!("Config must be upgraded to V1: {raw_config:?}");
+        };
+
+        assert_eq!(
+            farmer,
+            &FarmerConfiguration {
+                reduce_plotting_cpu_load: true,
+                ..FarmerConfiguration::default()
+            }
+        );
+        assert_eq!(network, &NetworkConfiguration::default());
+        assert!(raw_config.farmer().reduce_plotting_cpu_load);
+
+        let serialized = serde_json::to_string_pretty(&raw_config).unwrap();
+        assert_eq!(
+            serde_json::from_str::<RawConfig>(&serialized).unwrap(),
+            raw_config
+        );
+    }
+}
@@ -0,0 +1,251 @@
+//! External node farms connect to over farmer RPC instead of embedded node.
+//!
+//! Farmer RPC only exposes what is necessary for farming, so best block, balances and connected
+//! peers are not known in this mode, only whether node is synced.
+
+use crate::backend::node::{ChainInfo, SyncState};
+use crate::backend::utils::{Handler, HandlerFn};
+use anyhow::anyhow;
+use backoff::ExponentialBackoff;
+use backoff::backoff::Backoff;
+use event_listener_primitives::HandlerId;
+use std::fmt;
+use std::time::Duration;
+use subspace_farmer::node_client::NodeClient;
+use subspace_farmer::node_client::rpc_node_client::RpcNodeClient;
+use tracing::{info, warn};
+
+/// How often sync state of external node is checked
+const SYNC_STATE_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+/// Initial delay before retrying after node became unreachable
+const RECONNECT_INITIAL_INTERVAL: Duration = Duration::from_secs(1);
+/// Maximum delay between retries while node is unreachable
+const RECONNECT_MAX_INTERVAL: Duration = Duration::from_secs(30);
+/// For how long node is allowed to be unreachable before giving up
+const RECONNECT_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+pub(super) struct ExternalNode {
+    url: String,
+    genesis_hash: String,
+    node_client: RpcNodeClient,
+    chain_info: ChainInfo,
+    sync_state_change: Handler<SyncState>,
+}
+
+impl fmt::Debug for ExternalNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ExternalNode")
+            .field("url", &self.url)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ExternalNode {
+    /// Connects to farmer RPC at `url` and checks that node follows the chain with `genesis_hash`
+    pub(super) async fn connect(
+        url: &str,
+        genesis_hash: &str,
+        chain_info: ChainInfo,
+    ) -> anyhow::Result<Self> {
+        let node_client = RpcNodeClient::new(url)
+            .await
+            .map_err(|error| anyhow!("Failed to connect to node RPC at {url}: {error}"))?;
+
+        let farmer_app_info = node_client.farmer_app_info().await.map_err(|error| {
+            anyhow!("Failed to get farmer app info from node at {url}: {error}")
+        })?;
+        check_genesis_hash(url, genesis_hash, &farmer_app_info.genesis_hash)?;
+
+        info!(%url, "Connected to external node");
+
+        Ok(Self {
+            url: url.to_string(),
+            genesis_hash: genesis_hash.to_string(),
+            node_client,
+            chain_info,
+            sync_state_change: Handler::default(),
+        })
+    }
+
+    pub(super) fn node_client(&self) -> &RpcNodeClient {
+        &self.node_client
+    }
+
+    pub(super) fn chain_info(&self) -> &ChainInfo {
+        &self.chain_info
+    }
+
+    /// Sync state is either [`SyncState::Idle`] or [`SyncState::Unknown`] while node is syncing,
+    /// sync target is not known
+    pub(super) fn on_sync_state_change(&self, callback: HandlerFn<SyncState>) -> HandlerId {
+        self.sync_state_change.add(callback)
+    }
+
+    /// Tracks sync state of the node.
+    ///
+    /// Node being unreachable is reported as [`SyncState::Unknown`] and requests are retried with
+    /// backoff (RPC client reconnects on its own), error is returned once node was unreachable for
+    /// longer than [`RECONNECT_TIMEOUT`] or it switched to a different chain.
+    pub(super) async fn run(self) -> anyhow::Result<()> {
+        let mut sync_state = SyncState::Unknown;
+        // Present while node is unreachable
+        let mut reconnect_backoff = None::<ExponentialBackoff>;
+
+        loop {
+            let farmer_app_info = match self.node_client.farmer_app_info().await {
+                Ok(farmer_app_info) => farmer_app_info,
+                Err(error) => {
+                    if sync_state != SyncState::Unknown {
+                        sync_state = SyncState::Unknown;
+                        self.sync_state_change.call_simple(&sync_state);
+                    }
+
+                    let reconnect_backoff =
+                        reconnect_backoff.get_or_insert_with(|| ExponentialBackoff {
+                            initial_interval: RECONNECT_INITIAL_INTERVAL,
+                            max_interval: RECONNECT_MAX_INTERVAL,
+                            max_elapsed_time: Some(RECONNECT_TIMEOUT),
+                            ..ExponentialBackoff::default()
+                        });
+                    let Some(delay) = reconnect_backoff.next_backoff() else {
+                        return Err(anyhow!(
+                            "Lost connection to node at {} for more than {RECONNECT_TIMEOUT:?}: \
+                            {error}",
+                            self.url
+                        ));
+                    };
+
+                    warn!(
+                        url = %self.url,
+                        %error,
+                        ?delay,
+                        "External node is unreachable, retrying after delay"
+                    );
+
+                    tokio::time::sleep(delay).await;
+                    continue;
+                }
+            };
+
+            if reconnect_backoff.take().is_some() {
+                // Node might have been replaced with a different one while unreachable
+                check_genesis_hash(&self.url, &self.genesis_hash, &farmer_app_info.genesis_hash)?;
+
+                info!(url = %self.url, "Reconnected to external node");
+            }
+
+            let new_sync_state = if farmer_app_info.syncing {
+                SyncState::Unknown
+            } else {
+                SyncState::Idle
+            };
+            if new_sync_state != sync_state {
+                sync_state = new_sync_state;
+                info!(url = %self.url, synced = %sync_state.is_synced(), "External node sync state changed");
+
+                self.sync_state_change.call_simple(&sync_state);
+            }
+
+            tokio::time::sleep(SYNC_STATE_CHECK_INTERVAL).await;
+        }
+    }
+}
+
+fn check_genesis_hash(
+    url: &str,
+    expected_genesis_hash: &str,
+    node_genesis_hash: &[u8],
+) -> anyhow::Result<()> {
+    let node_genesis_hash = hex::encode(node_genesis_hash);
+    if node_genesis_hash != expected_genesis_hash {
+        return Err(anyhow!(
+            "Node at {url} follows a different chain, its genesis hash is \
+            {node_genesis_hash}, but {expected_genesis_hash} was expected"
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpsee::RpcModule;
+    use jsonrpsee::server::{Server, ServerHandle};
+    use subspace_core_primitives::segments::HistorySize;
+    use subspace_farmer_components::FarmerProtocolInfo;
+    use subspace_rpc_primitives::FarmerAppInfo;
+
+    const GENESIS_HASH: [u8; 32] = [1; 32];
+
+    /// Starts stand-in for node's farmer RPC that only answers farmer app info requests, returns
+    /// its URL
+    async fn start_node_rpc(genesis_hash: [u8; 32]) -> (String, ServerHandle) {
+        let farmer_app_info = FarmerAppInfo {
+            genesis_hash,
+            dsn_bootstrap_nodes: Vec::new(),
+            syncing: false,
+            farming_timeout: Duration::from_secs(1),
+            protocol_info: FarmerProtocolInfo {
+                history_size: HistorySize::ONE,
+                max_pieces_in_sector: 1,
+                recent_segments: HistorySize::ONE,
+                recent_history_fraction: (HistorySize::ONE, HistorySize::ONE),
+                min_sector_lifetime: HistorySize::ONE,
+            },
+        };
+
+        let server = Server::builder().build("127.0.0.1:0").await.unwrap();
+        let address = server.local_addr().unwrap();
+        let mut module = RpcModule::new(());
+        module
+            .register_method("subspace_getFarmerAppInfo", move |_params, _ctx, _ext| {
+                farmer_app_info.clone()
+            })
+            .unwrap();
+
+        (format!("ws://{address}"), server.start(module))
+    }
+
+    #[test]
+    fn connect() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let (url, _server_handle) = start_node_rpc(GENESIS_HASH).await;
+
+            let external_node =
+                ExternalNode::connect(&url, &hex::encode(GENESIS_HASH), ChainInfo::default())
+                    .await
+                    .unwrap();
+
+            assert_eq!(external_node.url, url);
+            assert_eq!(
+                external_node
+                    .node_client()
+                    .farmer_app_info()
+                    .await
+                    .unwrap()
+                    .genesis_hash,
+                GENESIS_HASH
+            );
+        });
+    }
+
+    #[test]
+    fn connect_genesis_hash_mismatch() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let (url, _server_handle) = start_node_rpc([2; 32]).await;
+
+            let error =
+                ExternalNode::connect(&url, &hex::encode(GENESIS_HASH), ChainInfo::default())
+                    .await
+                    .unwrap_err();
+
+            assert!(
+                error.to_string().contains("follows a different chain"),
+                "Unexpected error: {error}"
+            );
+        });
+    }
+}
@@ -1,24 +1,33 @@
 pub(super) mod direct_node_client;
 pub(super) mod maybe_node_client;
+mod pausable_plotter;
+pub mod scrub;
 
 use crate::PosTable;
+use crate::backend::config::{PlottingCpuConfiguration, PlottingSchedule};
 use crate::backend::farmer::maybe_node_client::MaybeNodeClient;
+use crate::backend::farmer::pausable_plotter::PausablePlotter;
+use crate::backend::farmer::scrub::{ScrubStep, ScrubSummary};
+use crate::backend::metrics::{MetricsRegistries, RegistryGuard};
 use crate::backend::utils::{Handler, HandlerFn};
 use anyhow::anyhow;
 use async_lock::{Mutex as AsyncMutex, RwLock as AsyncRwLock, Semaphore};
 use bytesize::ByteSize;
+use chrono::{Local, NaiveDateTime};
 use event_listener_primitives::HandlerId;
 use futures::channel::{mpsc, oneshot};
 use futures::future::BoxFuture;
 use futures::stream::FuturesUnordered;
 use futures::{FutureExt, StreamExt, select};
 use parking_lot::Mutex;
-use std::future::pending;
+use prometheus_client::registry::Registry;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::hash::Hash;
 use std::num::{NonZeroU8, NonZeroUsize};
 use std::path::PathBuf;
+use std::pin::pin;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::{fmt, fs};
 use subspace_core_primitives::PublicKey;
 use subspace_core_primitives::pieces::Record;
@@ -26,7 +35,7 @@ use subspace_core_primitives::sectors::SectorIndex;
 use subspace_erasure_coding::ErasureCoding;
 use subspace_farmer::farm::plotted_pieces::PlottedPieces;
 use subspace_farmer::farm::{
-    FarmingNotification, PlottedSectors, SectorPlottingDetails, SectorUpdate,
+    FarmingNotification, PieceCache, PlotCache, PlottedSectors, SectorPlottingDetails, SectorUpdate,
 };
 use subspace_farmer::farmer_cache::{FarmerCache, FarmerCacheWorker};
 use subspace_farmer::farmer_piece_getter::FarmerPieceGetter;
@@ -37,31 +46,126 @@ use subspace_farmer::plotter::cpu::CpuPlotter;
 #[cfg(feature = "_gpu")]
 use subspace_farmer::plotter::gpu::GpuPlotter;
 use subspace_farmer::single_disk_farm::{
-    SingleDiskFarm, SingleDiskFarmError, SingleDiskFarmOptions,
+    SingleDiskFarm, SingleDiskFarmError, SingleDiskFarmInfo, SingleDiskFarmOptions,
 };
+use subspace_farmer::thread_pool_manager::PlottingThreadPoolManager;
 use subspace_farmer::utils::{
-    AsyncJoinOnDrop, create_plotting_thread_pool_manager, recommended_number_of_farming_threads,
-    run_future_in_dedicated_thread, thread_pool_core_indices,
+    AsyncJoinOnDrop, create_plotting_thread_pool_manager, run_future_in_dedicated_thread,
+    thread_pool_core_indices,
 };
 use subspace_farmer_components::plotting::PlottedSector;
 use subspace_farmer_components::reading::ReadSectorRecordChunksMode;
 use subspace_kzg::Kzg;
-use thread_priority::ThreadPriority;
+use subspace_rpc_primitives::FarmerAppInfo;
 use tokio::sync::watch;
-use tracing::{Instrument, debug, error, info, info_span};
+use tokio::task::JoinHandle;
+use tracing::{Instrument, debug, error, info, info_span, warn};
 
 /// Minimal cache percentage, there is no need in setting it higher
-pub(super) const CACHE_PERCENTAGE: NonZeroU8 = NonZeroU8::MIN;
+pub const CACHE_PERCENTAGE: NonZeroU8 = NonZeroU8::MIN;
 /// NOTE: for large gaps between the plotted part and the end of the file plot cache will result in
 /// very long period of writing zeroes on Windows, see https://stackoverflow.com/q/78058306/3806795
 const MAX_SPACE_PLEDGED_FOR_PLOT_CACHE_ON_WINDOWS: u64 = ByteSize::tib(7).as_u64();
 const FARM_ERROR_PRINT_INTERVAL: Duration = Duration::from_secs(30);
-const MAX_PLOTTING_SECTORS_PER_FARM: NonZeroUsize = NonZeroUsize::new(2).expect("Not zero; qed");
+/// Delay before the first automatic restart of a farm that exited with error, doubled with every
+/// subsequent attempt
+const FARM_RESTART_INITIAL_DELAY: Duration = Duration::from_secs(10);
+/// Upper bound for delay between automatic restarts of a farm
+const FARM_RESTART_MAX_DELAY: Duration = Duration::from_secs(10 * 60);
+/// Farm that keeps failing is left stopped after this many automatic restarts
+const FARM_MAX_RESTART_ATTEMPTS: u32 = 5;
+/// Farm that was running for this long before exiting with error is not considered to be failing
+/// repeatedly, so restart attempts start from the beginning
+const FARM_RESTART_RESET_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// How often plotting schedule is checked at most, such that system time changes are noticed
+const PLOTTING_SCHEDULE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// Conservative estimate of how fast space is allocated when farm grows, it is especially slow on
+/// Windows, where newly allocated space is filled with zeroes
+const FARM_RESIZE_ALLOCATION_SPEED: u64 = ByteSize::mib(200).as_u64();
+/// How often progress of farm resizing is reported
+const FARM_RESIZE_PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+pub const MAX_PLOTTING_SECTORS_PER_FARM: NonZeroUsize =
+    NonZeroUsize::new(2).expect("Not zero; qed");
 
 #[derive(Debug, Default, Copy, Clone, PartialEq)]
 pub struct InitialFarmState {
     pub total_sectors_count: SectorIndex,
     pub plotted_sectors_count: SectorIndex,
+    /// Space allocated to the farm
+    pub allocated_space: u64,
+    /// Percentage of allocated space used for piece cache
+    pub cache_percentage: u8,
+}
+
+impl InitialFarmState {
+    /// Estimate total number of sectors farm will have after resizing to `allocated_space` with
+    /// `cache_percentage`, farm overhead is assumed to be proportional to space used for plot
+    pub fn estimate_total_sectors_count(
+        &self,
+        allocated_space: u64,
+        cache_percentage: u8,
+    ) -> SectorIndex {
+        let plot_space = |allocated_space: u64, cache_percentage: u8| {
+            u128::from(allocated_space) * u128::from(100 - cache_percentage.min(100))
+        };
+
+        let current_plot_space = plot_space(self.allocated_space, self.cache_percentage);
+        if current_plot_space == 0 {
+            return self.total_sectors_count;
+        }
+
+        let total_sectors_count = u128::from(self.total_sectors_count)
+            * plot_space(allocated_space, cache_percentage)
+            / current_plot_space;
+
+        SectorIndex::try_from(total_sectors_count).unwrap_or(SectorIndex::MAX)
+    }
+}
+
+/// Farm that is about to be opened with a different allocated space
+#[derive(Debug, Copy, Clone)]
+pub struct FarmResize {
+    /// Space allocated to the farm before resizing
+    pub old_allocated_space: u64,
+    /// Space that will be allocated to the farm after resizing
+    pub new_allocated_space: u64,
+}
+
+impl FarmResize {
+    /// Conservative estimate of how long resizing will take, shrinking is nearly instant
+    pub fn estimated_duration(&self) -> Duration {
+        let added_space = self
+            .new_allocated_space
+            .saturating_sub(self.old_allocated_space);
+
+        Duration::from_secs(added_space.div_ceil(FARM_RESIZE_ALLOCATION_SPEED))
+    }
+
+    /// Estimated progress in % after resizing for `elapsed` time, there is no way to track actual
+    /// progress, so it never reaches 100% before resizing is done
+    pub fn estimated_progress(&self, elapsed: Duration) -> f32 {
+        let estimated_duration = self.estimated_duration();
+        if estimated_duration.is_zero() {
+            return 0.0;
+        }
+
+        (elapsed.as_secs_f32() / estimated_duration.as_secs_f32() * 100.0).min(99.0)
+    }
+}
+
+/// Steps of automatic restart of a farm that exited with error
+#[derive(Debug, Clone)]
+pub enum FarmRestartStep {
+    /// Farm will be started again after delay
+    Scheduled {
+        error: Arc<anyhow::Error>,
+        /// Restart attempt, starting from `1`
+        attempt: u32,
+        max_attempts: u32,
+        delay: Duration,
+    },
+    /// Farm is being started again
+    Restarting { attempt: u32, max_attempts: u32 },
 }
 
 #[derive(Debug, Clone)]
@@ -83,12 +187,63 @@ pub enum FarmerNotification<FarmIndex> {
         farm_index: FarmIndex,
         error: Arc<anyhow::Error>,
     },
+    /// Farm was started at runtime, replaces previously removed farm with the same index if any
+    FarmAdded {
+        farm_index: FarmIndex,
+        farm: DiskFarm,
+        initial_farm_state: InitialFarmState,
+        /// Plotting of this farm was paused before it was restarted with the same index
+        plotting_paused: bool,
+    },
+    /// Farm was stopped at runtime
+    FarmRemoved { farm_index: FarmIndex },
+    /// Plotting was paused or resumed according to plotting schedule
+    PlottingScheduleUpdate {
+        /// Local time when plotting will be resumed, `None` if schedule doesn't pause plotting
+        paused_until: Option<NaiveDateTime>,
+    },
+    /// Farm integrity check progress, farm is stopped and started again without
+    /// [`Self::FarmRemoved`] in the meantime
+    FarmScrub {
+        farm_index: FarmIndex,
+        step: ScrubStep,
+    },
+    /// Farm exited with error and is restarted automatically, farm is started again without
+    /// [`Self::FarmRemoved`] in the meantime. [`Self::FarmError`] is sent instead once farm failed
+    /// too many times.
+    FarmRestart {
+        farm_index: FarmIndex,
+        step: FarmRestartStep,
+    },
+    /// Farm is being resized after configuration update, farm is started again without
+    /// [`Self::FarmRemoved`] in the meantime unless it fails to start
+    FarmResize {
+        farm_index: FarmIndex,
+        farm_resize: FarmResize,
+        /// Estimated progress so far in %
+        progress: f32,
+    },
 }
 
-#[derive(Debug, Clone)]
-pub enum FarmerAction {
+#[derive(Debug)]
+pub enum FarmerAction<FarmIndex> {
     /// Pause (or resume) plotting
     PausePlotting(bool),
+    /// Pause (or resume) plotting of a single farm, other farms are not affected
+    PauseFarmPlotting { farm_index: FarmIndex, paused: bool },
+    /// Apply new farms configuration without restarting the whole application
+    UpdateFarms {
+        disk_farms: Vec<DiskFarm>,
+        reduce_plotting_cpu_load: bool,
+        plotting_cpu: PlottingCpuConfiguration,
+        plotting_schedule: PlottingSchedule,
+        result_sender: oneshot::Sender<anyhow::Result<()>>,
+    },
+    /// Stop farm, check its integrity, repair it unless `dry_run` is `true` and start it again
+    ScrubFarm {
+        farm_index: FarmIndex,
+        dry_run: bool,
+    },
 }
 
 type Notifications<FarmIndex> = Handler<FarmerNotification<FarmIndex>>;
@@ -101,7 +256,7 @@ where
     farmer_cache_worker_fut: BoxFuture<'static, ()>,
     initial_farm_states: Vec<InitialFarmState>,
     notifications: Arc<Notifications<FarmIndex>>,
-    action_sender: mpsc::Sender<FarmerAction>,
+    action_sender: mpsc::Sender<FarmerAction<FarmIndex>>,
 }
 
 impl<FarmIndex> Farmer<FarmIndex>
@@ -161,7 +316,7 @@ where
         &self.initial_farm_states
     }
 
-    pub(super) fn action_sender(&self) -> mpsc::Sender<FarmerAction> {
+    pub(super) fn action_sender(&self) -> mpsc::Sender<FarmerAction<FarmIndex>> {
         self.action_sender.clone()
     }
 
@@ -179,15 +334,21 @@ impl<FarmIndex> fmt::Debug for Farmer<FarmIndex> {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct DiskFarm {
     pub directory: PathBuf,
     pub allocated_space: u64,
+    pub cache_percentage: NonZeroU8,
+    pub read_sector_record_chunks_mode: ReadSectorRecordChunksMode,
+    pub farming_thread_pool_size: usize,
+    pub max_plotting_sectors: NonZeroUsize,
+    /// Overrides global reward address when set
+    pub reward_address: Option<PublicKey>,
 }
 
 /// Arguments for farmer
 #[derive(Debug)]
-pub(super) struct FarmerOptions<FarmIndex, OnFarmInitialized> {
+pub(super) struct FarmerOptions<FarmIndex, OnFarmInitialized, OnFarmResize> {
     pub(super) reward_address: PublicKey,
     pub(super) disk_farms: Vec<DiskFarm>,
     pub(super) node_client: MaybeNodeClient,
@@ -202,17 +363,24 @@ pub(super) struct FarmerOptions<FarmIndex, OnFarmInitialized> {
     pub(super) farmer_cache_worker: FarmerCacheWorker<MaybeNodeClient>,
     pub(super) kzg: Kzg,
     pub(super) reduce_plotting_cpu_load: bool,
+    pub(super) plotting_cpu: PlottingCpuConfiguration,
+    pub(super) plotting_schedule: PlottingSchedule,
+    /// Farm metrics are registered here if provided
+    pub(super) metrics_registries: Option<MetricsRegistries>,
     pub(super) on_farm_initialized: OnFarmInitialized,
+    /// Called before existing farm is resized, which might take a while
+    pub(super) on_farm_resize: OnFarmResize,
 }
 
-pub(super) async fn create_farmer<FarmIndex, OnFarmInitialized>(
-    farmer_options: FarmerOptions<FarmIndex, OnFarmInitialized>,
+pub(super) async fn create_farmer<FarmIndex, OnFarmInitialized, OnFarmResize>(
+    farmer_options: FarmerOptions<FarmIndex, OnFarmInitialized, OnFarmResize>,
 ) -> anyhow::Result<Farmer<FarmIndex>>
 where
     FarmIndex:
-        Hash + Eq + Copy + fmt::Display + fmt::Debug + TryFrom<usize> + Send + Sync + 'static,
+        Hash + Eq + Ord + Copy + fmt::Display + fmt::Debug + TryFrom<usize> + Send + Sync + 'static,
     usize: From<FarmIndex>,
     OnFarmInitialized: Fn(FarmIndex),
+    OnFarmResize: Fn(FarmIndex, FarmResize, f32),
 {
     let span = info_span!("Farmer");
     let _enter = span.enter();
@@ -227,32 +395,17 @@ where
         farmer_cache_worker,
         kzg,
         reduce_plotting_cpu_load,
+        plotting_cpu,
+        mut plotting_schedule,
+        metrics_registries,
         on_farm_initialized,
+        on_farm_resize,
     } = farmer_options;
 
     if disk_farms.is_empty() {
         return Err(anyhow!("There must be at least one disk farm provided"));
     }
 
-    for farm in &disk_farms {
-        if !farm.directory.exists()
-            && let Err(error) = fs::create_dir(&farm.directory)
-        {
-            return Err(anyhow!(
-                "Directory {} doesn't exist and can't be created: {}",
-                farm.directory.display(),
-                error
-            ));
-        }
-    }
-
-    let plot_cache = !cfg!(windows)
-        || disk_farms
-            .iter()
-            .map(|farm| farm.allocated_space)
-            .sum::<u64>()
-            <= MAX_SPACE_PLEDGED_FOR_PLOT_CACHE_ON_WINDOWS;
-
     let farmer_app_info = node_client
         .farmer_app_info()
         .await
@@ -270,51 +423,7 @@ where
             .in_current_span(),
     );
 
-    let mut plotting_thread_pool_core_indices = thread_pool_core_indices(None, None);
-    let replotting_thread_pool_core_indices = {
-        let mut replotting_thread_pool_core_indices = thread_pool_core_indices(None, None);
-        // The default behavior is to use all CPU cores, but for replotting we just want half
-        replotting_thread_pool_core_indices
-            .iter_mut()
-            .for_each(|set| set.truncate(set.cpu_cores().len() / 2));
-        replotting_thread_pool_core_indices
-    };
-    if reduce_plotting_cpu_load {
-        plotting_thread_pool_core_indices = replotting_thread_pool_core_indices.clone();
-    }
-
-    if plotting_thread_pool_core_indices.len() > 1 {
-        info!(
-            l3_cache_groups = %plotting_thread_pool_core_indices.len(),
-            "Multiple L3 cache groups detected"
-        );
-    }
-
-    let plotting_thread_pools_count = plotting_thread_pool_core_indices.len();
-
-    let downloading_semaphore =
-        Arc::new(Semaphore::new(plotting_thread_pool_core_indices.len() + 1));
-
-    let record_encoding_concurrency = {
-        let cpu_cores = plotting_thread_pool_core_indices
-            .first()
-            .expect("Guaranteed to have some CPU cores; qed");
-
-        NonZeroUsize::new((cpu_cores.cpu_cores().len() / 2).min(8)).unwrap_or(NonZeroUsize::MIN)
-    };
-
-    info!(
-        ?plotting_thread_pool_core_indices,
-        ?replotting_thread_pool_core_indices,
-        "Preparing plotting thread pools"
-    );
-
-    let plotting_thread_pool_manager = create_plotting_thread_pool_manager(
-        plotting_thread_pool_core_indices
-            .into_iter()
-            .zip(replotting_thread_pool_core_indices),
-        Some(ThreadPriority::Min),
-    )?;
+    let plotting_thread_pools = PlottingThreadPools::new(reduce_plotting_cpu_load, &plotting_cpu)?;
 
     let global_mutex = Arc::default();
 
@@ -395,22 +504,58 @@ where
         }
     }
 
-    let plotter = if let Some(plotter) = plotter {
+    let (plotter, cpu_plotting) = if let Some(plotter) = plotter {
         info!("CPU plotting was disabled due to detected faster plotting with GPU");
 
-        plotter
+        (plotter, false)
     } else {
-        let cpu_plotter = Arc::new(CpuPlotter::<_, PosTable>::new(
+        let cpu_plotter = create_cpu_plotter(
             piece_getter.clone(),
-            downloading_semaphore,
-            plotting_thread_pool_manager.clone(),
-            record_encoding_concurrency,
+            &plotting_thread_pools,
             Arc::clone(&global_mutex),
             kzg.clone(),
             erasure_coding.clone(),
-            None,
-        ));
-        Arc::new(cpu_plotter)
+        );
+
+        (cpu_plotter, true)
+    };
+
+    let notifications = Arc::new(Notifications::default());
+    let (plotting_thread_pools_sender, plotting_thread_pools_receiver) =
+        watch::channel(plotting_thread_pools);
+    let (farm_exits_sender, mut farm_exits_receiver) = mpsc::unbounded();
+    let (scrub_results_sender, mut scrub_results_receiver) = mpsc::unbounded();
+    let (farm_restarts_sender, mut farm_restarts_receiver) = mpsc::unbounded();
+    let (farm_starts_sender, mut farm_starts_receiver) = mpsc::unbounded();
+
+    let mut farms_manager = FarmsManager {
+        reward_address,
+        node_client,
+        piece_getter,
+        kzg,
+        erasure_coding,
+        global_mutex,
+        plotter,
+        cpu_plotting,
+        reduce_plotting_cpu_load,
+        plotting_cpu,
+        plotting_thread_pools_sender,
+        paused_farms_sender: watch::Sender::new(HashSet::new()),
+        plotted_pieces,
+        farmer_cache,
+        notifications: Arc::clone(&notifications),
+        metrics_registries,
+        farms: BTreeMap::new(),
+        farm_exits_sender,
+        scrubbing_farms: HashMap::new(),
+        scrub_results_sender,
+        restarting_farms: HashMap::new(),
+        farm_restarts_sender,
+        starting_farms: HashMap::new(),
+        farm_starts_sender,
+        pending_updates: HashMap::new(),
+        next_update_id: 0,
+        next_run_id: 0,
     };
 
     let (farms, plotting_delay_senders) = {
@@ -420,6 +565,8 @@ where
             .map(|_| oneshot::channel())
             .unzip::<_, _, Vec<_>, Vec<_>>();
         let on_farm_initialized = &on_farm_initialized;
+        let on_farm_resize = &on_farm_resize;
+        let farm_creator = &farms_manager.farm_creator();
 
         let mut farms = Vec::with_capacity(farms_total);
         let mut farms_stream = disk_farms
@@ -427,77 +574,32 @@ where
             .zip(plotting_delay_receivers)
             .enumerate()
             .map(|(farm_index, (disk_farm, plotting_delay_receiver))| {
-                let node_client = node_client.clone();
                 let farmer_app_info = farmer_app_info.clone();
-                let max_pieces_in_sector = farmer_app_info.protocol_info.max_pieces_in_sector;
-                let kzg = kzg.clone();
-                let erasure_coding = erasure_coding.clone();
-                let plotter = Arc::clone(&plotter);
-                let global_mutex = Arc::clone(&global_mutex);
 
                 async move {
-                    let farm_fut = SingleDiskFarm::new::<_, PosTable>(
-                        SingleDiskFarmOptions {
-                            directory: disk_farm.directory.clone(),
-                            farmer_app_info,
-                            allocated_space: disk_farm.allocated_space,
-                            max_pieces_in_sector,
-                            node_client,
-                            reward_address,
-                            plotter,
-                            kzg,
-                            erasure_coding,
-                            cache_percentage: CACHE_PERCENTAGE.get(),
-                            farming_thread_pool_size: recommended_number_of_farming_threads(),
-                            plotting_delay: Some(plotting_delay_receiver),
-                            global_mutex,
-                            max_plotting_sectors_per_farm: MAX_PLOTTING_SECTORS_PER_FARM,
-                            disable_farm_locking: false,
-                            read_sector_record_chunks_mode:
-                                ReadSectorRecordChunksMode::ConcurrentChunks,
-                            registry: None,
-                            create: true,
-                        },
+                    let farm_fut = farm_creator.create(
                         farm_index,
+                        &disk_farm,
+                        farmer_app_info,
+                        Some(plotting_delay_receiver),
+                        |farm_resize, progress| {
+                            if let Ok(farm_index) = farm_index.try_into() {
+                                on_farm_resize(farm_index, farm_resize, progress);
+                            }
+                        },
                     );
 
-                    let farm = match farm_fut.await {
+                    let (farm, metrics_registry_guard) = match farm_fut.await {
                         Ok(farm) => farm,
-                        Err(SingleDiskFarmError::InsufficientAllocatedSpace {
-                            min_space,
-                            allocated_space,
-                        }) => {
-                            return (
-                                farm_index,
-                                Err(anyhow::anyhow!(
-                                    "Allocated space {} ({}) is not enough, minimum is ~{} (~{}, \
-                                    {} bytes to be exact)",
-                                    bytesize::to_string(allocated_space, true),
-                                    bytesize::to_string(allocated_space, false),
-                                    bytesize::to_string(min_space, true),
-                                    bytesize::to_string(min_space, false),
-                                    min_space
-                                )),
-                            );
-                        }
                         Err(error) => {
-                            return (farm_index, Err(error.into()));
+                            return (farm_index, Err(error));
                         }
                     };
 
-                    let _info_guard = info_mutex.lock().await;
-
-                    let info = farm.info();
-                    info!("Farm {farm_index}:");
-                    info!("  ID: {}", info.id());
-                    info!("  Genesis hash: 0x{}", hex::encode(info.genesis_hash()));
-                    info!("  Public key: 0x{}", hex::encode(info.public_key()));
-                    info!(
-                        "  Allocated space: {} ({})",
-                        bytesize::to_string(info.allocated_space(), true),
-                        bytesize::to_string(info.allocated_space(), false)
-                    );
-                    info!("  Directory: {}", disk_farm.directory.display());
+                    {
+                        let _info_guard = info_mutex.lock().await;
+                        log_farm_info(farm_index, &farm, &disk_farm);
+                    }
 
                     {
                         let Ok(farm_index) = farm_index.try_into() else {
@@ -510,7 +612,7 @@ where
                         };
                         on_farm_initialized(farm_index);
                     }
-                    (farm_index, Ok(farm))
+                    (farm_index, Ok((disk_farm, (farm, metrics_registry_guard))))
                 }
                 .instrument(info_span!("", %farm_index))
             })
@@ -530,11 +632,6 @@ where
         // Restore order after unordered initialization
         farms.sort_unstable_by_key(|(farm_index, _farm)| *farm_index);
 
-        let farms = farms
-            .into_iter()
-            .map(|(_farm_index, farm)| farm)
-            .collect::<Vec<_>>();
-
         (farms, plotting_delay_senders)
     };
 
@@ -544,7 +641,7 @@ where
         // cache hit ratio
         handler_id
             .lock()
-            .replace(farmer_cache.on_sync_progress(Arc::new({
+            .replace(farms_manager.farmer_cache.on_sync_progress(Arc::new({
                 let handler_id = Arc::clone(&handler_id);
                 let plotting_delay_senders = Mutex::new(plotting_delay_senders);
 
@@ -559,66 +656,32 @@ where
                 }
             })));
     }
-    farmer_cache
-        .replace_backing_caches(
-            farms
-                .iter()
-                .map(|farm| Arc::new(farm.piece_cache()) as Arc<_>)
-                .collect(),
-            if plot_cache {
-                farms
-                    .iter()
-                    .map(|farm| Arc::new(farm.plot_cache()) as Arc<_>)
-                    .collect()
-            } else {
-                Vec::new()
-            },
-        )
-        .await;
 
     info!("Collecting already plotted pieces (this will take some time)...");
 
     // Collect already plotted pieces
     let mut initial_farm_states = Vec::with_capacity(farms.len());
+    let mut farms_to_run = Vec::with_capacity(farms.len());
 
-    for (farm_index, farm) in farms.iter().enumerate() {
-        let mut plotted_pieces = plotted_pieces.write().await;
+    for (farm_index, (disk_farm, (farm, metrics_registry_guard))) in farms {
         let farm_index = farm_index
             .try_into()
             .map_err(|_error| anyhow!("More than 256 plots are not supported by Space Acres"))?;
 
-        plotted_pieces.add_farm(farm_index, Arc::new(farm.piece_reader()));
-
-        let total_sectors_count = farm.total_sectors_count();
-        let mut plotted_sectors_count = 0;
-        let plotted_sectors = farm.plotted_sectors();
-        let mut plotted_sectors = plotted_sectors.get().await.map_err(|error| {
-            anyhow!("Failed to get plotted sectors for farm {farm_index}: {error}")
-        })?;
-
-        while let Some(plotted_sector_result) = plotted_sectors.next().await {
-            plotted_sectors_count += 1;
-            plotted_pieces.add_sector(
-                farm_index,
-                &plotted_sector_result.map_err(|error| {
-                    anyhow!(
-                        "Failed reading plotted sector on startup for farm {farm_index}: {error}"
-                    )
-                })?,
-            )
-        }
-
-        initial_farm_states.push(InitialFarmState {
-            total_sectors_count,
-            plotted_sectors_count,
-        });
+        initial_farm_states.push(
+            farms_manager
+                .register_farm(farm_index, disk_farm, &farm, metrics_registry_guard)
+                .await?,
+        );
+        farms_to_run.push((farm_index, farm));
     }
 
     info!("Finished collecting already plotted pieces successfully");
 
-    let notifications = Arc::new(Notifications::default());
+    farms_manager.replace_backing_caches().await;
 
-    farmer_cache
+    farms_manager
+        .farmer_cache
         .on_sync_progress(Arc::new({
             let notifications = Arc::clone(&notifications);
 
@@ -630,182 +693,1497 @@ where
         }))
         .detach();
 
-    let mut farms_stream = farms
-        .into_iter()
-        .enumerate()
-        .map(|(farm_index, farm)| {
-            let Ok(farm_index) = FarmIndex::try_from(farm_index) else {
-                unreachable!(
-                    "More than 256 plots are not supported, this is checked above already; qed"
-                );
-            };
-            let plotted_pieces = Arc::clone(&plotted_pieces);
-            let span = info_span!("farm", %farm_index);
+    let (action_sender, mut action_receiver) = mpsc::channel(1);
+    let (pause_plotting_sender, pause_plotting_receiver) = watch::channel(false);
 
-            farm.on_sector_update(Arc::new({
-                let notifications = Arc::clone(&notifications);
+    let farmer_fut = Box::pin(
+        async move {
+            for (farm_index, farm) in farms_to_run {
+                farms_manager.spawn_farm(farm_index, farm);
+            }
 
-                move |(sector_index, sector_update)| {
-                    notifications.call_simple(&FarmerNotification::SectorUpdate {
-                        farm_index,
-                        sector_index: *sector_index,
-                        update: sector_update.clone(),
-                    });
+            let mut pause_plotting_fut = pin!(
+                pause_plotting(pause_plotting_receiver, plotting_thread_pools_receiver).fuse()
+            );
+            // Paused by user, as opposed to paused by schedule
+            let mut plotting_paused = false;
+            let mut plotting_paused_until = None;
+
+            loop {
+                let now = Local::now().naive_local();
+                let paused_until = plotting_schedule.paused_until(now);
+                if paused_until != plotting_paused_until {
+                    match paused_until {
+                        Some(paused_until) => {
+                            info!(%paused_until, "Plotting paused according to schedule");
+                        }
+                        None => {
+                            info!("Plotting resumed according to schedule");
+                        }
+                    }
+                    plotting_paused_until = paused_until;
+                    farms_manager
+                        .notifications
+                        .call_simple(&FarmerNotification::PlottingScheduleUpdate { paused_until });
                 }
-            }))
-            .detach();
-            farm.on_farming_notification(Arc::new({
-                let notifications = Arc::clone(&notifications);
-
-                move |notification| {
-                    notifications.call_simple(&FarmerNotification::FarmingNotification {
-                        farm_index,
-                        notification: notification.clone(),
+                let pause_plotting = plotting_paused || paused_until.is_some();
+                pause_plotting_sender.send_if_modified(|paused| {
+                    let modified = *paused != pause_plotting;
+                    *paused = pause_plotting;
+                    modified
+                });
+                // Check again when schedule changes, but also periodically in case system time
+                // changes
+                let schedule_check_delay = plotting_schedule
+                    .next_change(now)
+                    .and_then(|next_change| (next_change - now).to_std().ok())
+                    .map_or(PLOTTING_SCHEDULE_CHECK_INTERVAL, |delay| {
+                        delay.min(PLOTTING_SCHEDULE_CHECK_INTERVAL)
                     });
-                }
-            }))
-            .detach();
-
-            // Collect newly plotted pieces
-            let on_plotted_sector_callback =
-                move |plotted_sector: &PlottedSector,
-                      maybe_old_plotted_sector: &Option<PlottedSector>| {
-                    let _span_guard = span.enter();
 
-                    {
-                        let mut plotted_pieces = plotted_pieces.write_blocking();
+                select! {
+                    _ = pause_plotting_fut => {
+                        return Ok(());
+                    }
+                    _ = tokio::time::sleep(schedule_check_delay).fuse() => {
+                        // Schedule is checked at the beginning of the loop
+                    }
+                    maybe_action = action_receiver.next() => {
+                        let Some(action) = maybe_action else {
+                            return Ok(());
+                        };
 
-                        if let Some(old_plotted_sector) = &maybe_old_plotted_sector {
-                            plotted_pieces.delete_sector(farm_index, old_plotted_sector);
+                        match action {
+                            FarmerAction::PausePlotting(pause_plotting) => {
+                                plotting_paused = pause_plotting;
+                            }
+                            FarmerAction::PauseFarmPlotting { farm_index, paused } => {
+                                farms_manager.pause_farm_plotting(farm_index, paused);
+                            }
+                            FarmerAction::UpdateFarms {
+                                disk_farms,
+                                reduce_plotting_cpu_load,
+                                plotting_cpu,
+                                plotting_schedule: new_plotting_schedule,
+                                result_sender,
+                            } => {
+                                plotting_schedule = new_plotting_schedule;
+                                farms_manager
+                                    .update_farms(
+                                        disk_farms,
+                                        reduce_plotting_cpu_load,
+                                        plotting_cpu,
+                                        result_sender,
+                                    )
+                                    .await;
+                            }
+                            FarmerAction::ScrubFarm { farm_index, dry_run } => {
+                                farms_manager.scrub_farm(farm_index, dry_run).await;
+                            }
+                        }
+                    }
+                    maybe_farm_exit = farm_exits_receiver.next() => {
+                        if let Some(farm_exit) = maybe_farm_exit {
+                            farms_manager.process_farm_exit(farm_exit).await;
+                        }
+                    }
+                    maybe_scrub_result = scrub_results_receiver.next() => {
+                        if let Some(scrub_result) = maybe_scrub_result {
+                            farms_manager.finish_scrub(scrub_result);
+                        }
+                    }
+                    maybe_farm_index = farm_restarts_receiver.next() => {
+                        if let Some(farm_index) = maybe_farm_index {
+                            farms_manager.restart_farm(farm_index);
+                        }
+                    }
+                    maybe_farm_start = farm_starts_receiver.next() => {
+                        if let Some(farm_start) = maybe_farm_start {
+                            farms_manager.finish_farm_start(farm_start).await;
                         }
-                        plotted_pieces.add_sector(farm_index, plotted_sector);
                     }
-                };
-            farm.on_sector_update(Arc::new(move |(_sector_index, sector_state)| {
-                if let SectorUpdate::Plotting(SectorPlottingDetails::Finished {
-                    plotted_sector,
-                    old_plotted_sector,
-                    ..
-                }) = sector_state
-                {
-                    on_plotted_sector_callback(plotted_sector, old_plotted_sector);
                 }
-            }))
-            .detach();
-
-            farm.run().map(move |result| (farm_index, result))
-        })
-        .collect::<FuturesUnordered<_>>();
+            }
+        }
+        .in_current_span(),
+    );
 
-    // Drop original instance such that the only remaining instances are in `SingleDiskFarm`
-    // event handlers
-    drop(plotted_pieces);
+    anyhow::Ok(Farmer {
+        farmer_fut,
+        farmer_cache_worker_fut,
+        initial_farm_states,
+        notifications,
+        action_sender,
+    })
+}
 
-    let (action_sender, mut action_receiver) = mpsc::channel(1);
-    let (pause_plotting_sender, mut pause_plotting_receiver) = watch::channel(false);
+/// Plotting thread pools created according to CPU load preference and CPU plotting options
+#[derive(Clone)]
+struct PlottingThreadPools {
+    manager: PlottingThreadPoolManager,
+    /// Number of thread pools, all of them need to be acquired to pause plotting
+    count: usize,
+    record_encoding_concurrency: NonZeroUsize,
+    sector_downloading_concurrency: NonZeroUsize,
+}
 
-    let pause_plotting_actions_fut = async move {
-        let mut thread_pools = Vec::with_capacity(plotting_thread_pools_count);
+impl PlottingThreadPools {
+    fn new(
+        reduce_plotting_cpu_load: bool,
+        plotting_cpu: &PlottingCpuConfiguration,
+    ) -> anyhow::Result<Self> {
+        let (mut plotting_thread_pool_core_indices, replotting_thread_pool_core_indices) =
+            match plotting_cpu.cpu_cores()? {
+                Some(cpu_cores) => cpu_cores,
+                None => {
+                    let plotting_thread_pool_core_indices =
+                        thread_pool_core_indices(plotting_cpu.plotting_thread_pool_size, None);
+                    let mut replotting_thread_pool_core_indices =
+                        thread_pool_core_indices(plotting_cpu.replotting_thread_pool_size, None);
+                    if plotting_cpu.replotting_thread_pool_size.is_none() {
+                        // The default behavior is to use all CPU cores, but for replotting we just
+                        // want half
+                        replotting_thread_pool_core_indices
+                            .iter_mut()
+                            .for_each(|set| set.truncate(set.cpu_cores().len() / 2));
+                    }
 
-        loop {
-            if *pause_plotting_receiver.borrow_and_update() {
-                // Collect all managers so that plotting will be effectively paused
-                if thread_pools.len() < plotting_thread_pools_count {
-                    thread_pools.push(plotting_thread_pool_manager.get_thread_pools().await);
-                    // Allow to un-pause plotting quickly if user requests it
-                    continue;
+                    (
+                        plotting_thread_pool_core_indices,
+                        replotting_thread_pool_core_indices,
+                    )
                 }
-            } else {
-                // Returns all thread pools back to the manager
-                thread_pools.clear();
-            }
-
-            if pause_plotting_receiver.changed().await.is_err() {
-                break;
-            }
+            };
+        if reduce_plotting_cpu_load {
+            plotting_thread_pool_core_indices = replotting_thread_pool_core_indices.clone();
         }
-    };
 
-    let process_actions_fut = async move {
-        while let Some(action) = action_receiver.next().await {
-            match action {
-                FarmerAction::PausePlotting(pause_plotting) => {
-                    if let Err(error) = pause_plotting_sender.send(pause_plotting) {
-                        debug!(%error, "Failed to forward pause plotting");
-                    }
-                }
-            }
+        if plotting_thread_pool_core_indices.len() > 1 {
+            info!(
+                l3_cache_groups = %plotting_thread_pool_core_indices.len(),
+                "Multiple L3 cache groups detected"
+            );
         }
-        anyhow::Ok(())
-    };
 
-    let mut farm_errors = Vec::new();
+        let count = plotting_thread_pool_core_indices.len();
 
-    let farms_fut = {
-        let notifications = Arc::clone(&notifications);
+        let record_encoding_concurrency =
+            plotting_cpu.record_encoding_concurrency.unwrap_or_else(|| {
+                let cpu_cores = plotting_thread_pool_core_indices
+                    .first()
+                    .expect("Guaranteed to have some CPU cores; qed");
 
-        async move {
-            while let Some((farm_index, result)) = farms_stream.next().await {
-                match result {
-                    Ok(()) => {
-                        info!(%farm_index, "Farm exited successfully");
-                    }
-                    Err(error) => {
-                        error!(%farm_index, %error, "Farm exited with error");
-
-                        let error = Arc::new(error);
-
-                        farm_errors.push(AsyncJoinOnDrop::new(
-                            tokio::spawn({
-                                let error = Arc::clone(&error);
-
-                                async move {
-                                    loop {
-                                        tokio::time::sleep(FARM_ERROR_PRINT_INTERVAL).await;
-
-                                        error!(
-                                            %farm_index,
-                                            %error,
-                                            "Farm errored and stopped"
-                                        );
-                                    }
-                                }
-                            }),
-                            true,
-                        ));
-
-                        notifications
-                            .call_simple(&FarmerNotification::FarmError { farm_index, error });
-                    }
-                }
-            }
+                NonZeroUsize::new((cpu_cores.cpu_cores().len() / 2).min(8))
+                    .unwrap_or(NonZeroUsize::MIN)
+            });
+        let sector_downloading_concurrency = plotting_cpu
+            .sector_downloading_concurrency
+            .unwrap_or_else(|| NonZeroUsize::MIN.saturating_add(count));
+
+        info!(
+            ?plotting_thread_pool_core_indices,
+            ?replotting_thread_pool_core_indices,
+            "Preparing plotting thread pools"
+        );
+
+        let manager = create_plotting_thread_pool_manager(
+            plotting_thread_pool_core_indices
+                .into_iter()
+                .zip(replotting_thread_pool_core_indices),
+            plotting_cpu.thread_priority.into(),
+        )?;
+
+        Ok(Self {
+            manager,
+            count,
+            record_encoding_concurrency,
+            sector_downloading_concurrency,
+        })
+    }
+}
+
+fn create_cpu_plotter<FarmIndex>(
+    piece_getter: FarmerPieceGetter<
+        FarmIndex,
+        SegmentCommitmentPieceValidator<MaybeNodeClient>,
+        MaybeNodeClient,
+    >,
+    plotting_thread_pools: &PlottingThreadPools,
+    global_mutex: Arc<AsyncMutex<()>>,
+    kzg: Kzg,
+    erasure_coding: ErasureCoding,
+) -> Arc<dyn Plotter + Send + Sync>
+where
+    FarmIndex: Hash + Eq + Copy + fmt::Debug + Send + Sync + 'static,
+    usize: From<FarmIndex>,
+{
+    Arc::new(CpuPlotter::<_, PosTable>::new(
+        piece_getter,
+        Arc::new(Semaphore::new(
+            plotting_thread_pools.sector_downloading_concurrency.get(),
+        )),
+        plotting_thread_pools.manager.clone(),
+        plotting_thread_pools.record_encoding_concurrency,
+        global_mutex,
+        kzg,
+        erasure_coding,
+        None,
+    ))
+}
 
-            pending::<()>().await;
+/// Pauses plotting by holding all plotting thread pools while plotting is paused
+async fn pause_plotting(
+    mut pause_plotting_receiver: watch::Receiver<bool>,
+    mut plotting_thread_pools_receiver: watch::Receiver<PlottingThreadPools>,
+) {
+    let mut thread_pools = Vec::new();
+
+    loop {
+        if plotting_thread_pools_receiver
+            .has_changed()
+            .unwrap_or_default()
+        {
+            // Thread pools were re-created, the ones collected so far belong to the old manager
+            thread_pools.clear();
+        }
+        let plotting_thread_pools = plotting_thread_pools_receiver.borrow_and_update().clone();
+
+        if *pause_plotting_receiver.borrow_and_update() {
+            // Collect all managers so that plotting will be effectively paused
+            if thread_pools.len() < plotting_thread_pools.count {
+                thread_pools.push(plotting_thread_pools.manager.get_thread_pools().await);
+                // Allow to un-pause plotting quickly if user requests it
+                continue;
+            }
+        } else {
+            // Returns all thread pools back to the manager
+            thread_pools.clear();
         }
-    };
 
-    let farmer_fut = Box::pin(
-        async move {
-            select! {
-                _ = pause_plotting_actions_fut.fuse() => {
-                    Ok(())
-                }
-                _ = process_actions_fut.fuse() => {
-                    Ok(())
+        select! {
+            result = pause_plotting_receiver.changed().fuse() => {
+                if result.is_err() {
+                    break;
                 }
-                _ = farms_fut.fuse() => {
-                    Ok(())
+            }
+            result = plotting_thread_pools_receiver.changed().fuse() => {
+                if result.is_err() {
+                    break;
                 }
             }
         }
-        .in_current_span(),
+    }
+}
+
+fn log_farm_info(farm_index: usize, farm: &SingleDiskFarm, disk_farm: &DiskFarm) {
+    let info = farm.info();
+    info!("Farm {farm_index}:");
+    info!("  ID: {}", info.id());
+    info!("  Genesis hash: 0x{}", hex::encode(info.genesis_hash()));
+    info!("  Public key: 0x{}", hex::encode(info.public_key()));
+    info!(
+        "  Allocated space: {} ({})",
+        bytesize::to_string(info.allocated_space(), true),
+        bytesize::to_string(info.allocated_space(), false)
     );
+    info!("  Directory: {}", disk_farm.directory.display());
+}
 
-    anyhow::Ok(Farmer {
-        farmer_fut,
-        farmer_cache_worker_fut,
-        initial_farm_states,
-        notifications,
-        action_sender,
+/// Check whether existing farm will be resized when opened
+fn farm_resize(disk_farm: &DiskFarm) -> Option<FarmResize> {
+    let info = SingleDiskFarmInfo::load_from(&disk_farm.directory)
+        .ok()
+        .flatten()?;
+
+    (info.allocated_space() != disk_farm.allocated_space).then_some(FarmResize {
+        old_allocated_space: info.allocated_space(),
+        new_allocated_space: disk_farm.allocated_space,
     })
 }
+
+/// Periodically reminds about the error farm has stopped with
+fn farm_error_printer<FarmIndex>(
+    farm_index: FarmIndex,
+    error: Arc<anyhow::Error>,
+) -> AsyncJoinOnDrop<()>
+where
+    FarmIndex: fmt::Display + Send + 'static,
+{
+    AsyncJoinOnDrop::new(
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(FARM_ERROR_PRINT_INTERVAL).await;
+
+                error!(
+                    %farm_index,
+                    %error,
+                    "Farm errored and stopped"
+                );
+            }
+        }),
+        true,
+    )
+}
+
+/// Farm exit reported by its background task
+struct FarmExit<FarmIndex> {
+    farm_index: FarmIndex,
+    run_id: u64,
+    result: anyhow::Result<()>,
+}
+
+/// Farm integrity check result reported by its background task
+struct ScrubResult<FarmIndex> {
+    farm_index: FarmIndex,
+    result: anyhow::Result<ScrubSummary>,
+}
+
+/// Farm that is stopped for integrity check
+struct ScrubbingFarm {
+    directory: PathBuf,
+    /// Farm to start after check, `None` if farm was removed from configuration in the meantime
+    disk_farm: Option<DiskFarm>,
+}
+
+/// Farm that exited with error and is stopped until it is restarted
+struct RestartingFarm {
+    disk_farm: DiskFarm,
+    /// Restart attempt, starting from `1`
+    attempt: u32,
+    /// Waits for restart delay, or periodically reminds about the error once farm is not
+    /// restarted anymore
+    _task: AsyncJoinOnDrop<()>,
+}
+
+/// Why farm is being started, determines what happens once it was created
+enum FarmStartReason {
+    /// Farm is started by configuration update with this ID
+    Update { update_id: u64 },
+    /// Farm is started again after it exited with error
+    Restart { attempt: u32 },
+    /// Farm is started again after integrity check, `step` is reported once farm is started
+    Scrub { step: ScrubStep },
+}
+
+/// Farm that is being created in a background task, which takes a while for new or resized farms
+struct StartingFarm {
+    /// Configuration farm is being created with
+    disk_farm: DiskFarm,
+    /// Plotter farm is being created with
+    plotter: Arc<dyn Plotter + Send + Sync>,
+    /// Configuration to start farm with once created, `None` if farm was removed from
+    /// configuration in the meantime
+    latest_disk_farm: Option<DiskFarm>,
+    reason: FarmStartReason,
+    _task: AsyncJoinOnDrop<()>,
+}
+
+/// Farm creation result reported by its background task
+struct FarmStart<FarmIndex> {
+    farm_index: FarmIndex,
+    result: anyhow::Result<(SingleDiskFarm, Option<RegistryGuard>)>,
+}
+
+/// Configuration update that reports its result once all of its farms were started
+struct PendingFarmsUpdate {
+    /// Number of farms that are still being started
+    remaining: usize,
+    errors: Vec<String>,
+    result_sender: oneshot::Sender<anyhow::Result<()>>,
+}
+
+/// Everything necessary to create a farm, such that it can be done in a background task
+#[derive(Clone)]
+struct FarmCreator<FarmIndex> {
+    reward_address: PublicKey,
+    node_client: MaybeNodeClient,
+    kzg: Kzg,
+    erasure_coding: ErasureCoding,
+    global_mutex: Arc<AsyncMutex<()>>,
+    plotter: Arc<dyn Plotter + Send + Sync>,
+    paused_farms_receiver: watch::Receiver<HashSet<FarmIndex>>,
+    metrics_registries: Option<MetricsRegistries>,
+}
+
+impl<FarmIndex> FarmCreator<FarmIndex>
+where
+    FarmIndex: Hash + Eq + Copy + fmt::Debug + TryFrom<usize> + Send + Sync + 'static,
+    usize: From<FarmIndex>,
+{
+    async fn create(
+        &self,
+        farm_index: usize,
+        disk_farm: &DiskFarm,
+        farmer_app_info: FarmerAppInfo,
+        plotting_delay: Option<oneshot::Receiver<()>>,
+        on_farm_resize: impl Fn(FarmResize, f32),
+    ) -> anyhow::Result<(SingleDiskFarm, Option<RegistryGuard>)> {
+        // Directory of custom chain is nested in configured farm directory
+        if !disk_farm.directory.exists()
+            && let Err(error) = fs::create_dir_all(&disk_farm.directory)
+        {
+            return Err(anyhow!(
+                "Directory {} doesn't exist and can't be created: {}",
+                disk_farm.directory.display(),
+                error
+            ));
+        }
+
+        let max_pieces_in_sector = farmer_app_info.protocol_info.max_pieces_in_sector;
+        let plotter = PausablePlotter::new(
+            FarmIndex::try_from(farm_index).map_err(|_error| {
+                anyhow!("More than 256 plots are not supported by Space Acres")
+            })?,
+            Arc::clone(&self.plotter),
+            self.paused_farms_receiver.clone(),
+        );
+
+        // Each farm gets its own registry, such that its metrics can be removed together with farm
+        let mut farm_registry = self.metrics_registries.is_some().then(Registry::default);
+        let farm_registry_mutex = farm_registry.as_mut().map(AsyncMutex::new);
+
+        let farm_fut = SingleDiskFarm::new::<_, PosTable>(
+            SingleDiskFarmOptions {
+                directory: disk_farm.directory.clone(),
+                farmer_app_info,
+                allocated_space: disk_farm.allocated_space,
+                max_pieces_in_sector,
+                node_client: self.node_client.clone(),
+                reward_address: disk_farm.reward_address.unwrap_or(self.reward_address),
+                plotter: Arc::new(plotter),
+                kzg: self.kzg.clone(),
+                erasure_coding: self.erasure_coding.clone(),
+                cache_percentage: disk_farm.cache_percentage.get(),
+                farming_thread_pool_size: disk_farm.farming_thread_pool_size,
+                plotting_delay,
+                global_mutex: Arc::clone(&self.global_mutex),
+                max_plotting_sectors_per_farm: disk_farm.max_plotting_sectors,
+                disable_farm_locking: false,
+                read_sector_record_chunks_mode: disk_farm.read_sector_record_chunks_mode,
+                registry: farm_registry_mutex.as_ref(),
+                create: true,
+            },
+            farm_index,
+        );
+
+        let farm_result = match farm_resize(disk_farm) {
+            Some(farm_resize) => {
+                info!(
+                    %farm_index,
+                    "Resizing farm from {} to {}, estimated to take up to {}s",
+                    bytesize::to_string(farm_resize.old_allocated_space, true),
+                    bytesize::to_string(farm_resize.new_allocated_space, true),
+                    farm_resize.estimated_duration().as_secs()
+                );
+
+                let started_at = Instant::now();
+                let mut farm_fut = pin!(farm_fut);
+                loop {
+                    on_farm_resize(
+                        farm_resize,
+                        farm_resize.estimated_progress(started_at.elapsed()),
+                    );
+
+                    if let Ok(farm_result) =
+                        tokio::time::timeout(FARM_RESIZE_PROGRESS_INTERVAL, farm_fut.as_mut()).await
+                    {
+                        if farm_result.is_ok() {
+                            on_farm_resize(farm_resize, 100.0);
+                        }
+                        break farm_result;
+                    }
+                }
+            }
+            None => farm_fut.await,
+        };
+        drop(farm_registry_mutex);
+
+        match farm_result {
+            Ok(farm) => {
+                let metrics_registry_guard =
+                    self.metrics_registries.as_ref().zip(farm_registry).map(
+                        |(metrics_registries, farm_registry)| metrics_registries.add(farm_registry),
+                    );
+
+                Ok((farm, metrics_registry_guard))
+            }
+            Err(SingleDiskFarmError::InsufficientAllocatedSpace {
+                min_space,
+                allocated_space,
+            }) => Err(anyhow::anyhow!(
+                "Allocated space {} ({}) is not enough, minimum is ~{} (~{}, {} bytes to be exact)",
+                bytesize::to_string(allocated_space, true),
+                bytesize::to_string(allocated_space, false),
+                bytesize::to_string(min_space, true),
+                bytesize::to_string(min_space, false),
+                min_space
+            )),
+            Err(error) => Err(error.into()),
+        }
+    }
+}
+
+/// Farm managed by the farmer
+struct RunningFarm {
+    disk_farm: DiskFarm,
+    piece_cache: Arc<dyn PieceCache>,
+    plot_cache: Arc<dyn PlotCache>,
+    /// Identifier of the latest run, used to ignore exits of farms that were already replaced
+    run_id: u64,
+    /// Task running the farm, `None` before farm is started and after it has exited
+    run_task: Option<JoinHandle<()>>,
+    /// When the latest run has started
+    started_at: Instant,
+    /// Automatic restart attempt the latest run was started by, `0` if it wasn't restarted
+    restart_attempt: u32,
+    /// Periodically reminds about the error farm has stopped with
+    _error_printer: Option<AsyncJoinOnDrop<()>>,
+    /// Farm metrics are exposed while farm is running
+    _metrics_registry_guard: Option<RegistryGuard>,
+}
+
+impl Drop for RunningFarm {
+    fn drop(&mut self) {
+        if let Some(run_task) = &self.run_task {
+            run_task.abort();
+        }
+    }
+}
+
+/// Keeps track of running farms and everything necessary to add or replace farms at runtime
+struct FarmsManager<FarmIndex> {
+    reward_address: PublicKey,
+    node_client: MaybeNodeClient,
+    piece_getter: FarmerPieceGetter<
+        FarmIndex,
+        SegmentCommitmentPieceValidator<MaybeNodeClient>,
+        MaybeNodeClient,
+    >,
+    kzg: Kzg,
+    erasure_coding: ErasureCoding,
+    global_mutex: Arc<AsyncMutex<()>>,
+    plotter: Arc<dyn Plotter + Send + Sync>,
+    /// Whether CPU plotter is used, in which case plotter depends on CPU load preference
+    cpu_plotting: bool,
+    reduce_plotting_cpu_load: bool,
+    plotting_cpu: PlottingCpuConfiguration,
+    plotting_thread_pools_sender: watch::Sender<PlottingThreadPools>,
+    /// Farms that have plotting paused individually
+    paused_farms_sender: watch::Sender<HashSet<FarmIndex>>,
+    plotted_pieces: Arc<AsyncRwLock<PlottedPieces<FarmIndex>>>,
+    farmer_cache: FarmerCache,
+    notifications: Arc<Notifications<FarmIndex>>,
+    metrics_registries: Option<MetricsRegistries>,
+    farms: BTreeMap<FarmIndex, RunningFarm>,
+    farm_exits_sender: mpsc::UnboundedSender<FarmExit<FarmIndex>>,
+    scrubbing_farms: HashMap<FarmIndex, ScrubbingFarm>,
+    scrub_results_sender: mpsc::UnboundedSender<ScrubResult<FarmIndex>>,
+    restarting_farms: HashMap<FarmIndex, RestartingFarm>,
+    farm_restarts_sender: mpsc::UnboundedSender<FarmIndex>,
+    starting_farms: HashMap<FarmIndex, StartingFarm>,
+    farm_starts_sender: mpsc::UnboundedSender<FarmStart<FarmIndex>>,
+    pending_updates: HashMap<u64, PendingFarmsUpdate>,
+    next_update_id: u64,
+    next_run_id: u64,
+}
+
+impl<FarmIndex> FarmsManager<FarmIndex>
+where
+    FarmIndex:
+        Hash + Eq + Ord + Copy + fmt::Display + fmt::Debug + TryFrom<usize> + Send + Sync + 'static,
+    usize: From<FarmIndex>,
+{
+    fn farm_creator(&self) -> FarmCreator<FarmIndex> {
+        FarmCreator {
+            reward_address: self.reward_address,
+            node_client: self.node_client.clone(),
+            kzg: self.kzg.clone(),
+            erasure_coding: self.erasure_coding.clone(),
+            global_mutex: Arc::clone(&self.global_mutex),
+            plotter: Arc::clone(&self.plotter),
+            paused_farms_receiver: self.paused_farms_sender.subscribe(),
+            metrics_registries: self.metrics_registries.clone(),
+        }
+    }
+
+    /// Registers farm's plotted pieces and event handlers, farm needs to be spawned afterward
+    async fn register_farm(
+        &mut self,
+        farm_index: FarmIndex,
+        disk_farm: DiskFarm,
+        farm: &SingleDiskFarm,
+        metrics_registry_guard: Option<RegistryGuard>,
+    ) -> anyhow::Result<InitialFarmState> {
+        let total_sectors_count = farm.total_sectors_count();
+        let allocated_space = farm.info().allocated_space();
+        let cache_percentage = disk_farm.cache_percentage.get();
+        let mut plotted_sectors_count = 0;
+
+        {
+            let mut plotted_pieces = self.plotted_pieces.write().await;
+
+            plotted_pieces.add_farm(farm_index, Arc::new(farm.piece_reader()));
+
+            let result: anyhow::Result<()> = try {
+                let plotted_sectors = farm.plotted_sectors();
+                let mut plotted_sectors = plotted_sectors.get().await.map_err(|error| {
+                    anyhow!("Failed to get plotted sectors for farm {farm_index}: {error}")
+                })?;
+
+                while let Some(plotted_sector_result) = plotted_sectors.next().await {
+                    plotted_sectors_count += 1;
+                    plotted_pieces.add_sector(
+                        farm_index,
+                        &plotted_sector_result.map_err(|error| {
+                            anyhow!(
+                                "Failed reading plotted sector on startup for farm \
+                                {farm_index}: {error}"
+                            )
+                        })?,
+                    )
+                }
+            };
+
+            if let Err(error) = result {
+                plotted_pieces.delete_farm(farm_index);
+                return Err(error);
+            }
+        }
+
+        let span = info_span!("farm", %farm_index);
+
+        farm.on_sector_update(Arc::new({
+            let notifications = Arc::clone(&self.notifications);
+
+            move |(sector_index, sector_update)| {
+                notifications.call_simple(&FarmerNotification::SectorUpdate {
+                    farm_index,
+                    sector_index: *sector_index,
+                    update: sector_update.clone(),
+                });
+            }
+        }))
+        .detach();
+        farm.on_farming_notification(Arc::new({
+            let notifications = Arc::clone(&self.notifications);
+
+            move |notification| {
+                notifications.call_simple(&FarmerNotification::FarmingNotification {
+                    farm_index,
+                    notification: notification.clone(),
+                });
+            }
+        }))
+        .detach();
+
+        // Collect newly plotted pieces
+        let on_plotted_sector_callback = {
+            let plotted_pieces = Arc::clone(&self.plotted_pieces);
+
+            move |plotted_sector: &PlottedSector,
+                  maybe_old_plotted_sector: &Option<PlottedSector>| {
+                let _span_guard = span.enter();
+
+                {
+                    let mut plotted_pieces = plotted_pieces.write_blocking();
+
+                    if let Some(old_plotted_sector) = &maybe_old_plotted_sector {
+                        plotted_pieces.delete_sector(farm_index, old_plotted_sector);
+                    }
+                    plotted_pieces.add_sector(farm_index, plotted_sector);
+                }
+            }
+        };
+        farm.on_sector_update(Arc::new(move |(_sector_index, sector_state)| {
+            if let SectorUpdate::Plotting(SectorPlottingDetails::Finished {
+                plotted_sector,
+                old_plotted_sector,
+                ..
+            }) = sector_state
+            {
+                on_plotted_sector_callback(plotted_sector, old_plotted_sector);
+            }
+        }))
+        .detach();
+
+        self.farms.insert(
+            farm_index,
+            RunningFarm {
+                disk_farm,
+                piece_cache: Arc::new(farm.piece_cache()),
+                plot_cache: Arc::new(farm.plot_cache()),
+                run_id: 0,
+                run_task: None,
+                started_at: Instant::now(),
+                restart_attempt: 0,
+                _error_printer: None,
+                _metrics_registry_guard: metrics_registry_guard,
+            },
+        );
+
+        Ok(InitialFarmState {
+            total_sectors_count,
+            plotted_sectors_count,
+            allocated_space,
+            cache_percentage,
+        })
+    }
+
+    /// Starts previously registered farm in a background task
+    fn spawn_farm(&mut self, farm_index: FarmIndex, farm: SingleDiskFarm) {
+        let Some(running_farm) = self.farms.get_mut(&farm_index) else {
+            warn!(%farm_index, "Farm is not registered, not starting");
+            return;
+        };
+
+        let run_id = self.next_run_id;
+        self.next_run_id += 1;
+
+        let run_task = tokio::spawn({
+            let farm_exits_sender = self.farm_exits_sender.clone();
+
+            async move {
+                let result = farm.run().await;
+                // Receiver is only gone when farmer is shutting down
+                let _ = farm_exits_sender.unbounded_send(FarmExit {
+                    farm_index,
+                    run_id,
+                    result,
+                });
+            }
+            .instrument(info_span!("", %farm_index))
+        });
+
+        running_farm.run_id = run_id;
+        running_farm.run_task = Some(run_task);
+        running_farm.started_at = Instant::now();
+        running_farm._error_printer = None;
+    }
+
+    async fn process_farm_exit(&mut self, farm_exit: FarmExit<FarmIndex>) {
+        let FarmExit {
+            farm_index,
+            run_id,
+            result,
+        } = farm_exit;
+
+        let Some(running_farm) = self.farms.get_mut(&farm_index) else {
+            return;
+        };
+        if running_farm.run_id != run_id {
+            // Farm was replaced since then
+            return;
+        }
+        running_farm.run_task.take();
+
+        let error = match result {
+            Ok(()) => {
+                info!(%farm_index, "Farm exited successfully");
+                return;
+            }
+            Err(error) => {
+                error!(%farm_index, %error, "Farm exited with error");
+                Arc::new(error)
+            }
+        };
+
+        let attempt = if running_farm.started_at.elapsed() >= FARM_RESTART_RESET_INTERVAL {
+            1
+        } else {
+            running_farm.restart_attempt + 1
+        };
+
+        if attempt > FARM_MAX_RESTART_ATTEMPTS {
+            error!(%farm_index, "Farm failed too many times, not restarting it anymore");
+
+            running_farm._error_printer = Some(farm_error_printer(farm_index, Arc::clone(&error)));
+
+            self.notifications
+                .call_simple(&FarmerNotification::FarmError { farm_index, error });
+            return;
+        }
+
+        let running_farm = self
+            .farms
+            .remove(&farm_index)
+            .expect("Farm was just found above; qed");
+        let disk_farm = running_farm.disk_farm.clone();
+
+        // Stop using caches of farm that is about to be restarted
+        self.replace_backing_caches().await;
+        self.shut_down_farm(farm_index, running_farm).await;
+
+        self.schedule_farm_restart(farm_index, disk_farm, attempt, error);
+    }
+
+    /// Schedules restart of farm that exited with error and was already stopped, farm is started
+    /// again in [`Self::restart_farm()`]
+    fn schedule_farm_restart(
+        &mut self,
+        farm_index: FarmIndex,
+        disk_farm: DiskFarm,
+        attempt: u32,
+        error: Arc<anyhow::Error>,
+    ) {
+        let delay = FARM_RESTART_INITIAL_DELAY
+            .saturating_mul(2_u32.saturating_pow(attempt - 1))
+            .min(FARM_RESTART_MAX_DELAY);
+
+        info!(
+            %farm_index,
+            %attempt,
+            max_attempts = %FARM_MAX_RESTART_ATTEMPTS,
+            ?delay,
+            "Farm will be restarted after delay"
+        );
+
+        let restart_timer = AsyncJoinOnDrop::new(
+            tokio::spawn({
+                let farm_restarts_sender = self.farm_restarts_sender.clone();
+
+                async move {
+                    tokio::time::sleep(delay).await;
+                    // Receiver is only gone when farmer is shutting down
+                    let _ = farm_restarts_sender.unbounded_send(farm_index);
+                }
+            }),
+            true,
+        );
+
+        self.restarting_farms.insert(
+            farm_index,
+            RestartingFarm {
+                disk_farm,
+                attempt,
+                _task: restart_timer,
+            },
+        );
+
+        self.notifications
+            .call_simple(&FarmerNotification::FarmRestart {
+                farm_index,
+                step: FarmRestartStep::Scheduled {
+                    error,
+                    attempt,
+                    max_attempts: FARM_MAX_RESTART_ATTEMPTS,
+                    delay,
+                },
+            });
+    }
+
+    /// Starts farm that exited with error again once restart delay has passed, farm that fails to
+    /// start is scheduled to be restarted again until it runs out of attempts
+    fn restart_farm(&mut self, farm_index: FarmIndex) {
+        let Some(restarting_farm) = self.restarting_farms.remove(&farm_index) else {
+            // Farm was started or removed by configuration update in the meantime
+            return;
+        };
+        let RestartingFarm {
+            disk_farm, attempt, ..
+        } = restarting_farm;
+
+        info!(%farm_index, %attempt, "Restarting farm");
+
+        self.notifications
+            .call_simple(&FarmerNotification::FarmRestart {
+                farm_index,
+                step: FarmRestartStep::Restarting {
+                    attempt,
+                    max_attempts: FARM_MAX_RESTART_ATTEMPTS,
+                },
+            });
+
+        self.start_farm(farm_index, disk_farm, FarmStartReason::Restart { attempt });
+    }
+
+    /// Stops farm that was already removed from the list of farms and waits for it to exit
+    async fn stop_farm(&self, farm_index: FarmIndex, running_farm: RunningFarm) {
+        self.shut_down_farm(farm_index, running_farm).await;
+
+        self.notifications
+            .call_simple(&FarmerNotification::FarmRemoved { farm_index });
+    }
+
+    /// Same as [`Self::stop_farm()`], but without notifying about farm removal
+    async fn shut_down_farm(&self, farm_index: FarmIndex, mut running_farm: RunningFarm) {
+        if let Some(run_task) = running_farm.run_task.take() {
+            run_task.abort();
+            // Wait for farm to be dropped, such that its directory can be opened again right away
+            let _ = run_task.await;
+        }
+
+        self.plotted_pieces.write().await.delete_farm(farm_index);
+
+        info!(
+            %farm_index,
+            directory = %running_farm.disk_farm.directory.display(),
+            "Farm stopped"
+        );
+    }
+
+    /// Plotting pause is preserved while farm is restarted with the same index, it is forgotten
+    /// once index is released, such that a different farm that gets it later is not paused
+    fn forget_farm_plotting_pause(&self, farm_index: FarmIndex) {
+        self.paused_farms_sender
+            .send_if_modified(|paused_farms| paused_farms.remove(&farm_index));
+    }
+
+    fn pause_farm_plotting(&self, farm_index: FarmIndex, paused: bool) {
+        if !self.farms.contains_key(&farm_index) && !self.starting_farms.contains_key(&farm_index) {
+            warn!(%farm_index, "Farm is not registered, can't pause plotting");
+            return;
+        }
+
+        let modified = self.paused_farms_sender.send_if_modified(|paused_farms| {
+            if paused {
+                paused_farms.insert(farm_index)
+            } else {
+                paused_farms.remove(&farm_index)
+            }
+        });
+
+        if modified {
+            if paused {
+                info!(%farm_index, "Farm plotting paused");
+            } else {
+                info!(%farm_index, "Farm plotting resumed");
+            }
+        }
+    }
+
+    async fn replace_backing_caches(&self) {
+        let plot_cache = !cfg!(windows)
+            || self
+                .farms
+                .values()
+                .map(|running_farm| running_farm.disk_farm.allocated_space)
+                .sum::<u64>()
+                <= MAX_SPACE_PLEDGED_FOR_PLOT_CACHE_ON_WINDOWS;
+
+        self.farmer_cache
+            .replace_backing_caches(
+                self.farms
+                    .values()
+                    .map(|running_farm| Arc::clone(&running_farm.piece_cache))
+                    .collect(),
+                if plot_cache {
+                    self.farms
+                        .values()
+                        .map(|running_farm| Arc::clone(&running_farm.plot_cache))
+                        .collect()
+                } else {
+                    Vec::new()
+                },
+            )
+            .await;
+    }
+
+    /// Smallest farm index that is not used yet and not in `reserved`
+    fn next_free_farm_index(&self, reserved: &[FarmIndex]) -> Option<FarmIndex> {
+        (0..)
+            .map_while(|farm_index: usize| FarmIndex::try_from(farm_index).ok())
+            .find(|farm_index| {
+                !self.farms.contains_key(farm_index)
+                    && !self.scrubbing_farms.contains_key(farm_index)
+                    && !self.restarting_farms.contains_key(farm_index)
+                    && !self.starting_farms.contains_key(farm_index)
+                    && !reserved.contains(farm_index)
+            })
+    }
+
+    /// Stops farm and checks its integrity in a background task, farm is started again in
+    /// [`Self::finish_scrub()`]
+    async fn scrub_farm(&mut self, farm_index: FarmIndex, dry_run: bool) {
+        let Some(running_farm) = self.farms.remove(&farm_index) else {
+            warn!(%farm_index, "Farm is not registered, can't check it");
+            return;
+        };
+
+        let notifications = Arc::clone(&self.notifications);
+        let on_step = move |step: ScrubStep| {
+            notifications.call_simple(&FarmerNotification::FarmScrub { farm_index, step });
+        };
+
+        on_step(ScrubStep::StoppingFarm);
+        // Stop using caches of farm that is about to be stopped
+        self.replace_backing_caches().await;
+
+        let directory = running_farm.disk_farm.directory.clone();
+        self.scrubbing_farms.insert(
+            farm_index,
+            ScrubbingFarm {
+                directory: directory.clone(),
+                disk_farm: Some(running_farm.disk_farm.clone()),
+            },
+        );
+        self.shut_down_farm(farm_index, running_farm).await;
+
+        info!(%farm_index, directory = %directory.display(), %dry_run, "Checking farm");
+
+        tokio::spawn({
+            let scrub_results_sender = self.scrub_results_sender.clone();
+
+            async move {
+                let result = scrub::scrub_farm(directory, dry_run, on_step).await;
+                // Receiver is only gone when farmer is shutting down
+                let _ = scrub_results_sender.unbounded_send(ScrubResult { farm_index, result });
+            }
+            .instrument(info_span!("", %farm_index))
+        });
+    }
+
+    /// Reports farm integrity check result and starts farm again, result is reported once farm is
+    /// started
+    fn finish_scrub(&mut self, scrub_result: ScrubResult<FarmIndex>) {
+        let ScrubResult { farm_index, result } = scrub_result;
+
+        let Some(scrubbing_farm) = self.scrubbing_farms.remove(&farm_index) else {
+            return;
+        };
+
+        let step = match result {
+            Ok(summary) => {
+                info!(
+                    %farm_index,
+                    dry_run = %summary.dry_run,
+                    plot_issues = %summary.plot_issues.len(),
+                    cache_issues = %summary.cache_issues.len(),
+                    "Farm check finished"
+                );
+
+                ScrubStep::Finished { summary }
+            }
+            Err(error) => {
+                error!(%farm_index, %error, "Farm check failed");
+
+                ScrubStep::Failed {
+                    error: Arc::new(error),
+                }
+            }
+        };
+
+        let Some(disk_farm) = scrubbing_farm.disk_farm else {
+            info!(%farm_index, "Farm was removed from configuration while being checked");
+
+            self.forget_farm_plotting_pause(farm_index);
+            self.notifications
+                .call_simple(&FarmerNotification::FarmRemoved { farm_index });
+            return;
+        };
+
+        self.notifications
+            .call_simple(&FarmerNotification::FarmScrub {
+                farm_index,
+                step: ScrubStep::StartingFarm,
+            });
+
+        self.start_farm(farm_index, disk_farm, FarmStartReason::Scrub { step });
+    }
+
+    /// Brings farms in line with the new configuration.
+    ///
+    /// Farms are matched by their configuration, farms that are no longer present or changed are
+    /// stopped, new or changed farms are started. Farms that exited previously are restarted.
+    /// Farms are started in background tasks, result is sent to `result_sender` once all of them
+    /// were started.
+    async fn update_farms(
+        &mut self,
+        disk_farms: Vec<DiskFarm>,
+        reduce_plotting_cpu_load: bool,
+        plotting_cpu: PlottingCpuConfiguration,
+        result_sender: oneshot::Sender<anyhow::Result<()>>,
+    ) {
+        let mut restart_all_farms = false;
+        if reduce_plotting_cpu_load != self.reduce_plotting_cpu_load
+            || plotting_cpu != self.plotting_cpu
+        {
+            let plotting_thread_pools =
+                match PlottingThreadPools::new(reduce_plotting_cpu_load, &plotting_cpu) {
+                    Ok(plotting_thread_pools) => plotting_thread_pools,
+                    Err(error) => {
+                        // Doesn't matter if receiver is gone
+                        let _ = result_sender.send(Err(error));
+                        return;
+                    }
+                };
+
+            if self.cpu_plotting {
+                self.plotter = create_cpu_plotter(
+                    self.piece_getter.clone(),
+                    &plotting_thread_pools,
+                    Arc::clone(&self.global_mutex),
+                    self.kzg.clone(),
+                    self.erasure_coding.clone(),
+                );
+                // Farms keep plotter they were created with, restart them to use the new one
+                restart_all_farms = true;
+            }
+
+            self.plotting_thread_pools_sender
+                .send_replace(plotting_thread_pools);
+            self.reduce_plotting_cpu_load = reduce_plotting_cpu_load;
+            self.plotting_cpu = plotting_cpu;
+        }
+
+        let farms_to_stop = self
+            .farms
+            .iter()
+            .filter(|(_farm_index, running_farm)| {
+                restart_all_farms
+                    || running_farm.run_task.is_none()
+                    || !disk_farms.contains(&running_farm.disk_farm)
+            })
+            .map(|(farm_index, _running_farm)| *farm_index)
+            .collect::<Vec<_>>();
+        // Farms that are being checked are started with the latest configuration afterward
+        for scrubbing_farm in self.scrubbing_farms.values_mut() {
+            scrubbing_farm.disk_farm = disk_farms
+                .iter()
+                .find(|disk_farm| disk_farm.directory == scrubbing_farm.directory)
+                .cloned();
+        }
+        // Farms that are being started are started again with the latest configuration afterward
+        for starting_farm in self.starting_farms.values_mut() {
+            starting_farm.latest_disk_farm = disk_farms
+                .iter()
+                .find(|disk_farm| disk_farm.directory == starting_farm.disk_farm.directory)
+                .cloned();
+        }
+        let farms_to_start = disk_farms
+            .into_iter()
+            .filter(|disk_farm| {
+                !self.farms.iter().any(|(farm_index, running_farm)| {
+                    !farms_to_stop.contains(farm_index) && running_farm.disk_farm == *disk_farm
+                }) && !self
+                    .scrubbing_farms
+                    .values()
+                    .any(|scrubbing_farm| scrubbing_farm.directory == disk_farm.directory)
+                    && !self.starting_farms.values().any(|starting_farm| {
+                        starting_farm.disk_farm.directory == disk_farm.directory
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        let stopped_farms = farms_to_stop
+            .into_iter()
+            .filter_map(|farm_index| Some((farm_index, self.farms.remove(&farm_index)?)))
+            .collect::<Vec<_>>();
+
+        // Farms with the same directory keep their index
+        let mut reused_farm_indices = HashMap::with_capacity(stopped_farms.len());
+
+        // Farms waiting for restart are started right away with the latest configuration instead
+        for (farm_index, restarting_farm) in self.restarting_farms.drain() {
+            if !farms_to_start
+                .iter()
+                .any(|disk_farm| disk_farm.directory == restarting_farm.disk_farm.directory)
+            {
+                info!(%farm_index, "Farm was removed from configuration while waiting for restart");
+
+                self.notifications
+                    .call_simple(&FarmerNotification::FarmRemoved { farm_index });
+            }
+            reused_farm_indices.insert(restarting_farm.disk_farm.directory, farm_index);
+        }
+
+        if !stopped_farms.is_empty() {
+            // Stop using caches of farms that are about to be stopped
+            self.replace_backing_caches().await;
+
+            for (farm_index, running_farm) in stopped_farms {
+                let directory = running_farm.disk_farm.directory.clone();
+                // Farms that are started again keep being displayed while they are opened (and
+                // potentially resized), removal is reported if they fail to start
+                if farms_to_start
+                    .iter()
+                    .any(|disk_farm| disk_farm.directory == directory)
+                {
+                    self.shut_down_farm(farm_index, running_farm).await;
+                } else {
+                    self.stop_farm(farm_index, running_farm).await;
+                }
+                reused_farm_indices.insert(directory, farm_index);
+            }
+        }
+
+        let reserved_farm_indices = farms_to_start
+            .iter()
+            .filter_map(|disk_farm| reused_farm_indices.get(&disk_farm.directory).copied())
+            .collect::<Vec<_>>();
+
+        let update_id = self.next_update_id;
+        self.next_update_id += 1;
+        let mut errors = Vec::new();
+        let mut farms_starting = 0;
+
+        for disk_farm in farms_to_start {
+            let farm_index = match reused_farm_indices
+                .remove(&disk_farm.directory)
+                .or_else(|| self.next_free_farm_index(&reserved_farm_indices))
+            {
+                Some(farm_index) => farm_index,
+                None => {
+                    errors.push(format!(
+                        "{}: more than 256 plots are not supported by Space Acres",
+                        disk_farm.directory.display()
+                    ));
+                    continue;
+                }
+            };
+
+            self.start_farm(farm_index, disk_farm, FarmStartReason::Update { update_id });
+            farms_starting += 1;
+        }
+
+        // Farms that were removed from configuration
+        for farm_index in reused_farm_indices.into_values() {
+            self.forget_farm_plotting_pause(farm_index);
+        }
+
+        if farms_starting == 0 {
+            // Doesn't matter if receiver is gone
+            let _ = result_sender.send(farms_update_result(&errors));
+        } else {
+            self.pending_updates.insert(
+                update_id,
+                PendingFarmsUpdate {
+                    remaining: farms_starting,
+                    errors,
+                    result_sender,
+                },
+            );
+        }
+    }
+
+    /// Creates farm in a background task, since it might take a while for new or resized farms,
+    /// farm is registered and spawned in [`Self::finish_farm_start()`] afterward
+    fn start_farm(&mut self, farm_index: FarmIndex, disk_farm: DiskFarm, reason: FarmStartReason) {
+        let farm_creator = self.farm_creator();
+
+        let task = AsyncJoinOnDrop::new(
+            tokio::spawn({
+                let disk_farm = disk_farm.clone();
+                let farm_starts_sender = self.farm_starts_sender.clone();
+                let notifications = Arc::clone(&self.notifications);
+
+                async move {
+                    let result = async {
+                        let farmer_app_info = farm_creator
+                            .node_client
+                            .farmer_app_info()
+                            .await
+                            .map_err(|error| anyhow::anyhow!(error))?;
+
+                        farm_creator
+                            .create(
+                                usize::from(farm_index),
+                                &disk_farm,
+                                farmer_app_info,
+                                None,
+                                |farm_resize, progress| {
+                                    notifications.call_simple(&FarmerNotification::FarmResize {
+                                        farm_index,
+                                        farm_resize,
+                                        progress,
+                                    });
+                                },
+                            )
+                            .await
+                    }
+                    .await;
+                    // Receiver is only gone when farmer is shutting down
+                    let _ = farm_starts_sender.unbounded_send(FarmStart { farm_index, result });
+                }
+                .instrument(info_span!("", %farm_index))
+            }),
+            true,
+        );
+
+        self.starting_farms.insert(
+            farm_index,
+            StartingFarm {
+                disk_farm: disk_farm.clone(),
+                plotter: Arc::clone(&self.plotter),
+                latest_disk_farm: Some(disk_farm),
+                reason,
+                _task: task,
+            },
+        );
+    }
+
+    /// Registers and spawns farm created by [`Self::start_farm()`] and reports the outcome
+    /// according to the reason farm was started for
+    async fn finish_farm_start(&mut self, farm_start: FarmStart<FarmIndex>) {
+        let FarmStart { farm_index, result } = farm_start;
+
+        let Some(starting_farm) = self.starting_farms.remove(&farm_index) else {
+            return;
+        };
+        let StartingFarm {
+            disk_farm,
+            plotter,
+            latest_disk_farm,
+            reason,
+            ..
+        } = starting_farm;
+
+        let Some(latest_disk_farm) = latest_disk_farm else {
+            info!(%farm_index, "Farm was removed from configuration while being started");
+
+            drop(result);
+            self.forget_farm_plotting_pause(farm_index);
+            self.notifications
+                .call_simple(&FarmerNotification::FarmRemoved { farm_index });
+            if let FarmStartReason::Update { update_id } = reason {
+                self.finish_farms_update_step(update_id, None);
+            }
+            return;
+        };
+
+        if latest_disk_farm != disk_farm || !Arc::ptr_eq(&plotter, &self.plotter) {
+            info!(%farm_index, "Farm configuration changed while being started, starting again");
+
+            // Farm must be closed before it can be opened again
+            drop(result);
+            self.start_farm(farm_index, latest_disk_farm, reason);
+            return;
+        }
+
+        let result = match result {
+            Ok((farm, metrics_registry_guard)) => {
+                self.run_farm(farm_index, disk_farm.clone(), farm, metrics_registry_guard)
+                    .instrument(info_span!("", %farm_index))
+                    .await
+            }
+            Err(error) => Err(error),
+        };
+
+        if result.is_ok() {
+            self.replace_backing_caches().await;
+        }
+
+        match reason {
+            FarmStartReason::Update { update_id } => {
+                let error = result.err().map(|error| {
+                    error!(
+                        %farm_index,
+                        directory = %disk_farm.directory.display(),
+                        %error,
+                        "Failed to start farm"
+                    );
+                    self.forget_farm_plotting_pause(farm_index);
+                    self.notifications
+                        .call_simple(&FarmerNotification::FarmRemoved { farm_index });
+
+                    format!("{}: {error}", disk_farm.directory.display())
+                });
+
+                self.finish_farms_update_step(update_id, error);
+            }
+            FarmStartReason::Restart { attempt } => match result {
+                Ok(()) => {
+                    if let Some(running_farm) = self.farms.get_mut(&farm_index) {
+                        running_farm.restart_attempt = attempt;
+                    }
+                }
+                Err(error) => {
+                    error!(%farm_index, %error, "Failed to restart farm");
+                    let error = Arc::new(error);
+
+                    if attempt < FARM_MAX_RESTART_ATTEMPTS {
+                        self.schedule_farm_restart(farm_index, disk_farm, attempt + 1, error);
+                        return;
+                    }
+
+                    error!(%farm_index, "Farm failed too many times, not restarting it anymore");
+
+                    self.restarting_farms.insert(
+                        farm_index,
+                        RestartingFarm {
+                            disk_farm,
+                            attempt,
+                            _task: farm_error_printer(farm_index, Arc::clone(&error)),
+                        },
+                    );
+
+                    self.notifications
+                        .call_simple(&FarmerNotification::FarmError { farm_index, error });
+                }
+            },
+            FarmStartReason::Scrub { step } => {
+                if let Err(error) = result {
+                    error!(%farm_index, %error, "Failed to start farm after check");
+
+                    self.forget_farm_plotting_pause(farm_index);
+                    self.notifications
+                        .call_simple(&FarmerNotification::FarmError {
+                            farm_index,
+                            error: Arc::new(error),
+                        });
+                }
+
+                // Sent last, such that it reaches farm that was just started
+                self.notifications
+                    .call_simple(&FarmerNotification::FarmScrub { farm_index, step });
+            }
+        }
+    }
+
+    /// Records that one of the farms of configuration update was started (or failed to start with
+    /// `error`), result is reported once all farms of the update were started
+    fn finish_farms_update_step(&mut self, update_id: u64, error: Option<String>) {
+        let Some(pending_update) = self.pending_updates.get_mut(&update_id) else {
+            return;
+        };
+
+        pending_update.errors.extend(error);
+        pending_update.remaining = pending_update.remaining.saturating_sub(1);
+        if pending_update.remaining > 0 {
+            return;
+        }
+
+        let pending_update = self
+            .pending_updates
+            .remove(&update_id)
+            .expect("Pending update was just found above; qed");
+        // Doesn't matter if receiver is gone
+        let _ = pending_update
+            .result_sender
+            .send(farms_update_result(&pending_update.errors));
+    }
+
+    /// Registers farm that was just created and runs it
+    async fn run_farm(
+        &mut self,
+        farm_index: FarmIndex,
+        disk_farm: DiskFarm,
+        farm: SingleDiskFarm,
+        metrics_registry_guard: Option<RegistryGuard>,
+    ) -> anyhow::Result<()> {
+        log_farm_info(usize::from(farm_index), &farm, &disk_farm);
+
+        let initial_farm_state = self
+            .register_farm(farm_index, disk_farm.clone(), &farm, metrics_registry_guard)
+            .await?;
+        self.spawn_farm(farm_index, farm);
+
+        let plotting_paused = self.paused_farms_sender.borrow().contains(&farm_index);
+        self.notifications
+            .call_simple(&FarmerNotification::FarmAdded {
+                farm_index,
+                farm: disk_farm,
+                initial_farm_state,
+                plotting_paused,
+            });
+
+        Ok(())
+    }
+}
+
+fn farms_update_result(errors: &[String]) -> anyhow::Result<()> {
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!("Failed to start farms: {}", errors.join(", ")))
+    }
+}
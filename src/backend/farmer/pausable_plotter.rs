@@ -0,0 +1,112 @@
+use futures::channel::mpsc;
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::Hash;
+use std::sync::Arc;
+use subspace_core_primitives::PublicKey;
+use subspace_core_primitives::sectors::SectorIndex;
+use subspace_farmer::plotter::{Plotter, SectorPlottingProgress};
+use subspace_farmer_components::FarmerProtocolInfo;
+use tokio::sync::watch;
+
+/// Wrapper plotter used by a single farm that allows pausing plotting of individual farms.
+///
+/// Sectors that are already being plotted are not interrupted, new sectors are not started while
+/// farm is paused.
+#[derive(Debug)]
+pub(super) struct PausablePlotter<FarmIndex> {
+    farm_index: FarmIndex,
+    inner: Arc<dyn Plotter + Send + Sync>,
+    paused_farms: watch::Receiver<HashSet<FarmIndex>>,
+}
+
+#[async_trait::async_trait]
+impl<FarmIndex> Plotter for PausablePlotter<FarmIndex>
+where
+    FarmIndex: Hash + Eq + Copy + fmt::Debug + Send + Sync + 'static,
+{
+    async fn has_free_capacity(&self) -> Result<bool, String> {
+        if self.is_paused() {
+            return Ok(false);
+        }
+
+        self.inner.has_free_capacity().await
+    }
+
+    async fn plot_sector(
+        &self,
+        public_key: PublicKey,
+        sector_index: SectorIndex,
+        farmer_protocol_info: FarmerProtocolInfo,
+        pieces_in_sector: u16,
+        replotting: bool,
+        progress_sender: mpsc::Sender<SectorPlottingProgress>,
+    ) {
+        self.wait_for_resume().await;
+
+        self.inner
+            .plot_sector(
+                public_key,
+                sector_index,
+                farmer_protocol_info,
+                pieces_in_sector,
+                replotting,
+                progress_sender,
+            )
+            .await
+    }
+
+    async fn try_plot_sector(
+        &self,
+        public_key: PublicKey,
+        sector_index: SectorIndex,
+        farmer_protocol_info: FarmerProtocolInfo,
+        pieces_in_sector: u16,
+        replotting: bool,
+        progress_sender: mpsc::Sender<SectorPlottingProgress>,
+    ) -> bool {
+        if self.is_paused() {
+            return false;
+        }
+
+        self.inner
+            .try_plot_sector(
+                public_key,
+                sector_index,
+                farmer_protocol_info,
+                pieces_in_sector,
+                replotting,
+                progress_sender,
+            )
+            .await
+    }
+}
+
+impl<FarmIndex> PausablePlotter<FarmIndex>
+where
+    FarmIndex: Hash + Eq + Copy,
+{
+    pub(super) fn new(
+        farm_index: FarmIndex,
+        inner: Arc<dyn Plotter + Send + Sync>,
+        paused_farms: watch::Receiver<HashSet<FarmIndex>>,
+    ) -> Self {
+        Self {
+            farm_index,
+            inner,
+            paused_farms,
+        }
+    }
+
+    fn is_paused(&self) -> bool {
+        self.paused_farms.borrow().contains(&self.farm_index)
+    }
+
+    async fn wait_for_resume(&self) {
+        let mut paused_farms = self.paused_farms.clone();
+        // Error means farmer is shutting down, there is nothing to wait for then
+        let _ = paused_farms
+            .wait_for(|paused_farms| !paused_farms.contains(&self.farm_index))
+            .await;
+    }
+}
@@ -0,0 +1,186 @@
+//! Farm integrity check and repair.
+//!
+//! Upstream scrubbing only reports problems it finds through logs, so warnings and errors emitted
+//! while scrubbing are captured by a dedicated tracing layer and presented to the user afterward.
+
+use anyhow::anyhow;
+use async_lock::Mutex as AsyncMutex;
+use parking_lot::Mutex;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::{fmt, mem};
+use subspace_farmer::single_disk_farm::{ScrubTarget, SingleDiskFarm};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber, info_span};
+use tracing_subscriber::Layer;
+use tracing_subscriber::filter::filter_fn;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+
+/// Events emitted within span with this name are attributed to scrubbing
+const SCRUB_SPAN_NAME: &str = "scrub";
+
+/// Only one farm is scrubbed at a time, such that captured issues are attributed correctly
+static SCRUB_MUTEX: AsyncMutex<()> = AsyncMutex::new(());
+/// Issues captured while scrubbing the current farm
+static SCRUB_ISSUES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Steps of farm integrity check and repair
+#[derive(Debug, Clone)]
+pub enum ScrubStep {
+    /// Farm is being stopped, it can't be checked while running
+    StoppingFarm,
+    /// Waiting for check of another farm to finish
+    Waiting,
+    /// Checking farm metadata and plotted sectors
+    CheckingPlot,
+    /// Checking piece cache
+    CheckingCache,
+    /// Farm is being started again after check
+    StartingFarm,
+    Finished {
+        summary: ScrubSummary,
+    },
+    Failed {
+        error: Arc<anyhow::Error>,
+    },
+}
+
+/// Problems found during farm integrity check
+#[derive(Debug, Clone)]
+pub struct ScrubSummary {
+    /// Problems were only reported, but not repaired
+    pub dry_run: bool,
+    /// Problems with farm metadata and plotted sectors
+    pub plot_issues: Vec<String>,
+    /// Problems with piece cache entries
+    pub cache_issues: Vec<String>,
+}
+
+impl ScrubSummary {
+    pub fn is_healthy(&self) -> bool {
+        self.plot_issues.is_empty() && self.cache_issues.is_empty()
+    }
+}
+
+/// Checks farm in `directory` and repairs it unless `dry_run` is `true`.
+///
+/// Farm must not be running, it is locked for the duration of the check.
+pub async fn scrub_farm<OnStep>(
+    directory: PathBuf,
+    dry_run: bool,
+    on_step: OnStep,
+) -> anyhow::Result<ScrubSummary>
+where
+    OnStep: Fn(ScrubStep),
+{
+    let _scrub_guard = match SCRUB_MUTEX.try_lock() {
+        Some(scrub_guard) => scrub_guard,
+        None => {
+            on_step(ScrubStep::Waiting);
+            SCRUB_MUTEX.lock().await
+        }
+    };
+
+    on_step(ScrubStep::CheckingPlot);
+    let plot_issues = scrub_target(directory.clone(), ScrubTarget::Plot, dry_run).await?;
+
+    on_step(ScrubStep::CheckingCache);
+    let cache_issues = scrub_target(directory, ScrubTarget::Cache, dry_run).await?;
+
+    Ok(ScrubSummary {
+        dry_run,
+        plot_issues,
+        cache_issues,
+    })
+}
+
+/// Scrubs a single target on a blocking thread, returns issues that were found
+async fn scrub_target(
+    directory: PathBuf,
+    target: ScrubTarget,
+    dry_run: bool,
+) -> anyhow::Result<Vec<String>> {
+    let span = info_span!(SCRUB_SPAN_NAME);
+
+    SCRUB_ISSUES.lock().clear();
+
+    tokio::task::spawn_blocking(move || {
+        span.in_scope(|| SingleDiskFarm::scrub(&directory, false, target, dry_run))
+    })
+    .await
+    .map_err(|error| anyhow!("Farm check panicked: {error}"))??;
+
+    Ok(mem::take(&mut *SCRUB_ISSUES.lock()))
+}
+
+/// Layer that captures warnings and errors emitted while farm is being scrubbed, needs to be
+/// installed for scrubbing summary to contain anything
+pub fn scrub_issues_layer<S>() -> impl Layer<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    // Scrub span needs to be enabled too for events to be attributed to it
+    ScrubIssuesLayer.with_filter(filter_fn(|metadata| {
+        if metadata.is_span() {
+            metadata.name() == SCRUB_SPAN_NAME
+        } else {
+            *metadata.level() <= Level::WARN
+        }
+    }))
+}
+
+struct ScrubIssuesLayer;
+
+impl<S> Layer<S> for ScrubIssuesLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let Some(mut scope) = ctx.event_scope(event) else {
+            return;
+        };
+        if !scope.any(|span| span.name() == SCRUB_SPAN_NAME) {
+            return;
+        }
+
+        let mut visitor = IssueVisitor::default();
+        event.record(&mut visitor);
+        SCRUB_ISSUES.lock().push(visitor.into_issue());
+    }
+}
+
+/// Formats event as a single line, message followed by fields
+#[derive(Default)]
+struct IssueVisitor {
+    message: String,
+    fields: Vec<String>,
+}
+
+impl Visit for IssueVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields.push(format!("{}={value}", field.name()));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{value:?}");
+        } else {
+            self.fields.push(format!("{}={value:?}", field.name()));
+        }
+    }
+}
+
+impl IssueVisitor {
+    fn into_issue(self) -> String {
+        if self.fields.is_empty() {
+            self.message
+        } else {
+            format!("{} ({})", self.message, self.fields.join(", "))
+        }
+    }
+}
@@ -0,0 +1,454 @@
+//! Persistent farming history that survives application restarts.
+//!
+//! Statistics are aggregated per local calendar day, which keeps the file small even after years of
+//! farming while still allowing to show per-day and per-week history. History is kept separately
+//! for each chain (by genesis hash) and each farm (by directory) in addition to chain-wide totals.
+//!
+//! History is recorded by backend from node and farmer notifications, such that it is recorded
+//! regardless of whether GUI is running, and written to disk in the background.
+
+use crate::backend::farmer::{DiskFarm, FarmerNotification};
+use crate::backend::{FarmIndex, NodeNotification};
+use chrono::{Days, Local, NaiveDate};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use std::{fs, io};
+use subspace_core_primitives::PublicKey;
+use subspace_farmer::farm::{
+    FarmingNotification, ProvingResult, SectorPlottingDetails, SectorUpdate,
+};
+use subspace_runtime_primitives::Balance;
+use tracing::{debug, warn};
+
+const HISTORY_FILE_NAME: &str = "farming-history.json";
+/// Daily statistics older than this are dropped, they are still accounted in lifetime statistics
+const DAYS_TO_RETAIN: usize = 366;
+/// How often history is written to disk, limits how much is lost in case of a crash
+const SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Farming statistics accumulated over some period of time
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HistoryStats {
+    pub rewards: u64,
+    pub reward_amount: Balance,
+    pub successful_proofs: u64,
+    pub missed_proofs: u64,
+    pub sectors_plotted: u64,
+    pub sectors_replotted: u64,
+    plotting_time_ms: u64,
+    audits: u64,
+    auditing_time_ms: u64,
+    proving_time_ms: u64,
+}
+
+impl HistoryStats {
+    fn add(&mut self, other: &Self) {
+        self.rewards += other.rewards;
+        self.reward_amount += other.reward_amount;
+        self.successful_proofs += other.successful_proofs;
+        self.missed_proofs += other.missed_proofs;
+        self.sectors_plotted += other.sectors_plotted;
+        self.sectors_replotted += other.sectors_replotted;
+        self.plotting_time_ms += other.plotting_time_ms;
+        self.audits += other.audits;
+        self.auditing_time_ms += other.auditing_time_ms;
+        self.proving_time_ms += other.proving_time_ms;
+    }
+
+    /// Average time it took to plot or replot a sector
+    pub fn average_sector_plotting_time(&self) -> Option<Duration> {
+        average(
+            self.plotting_time_ms,
+            self.sectors_plotted + self.sectors_replotted,
+        )
+    }
+
+    pub fn average_auditing_time(&self) -> Option<Duration> {
+        average(self.auditing_time_ms, self.audits)
+    }
+
+    pub fn average_proving_time(&self) -> Option<Duration> {
+        average(
+            self.proving_time_ms,
+            self.successful_proofs + self.missed_proofs,
+        )
+    }
+}
+
+fn average(total_ms: u64, count: u64) -> Option<Duration> {
+    (count > 0).then(|| Duration::from_millis(total_ms / count))
+}
+
+/// Lifetime and daily statistics of a chain or farm
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct PeriodStats {
+    lifetime: HistoryStats,
+    days: BTreeMap<NaiveDate, HistoryStats>,
+}
+
+impl PeriodStats {
+    fn record<F>(&mut self, today: NaiveDate, f: &F)
+    where
+        F: Fn(&mut HistoryStats),
+    {
+        f(&mut self.lifetime);
+        f(self.days.entry(today).or_default());
+
+        while self.days.len() > DAYS_TO_RETAIN {
+            self.days.pop_first();
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct ChainHistory {
+    /// Totals of all farms, rewards are only accounted here since they are received by reward
+    /// address rather than individual farm
+    #[serde(flatten)]
+    total: PeriodStats,
+    /// Statistics of individual farms by farm directory
+    farms: BTreeMap<String, PeriodStats>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct HistoryContents {
+    /// Statistics of individual chains by genesis hash
+    chains: BTreeMap<String, ChainHistory>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    /// `None` if there is no application data directory, history is only kept in memory then
+    path: Option<PathBuf>,
+    contents: HistoryContents,
+    unsaved_changes: bool,
+    /// Genesis hash of the chain history is recorded for
+    genesis_hash: String,
+    /// Directories of farms by farm index
+    farms: HashMap<FarmIndex, String>,
+    /// Rewards received during sync are not related to farming
+    node_synced: bool,
+    /// `None` until known, which is the case when connected to external node
+    reward_address_balance: Option<Balance>,
+    farm_reward_address_balances: HashMap<PublicKey, Balance>,
+}
+
+impl Inner {
+    /// Chain-wide statistics of the current chain, `None` if nothing was recorded yet
+    fn chain_total(&self) -> Option<&PeriodStats> {
+        self.contents
+            .chains
+            .get(&self.genesis_hash)
+            .map(|chain| &chain.total)
+    }
+
+    fn chain(&mut self) -> &mut ChainHistory {
+        self.contents
+            .chains
+            .entry(self.genesis_hash.clone())
+            .or_default()
+    }
+
+    /// Record change in chain-wide statistics and in statistics of the farm, if specified
+    fn record<F>(&mut self, maybe_farm_index: Option<FarmIndex>, f: F)
+    where
+        F: Fn(&mut HistoryStats),
+    {
+        let today = FarmingHistory::today();
+        let maybe_farm =
+            maybe_farm_index.and_then(|farm_index| self.farms.get(&farm_index).cloned());
+        let chain = self.chain();
+
+        chain.total.record(today, &f);
+        if let Some(farm) = maybe_farm {
+            chain.farms.entry(farm).or_default().record(today, &f);
+        }
+
+        self.unsaved_changes = true;
+    }
+
+    fn record_reward(&mut self, amount: Balance) {
+        self.record(None, |stats| {
+            stats.rewards += 1;
+            stats.reward_amount += amount;
+        });
+    }
+}
+
+/// Farming history of the current chain stored in application data directory, cheap to clone
+#[derive(Debug, Clone)]
+pub struct FarmingHistory {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl FarmingHistory {
+    /// Load history of the chain with `genesis_hash`, `farms` are configured farms in order of
+    /// their indices, balances are the ones farming has started with (if known)
+    pub(super) fn load(
+        app_data_dir: Option<&Path>,
+        genesis_hash: String,
+        farms: &[DiskFarm],
+        reward_address_balance: Option<Balance>,
+        farm_reward_address_balances: &[(PublicKey, Balance)],
+    ) -> Self {
+        let path = app_data_dir.map(|app_data_dir| app_data_dir.join(HISTORY_FILE_NAME));
+        let contents = path
+            .as_deref()
+            .and_then(|path| match Self::read(path) {
+                Ok(contents) => contents,
+                Err(error) => {
+                    warn!(%error, path = %path.display(), "Failed to read farming history");
+
+                    // Keep corrupted file around instead of overwriting it on next save
+                    let backup_path = path.with_extension("json.bak");
+                    if let Err(error) = fs::rename(path, &backup_path) {
+                        warn!(
+                            %error,
+                            path = %backup_path.display(),
+                            "Failed to back up corrupted farming history"
+                        );
+                    }
+
+                    None
+                }
+            })
+            .unwrap_or_default();
+
+        let farms = farms
+            .iter()
+            .enumerate()
+            .filter_map(|(farm_index, farm)| {
+                Some((
+                    FarmIndex::try_from(farm_index).ok()?,
+                    farm.directory.display().to_string(),
+                ))
+            })
+            .collect();
+
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                path,
+                contents,
+                unsaved_changes: false,
+                genesis_hash,
+                farms,
+                node_synced: false,
+                reward_address_balance,
+                farm_reward_address_balances: farm_reward_address_balances
+                    .iter()
+                    .copied()
+                    .collect(),
+            })),
+        }
+    }
+
+    /// Returns `Ok(None)` if there is no history yet
+    fn read(path: &Path) -> io::Result<Option<HistoryContents>> {
+        let contents = match fs::read(path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                return Ok(None);
+            }
+            Err(error) => {
+                return Err(error);
+            }
+        };
+
+        serde_json::from_slice(&contents)
+            .map(Some)
+            .map_err(io::Error::other)
+    }
+
+    /// Write history to disk periodically, never returns
+    pub(super) async fn run_saving(&self) {
+        loop {
+            tokio::time::sleep(SAVE_INTERVAL).await;
+            self.save().await;
+        }
+    }
+
+    /// Write history to disk if it has changed since it was last written
+    pub(super) async fn save(&self) {
+        let (path, result) = {
+            let mut inner = self.inner.lock();
+            if !inner.unsaved_changes {
+                return;
+            }
+            let Some(path) = inner.path.clone() else {
+                return;
+            };
+            inner.unsaved_changes = false;
+
+            (path, serde_json::to_vec(&inner.contents))
+        };
+
+        // Write to temporary file first to not end up with truncated history on crash
+        let tmp_path = path.with_extension("json.tmp");
+        let result = match result {
+            Ok(contents) => match tokio::fs::write(&tmp_path, contents).await {
+                Ok(()) => tokio::fs::rename(&tmp_path, &path).await,
+                Err(error) => Err(error),
+            },
+            Err(error) => Err(io::Error::other(error)),
+        };
+
+        match result {
+            Ok(()) => {
+                debug!(path = %path.display(), "Farming history saved");
+            }
+            Err(error) => {
+                warn!(%error, path = %path.display(), "Failed to save farming history");
+            }
+        }
+    }
+
+    pub(super) fn on_node_notification(&self, notification: &NodeNotification) {
+        let mut inner = self.inner.lock();
+
+        match notification {
+            NodeNotification::SyncStateUpdate(sync_state) => {
+                inner.node_synced = sync_state.is_synced();
+            }
+            NodeNotification::ConnectedPeersUpdate(_) => {
+                // Ignore
+            }
+            NodeNotification::BlockImported(imported_block) => {
+                let previous_balance = inner
+                    .reward_address_balance
+                    .replace(imported_block.reward_address_balance);
+                if inner.node_synced
+                    && let Some(previous_balance) = previous_balance
+                    && let Some(increased_by) = imported_block
+                        .reward_address_balance
+                        .checked_sub(previous_balance)
+                    && increased_by > 0
+                {
+                    inner.record_reward(increased_by);
+                }
+
+                for (reward_address, balance) in &imported_block.farm_reward_address_balances {
+                    let previous_balance = inner
+                        .farm_reward_address_balances
+                        .insert(*reward_address, *balance);
+                    if inner.node_synced
+                        && let Some(previous_balance) = previous_balance
+                        && let Some(increased_by) = balance.checked_sub(previous_balance)
+                        && increased_by > 0
+                    {
+                        inner.record_reward(increased_by);
+                    }
+                }
+            }
+        }
+    }
+
+    pub(super) fn on_farmer_notification(&self, notification: &FarmerNotification<FarmIndex>) {
+        let mut inner = self.inner.lock();
+
+        match notification {
+            FarmerNotification::SectorUpdate {
+                farm_index,
+                update:
+                    SectorUpdate::Plotting(SectorPlottingDetails::Finished {
+                        old_plotted_sector,
+                        time,
+                        ..
+                    }),
+                ..
+            } => {
+                let replotted = old_plotted_sector.is_some();
+                inner.record(Some(*farm_index), |stats| {
+                    if replotted {
+                        stats.sectors_replotted += 1;
+                    } else {
+                        stats.sectors_plotted += 1;
+                    }
+                    stats.plotting_time_ms += time.as_millis() as u64;
+                });
+            }
+            FarmerNotification::FarmingNotification {
+                farm_index,
+                notification: FarmingNotification::Auditing(auditing_details),
+            } => {
+                inner.record(Some(*farm_index), |stats| {
+                    stats.audits += 1;
+                    stats.auditing_time_ms += auditing_details.time.as_millis() as u64;
+                });
+            }
+            FarmerNotification::FarmingNotification {
+                farm_index,
+                notification: FarmingNotification::Proving(proving_details),
+            } => {
+                let success = matches!(proving_details.result, ProvingResult::Success);
+                inner.record(Some(*farm_index), |stats| {
+                    if success {
+                        stats.successful_proofs += 1;
+                    } else {
+                        stats.missed_proofs += 1;
+                    }
+                    stats.proving_time_ms += proving_details.time.as_millis() as u64;
+                });
+            }
+            FarmerNotification::FarmAdded {
+                farm_index, farm, ..
+            } => {
+                inner
+                    .farms
+                    .insert(*farm_index, farm.directory.display().to_string());
+            }
+            FarmerNotification::FarmRemoved { farm_index } => {
+                inner.farms.remove(farm_index);
+            }
+            _ => {
+                // Not tracked in history
+            }
+        }
+    }
+
+    pub fn today() -> NaiveDate {
+        Local::now().date_naive()
+    }
+
+    /// Chain-wide statistics for specified day
+    pub fn day(&self, date: NaiveDate) -> HistoryStats {
+        self.inner
+            .lock()
+            .chain_total()
+            .and_then(|total| total.days.get(&date).copied())
+            .unwrap_or_default()
+    }
+
+    /// Chain-wide statistics for specified number of days, including today
+    pub fn last_days(&self, days: u64) -> HistoryStats {
+        let today = Self::today();
+        let first_day = today
+            .checked_sub_days(Days::new(days.saturating_sub(1)))
+            .unwrap_or(NaiveDate::MIN);
+
+        let inner = self.inner.lock();
+        let mut stats = HistoryStats::default();
+        if let Some(total) = inner.chain_total() {
+            for day_stats in total.days.range(first_day..=today).map(|(_, s)| s) {
+                stats.add(day_stats);
+            }
+        }
+        stats
+    }
+
+    /// Chain-wide lifetime statistics
+    pub fn lifetime(&self) -> HistoryStats {
+        self.inner
+            .lock()
+            .chain_total()
+            .map(|total| total.lifetime)
+            .unwrap_or_default()
+    }
+}
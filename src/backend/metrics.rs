@@ -0,0 +1,647 @@
+//! Prometheus metrics endpoint that combines metrics of Substrate node, networking stack, farms and
+//! application itself.
+//!
+//! Upstream metrics server takes ownership of registries, which doesn't work with farms that can
+//! be added and removed at runtime, hence a small dedicated server here.
+//!
+//! Everything is served in OpenMetrics text format, Substrate registry uses a different library,
+//! so its metrics are converted into OpenMetrics here as well.
+
+use crate::backend::farmer::{FarmerNotification, InitialFarmState};
+use crate::backend::node::SyncState;
+use crate::backend::{FarmIndex, NodeNotification};
+use parking_lot::Mutex;
+use prometheus_client::encoding::EncodeLabelSet;
+use prometheus_client::metrics::family::Family;
+use prometheus_client::metrics::gauge::Gauge;
+use prometheus_client::registry::Registry;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::sync::atomic::AtomicU64;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use std::{fmt, io};
+use subspace_farmer::farm::{SectorPlottingDetails, SectorUpdate};
+use subspace_runtime_primitives::{AI3, Balance};
+use substrate_prometheus_endpoint::Registry as SubstrateRegistry;
+use substrate_prometheus_endpoint::prometheus::proto::{Metric, MetricFamily, MetricType};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{debug, info, warn};
+
+/// Requests that take longer than this to arrive are dropped
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Request line and headers are not expected to be larger than this
+const MAX_REQUEST_HEAD_SIZE: u64 = 8 * 1024;
+const OPEN_METRICS_EOF: &str = "# EOF\n";
+
+#[derive(Debug, Default)]
+struct RegistriesInner {
+    next_id: u64,
+    registries: BTreeMap<u64, Registry>,
+}
+
+/// Collection of registries exposed through Prometheus endpoint, registries are removed when
+/// corresponding [`RegistryGuard`] is dropped
+#[derive(Debug, Default, Clone)]
+pub(super) struct MetricsRegistries {
+    inner: Arc<Mutex<RegistriesInner>>,
+}
+
+impl MetricsRegistries {
+    pub(super) fn add(&self, registry: Registry) -> RegistryGuard {
+        let mut inner = self.inner.lock();
+        let id = inner.next_id;
+        inner.next_id += 1;
+        inner.registries.insert(id, registry);
+
+        RegistryGuard {
+            id,
+            inner: Arc::downgrade(&self.inner),
+        }
+    }
+
+    fn encode(&self, substrate_registry: Option<&SubstrateRegistry>) -> anyhow::Result<String> {
+        let mut output = String::new();
+
+        if let Some(substrate_registry) = substrate_registry {
+            for metric_family in substrate_registry.gather() {
+                encode_substrate_metric_family(&mut output, &metric_family)?;
+            }
+        }
+
+        for registry in self.inner.lock().registries.values() {
+            let mut encoded = String::new();
+            prometheus_client::encoding::text::encode(&mut encoded, registry)?;
+            // Each registry is terminated separately, but there must only be one terminator
+            output.push_str(
+                encoded
+                    .strip_suffix(OPEN_METRICS_EOF)
+                    .unwrap_or(encoded.as_str()),
+            );
+        }
+
+        output.push_str(OPEN_METRICS_EOF);
+
+        Ok(output)
+    }
+}
+
+/// Encode metric family of Substrate registry in OpenMetrics text format
+fn encode_substrate_metric_family(
+    output: &mut String,
+    metric_family: &MetricFamily,
+) -> fmt::Result {
+    if metric_family.get_metric().is_empty() {
+        return Ok(());
+    }
+
+    let name = metric_family.get_name();
+    let (metric_type, name) = match metric_family.get_field_type() {
+        // OpenMetrics counter family name doesn't include suffix, but its sample does
+        MetricType::COUNTER => ("counter", name.strip_suffix("_total").unwrap_or(name)),
+        MetricType::GAUGE => ("gauge", name),
+        MetricType::SUMMARY => ("summary", name),
+        MetricType::HISTOGRAM => ("histogram", name),
+        MetricType::UNTYPED => ("unknown", name),
+    };
+
+    writeln!(output, "# HELP {name} {}", escape(metric_family.get_help()))?;
+    writeln!(output, "# TYPE {name} {metric_type}")?;
+
+    for metric in metric_family.get_metric() {
+        match metric_family.get_field_type() {
+            MetricType::COUNTER => {
+                encode_sample(
+                    output,
+                    &format!("{name}_total"),
+                    metric,
+                    None,
+                    metric.get_counter().get_value(),
+                )?;
+            }
+            MetricType::GAUGE => {
+                encode_sample(output, name, metric, None, metric.get_gauge().get_value())?;
+            }
+            MetricType::UNTYPED => {
+                encode_sample(output, name, metric, None, metric.get_untyped().get_value())?;
+            }
+            MetricType::SUMMARY => {
+                let summary = metric.get_summary();
+                for quantile in summary.get_quantile() {
+                    encode_sample(
+                        output,
+                        name,
+                        metric,
+                        Some(("quantile", quantile.get_quantile())),
+                        quantile.get_value(),
+                    )?;
+                }
+                encode_sample(
+                    output,
+                    &format!("{name}_sum"),
+                    metric,
+                    None,
+                    summary.get_sample_sum(),
+                )?;
+                encode_sample(
+                    output,
+                    &format!("{name}_count"),
+                    metric,
+                    None,
+                    summary.get_sample_count() as f64,
+                )?;
+            }
+            MetricType::HISTOGRAM => {
+                let histogram = metric.get_histogram();
+                let bucket_name = format!("{name}_bucket");
+                for bucket in histogram.get_bucket() {
+                    encode_sample(
+                        output,
+                        &bucket_name,
+                        metric,
+                        Some(("le", bucket.get_upper_bound())),
+                        bucket.get_cumulative_count() as f64,
+                    )?;
+                }
+                // OpenMetrics requires `+Inf` bucket, while Prometheus library adds it on encoding
+                if histogram
+                    .get_bucket()
+                    .last()
+                    .is_none_or(|bucket| bucket.get_upper_bound() != f64::INFINITY)
+                {
+                    encode_sample(
+                        output,
+                        &bucket_name,
+                        metric,
+                        Some(("le", f64::INFINITY)),
+                        histogram.get_sample_count() as f64,
+                    )?;
+                }
+                encode_sample(
+                    output,
+                    &format!("{name}_sum"),
+                    metric,
+                    None,
+                    histogram.get_sample_sum(),
+                )?;
+                encode_sample(
+                    output,
+                    &format!("{name}_count"),
+                    metric,
+                    None,
+                    histogram.get_sample_count() as f64,
+                )?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn encode_sample(
+    output: &mut String,
+    name: &str,
+    metric: &Metric,
+    extra_label: Option<(&str, f64)>,
+    value: f64,
+) -> fmt::Result {
+    output.push_str(name);
+
+    let labels = metric
+        .get_label()
+        .iter()
+        .map(|label| (label.get_name(), escape(label.get_value())))
+        .chain(extra_label.map(|(label_name, label_value)| (label_name, encode_float(label_value))))
+        .collect::<Vec<_>>();
+    if !labels.is_empty() {
+        output.push('{');
+        for (index, (label_name, label_value)) in labels.iter().enumerate() {
+            if index > 0 {
+                output.push(',');
+            }
+            write!(output, "{label_name}=\"{label_value}\"")?;
+        }
+        output.push('}');
+    }
+
+    writeln!(output, " {}", encode_float(value))
+}
+
+fn encode_float(value: f64) -> String {
+    if value == f64::INFINITY {
+        "+Inf".to_string()
+    } else if value == f64::NEG_INFINITY {
+        "-Inf".to_string()
+    } else if value.is_nan() {
+        "NaN".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Escape help text or label value
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('"', "\\\"")
+}
+
+/// Removes registry from [`MetricsRegistries`] on drop
+#[must_use = "Registry is removed when guard is dropped"]
+pub(super) struct RegistryGuard {
+    id: u64,
+    inner: Weak<Mutex<RegistriesInner>>,
+}
+
+impl fmt::Debug for RegistryGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RegistryGuard")
+            .field("id", &self.id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for RegistryGuard {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.upgrade() {
+            inner.lock().registries.remove(&self.id);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, Eq, PartialEq, EncodeLabelSet)]
+struct FarmLabels {
+    farm_index: String,
+}
+
+impl FarmLabels {
+    fn new(farm_index: FarmIndex) -> Self {
+        Self {
+            farm_index: farm_index.to_string(),
+        }
+    }
+}
+
+/// Application-level metrics that are not covered by upstream components
+#[derive(Debug, Clone)]
+pub(super) struct AppMetrics {
+    farm_sectors_total: Family<FarmLabels, Gauge>,
+    farm_sectors_plotted: Family<FarmLabels, Gauge>,
+    /// `None` when connected to external node, balance is not known then
+    reward_address_balance: Option<Gauge<f64, AtomicU64>>,
+    /// `None` when connected to external node, best block is not known then
+    best_block_number: Option<Gauge>,
+    sync_target_block_number: Gauge,
+    synced: Gauge,
+    connected_peers: Gauge,
+}
+
+impl AppMetrics {
+    /// Node-specific metrics are not registered when `external_node` is `true`
+    pub(super) fn new(registry: &mut Registry, external_node: bool) -> Self {
+        let registry = registry.sub_registry_with_prefix("space_acres");
+
+        let farm_sectors_total = Family::default();
+        registry.register(
+            "farm_sectors_total",
+            "Total number of sectors in the farm",
+            farm_sectors_total.clone(),
+        );
+
+        let farm_sectors_plotted = Family::default();
+        registry.register(
+            "farm_sectors_plotted",
+            "Number of sectors plotted in the farm, excluding sectors being replotted",
+            farm_sectors_plotted.clone(),
+        );
+
+        let reward_address_balance = (!external_node).then(|| {
+            let reward_address_balance = Gauge::default();
+            registry.register(
+                "reward_address_balance",
+                "Balance of the reward address in tokens",
+                reward_address_balance.clone(),
+            );
+            reward_address_balance
+        });
+
+        let best_block_number = (!external_node).then(|| {
+            let best_block_number = Gauge::default();
+            registry.register(
+                "best_block_number",
+                "Best block number known to the node",
+                best_block_number.clone(),
+            );
+            best_block_number
+        });
+
+        let sync_target_block_number = Gauge::default();
+        registry.register(
+            "sync_target_block_number",
+            "Block number node is syncing to, 0 when not syncing",
+            sync_target_block_number.clone(),
+        );
+
+        let synced = Gauge::default();
+        registry.register(
+            "synced",
+            "Whether node is synced (1) or not (0)",
+            synced.clone(),
+        );
+
+        let connected_peers = Gauge::default();
+        registry.register(
+            "connected_peers",
+            "Number of peers node is connected to",
+            connected_peers.clone(),
+        );
+
+        Self {
+            farm_sectors_total,
+            farm_sectors_plotted,
+            reward_address_balance,
+            best_block_number,
+            sync_target_block_number,
+            synced,
+            connected_peers,
+        }
+    }
+
+    pub(super) fn initialize(
+        &self,
+        best_block_number: Option<u32>,
+        reward_address_balance: Option<Balance>,
+        initial_farm_states: &[InitialFarmState],
+    ) {
+        if let Some(best_block_number) = best_block_number {
+            self.set_best_block_number(best_block_number);
+        }
+        if let Some(reward_address_balance) = reward_address_balance {
+            self.set_reward_address_balance(reward_address_balance);
+        }
+
+        for (farm_index, initial_farm_state) in initial_farm_states.iter().enumerate() {
+            self.set_initial_farm_state(farm_index as FarmIndex, initial_farm_state);
+        }
+    }
+
+    pub(super) fn on_node_notification(&self, notification: &NodeNotification) {
+        match notification {
+            NodeNotification::SyncStateUpdate(sync_state) => {
+                self.synced.set(i64::from(sync_state.is_synced()));
+                self.sync_target_block_number.set(match sync_state {
+                    SyncState::Syncing { target, .. } => i64::from(*target),
+                    SyncState::Unknown | SyncState::Idle => 0,
+                });
+            }
+            NodeNotification::ConnectedPeersUpdate(connected_peers) => {
+                self.connected_peers.set(i64::from(*connected_peers));
+            }
+            NodeNotification::BlockImported(imported_block) => {
+                self.set_best_block_number(imported_block.number);
+                self.set_reward_address_balance(imported_block.reward_address_balance);
+            }
+        }
+    }
+
+    pub(super) fn on_farmer_notification(&self, notification: &FarmerNotification<FarmIndex>) {
+        match notification {
+            FarmerNotification::SectorUpdate {
+                farm_index,
+                update:
+                    SectorUpdate::Plotting(SectorPlottingDetails::Finished {
+                        old_plotted_sector: None,
+                        ..
+                    }),
+                ..
+            } => {
+                self.farm_sectors_plotted
+                    .get_or_create(&FarmLabels::new(*farm_index))
+                    .inc();
+            }
+            FarmerNotification::FarmAdded {
+                farm_index,
+                initial_farm_state,
+                ..
+            } => {
+                self.set_initial_farm_state(*farm_index, initial_farm_state);
+            }
+            FarmerNotification::FarmRemoved { farm_index } => {
+                let labels = FarmLabels::new(*farm_index);
+                self.farm_sectors_total.remove(&labels);
+                self.farm_sectors_plotted.remove(&labels);
+            }
+            _ => {
+                // Not tracked
+            }
+        }
+    }
+
+    fn set_initial_farm_state(&self, farm_index: FarmIndex, initial_farm_state: &InitialFarmState) {
+        let labels = FarmLabels::new(farm_index);
+        self.farm_sectors_total
+            .get_or_create(&labels)
+            .set(i64::from(initial_farm_state.total_sectors_count));
+        self.farm_sectors_plotted
+            .get_or_create(&labels)
+            .set(i64::from(initial_farm_state.plotted_sectors_count));
+    }
+
+    fn set_best_block_number(&self, best_block_number: u32) {
+        if let Some(gauge) = &self.best_block_number {
+            gauge.set(i64::from(best_block_number));
+        }
+    }
+
+    fn set_reward_address_balance(&self, reward_address_balance: Balance) {
+        if let Some(gauge) = &self.reward_address_balance {
+            gauge.set(reward_address_balance as f64 / AI3 as f64);
+        }
+    }
+}
+
+/// Serve metrics on specified address until error happens
+pub(super) async fn serve(
+    listen_on: SocketAddr,
+    registries: MetricsRegistries,
+    substrate_registry: Option<SubstrateRegistry>,
+) -> io::Result<()> {
+    let listener = TcpListener::bind(listen_on).await?;
+    info!(%listen_on, "Prometheus metrics endpoint started");
+
+    loop {
+        let (stream, address) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(error) => {
+                warn!(%error, "Failed to accept Prometheus metrics connection");
+                continue;
+            }
+        };
+
+        let response_fut = respond(stream, registries.clone(), substrate_registry.clone());
+        tokio::spawn(async move {
+            match tokio::time::timeout(REQUEST_TIMEOUT, response_fut).await {
+                Ok(Ok(())) => {}
+                Ok(Err(error)) => {
+                    debug!(%error, %address, "Failed to respond to Prometheus metrics request");
+                }
+                Err(_elapsed) => {
+                    debug!(%address, "Prometheus metrics request timed out");
+                }
+            }
+        });
+    }
+}
+
+async fn respond(
+    mut stream: TcpStream,
+    registries: MetricsRegistries,
+    substrate_registry: Option<SubstrateRegistry>,
+) -> io::Result<()> {
+    // Reading is limited, such that lines that are too long are not buffered in full
+    let mut reader = BufReader::new((&mut stream).take(MAX_REQUEST_HEAD_SIZE));
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+
+    // Skip headers, they are not used
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).await? == 0 {
+            if reader.get_ref().limit() == 0 {
+                return Err(io::Error::other("Request is too large"));
+            }
+            break;
+        }
+
+        if header.trim().is_empty() {
+            break;
+        }
+    }
+    drop(reader);
+
+    let mut request_parts = request_line.split_ascii_whitespace();
+    let (status, content_type, body) = match (request_parts.next(), request_parts.next()) {
+        (Some("GET"), Some("/metrics")) => match registries.encode(substrate_registry.as_ref()) {
+            Ok(body) => (
+                "200 OK",
+                "application/openmetrics-text; version=1.0.0; charset=utf-8",
+                body,
+            ),
+            Err(error) => {
+                warn!(%error, "Failed to encode Prometheus metrics");
+                (
+                    "500 Internal Server Error",
+                    "text/plain; charset=utf-8",
+                    "Failed to encode metrics\n".to_string(),
+                )
+            }
+        },
+        _ => (
+            "404 Not Found",
+            "text/plain; charset=utf-8",
+            "Metrics are available at /metrics\n".to_string(),
+        ),
+    };
+
+    stream
+        .write_all(
+            format!(
+                "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n\
+                Connection: close\r\n\r\n",
+                body.len()
+            )
+            .as_bytes(),
+        )
+        .await?;
+    stream.write_all(body.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use substrate_prometheus_endpoint::prometheus::{
+        Histogram, HistogramOpts, IntCounter, IntCounterVec, Opts, Registry as PrometheusRegistry,
+    };
+
+    fn encode(registry: &PrometheusRegistry) -> String {
+        let mut output = String::new();
+        for metric_family in registry.gather() {
+            encode_substrate_metric_family(&mut output, &metric_family).unwrap();
+        }
+        output
+    }
+
+    #[test]
+    fn counter_total_suffix() {
+        let registry = PrometheusRegistry::new();
+        let requests = IntCounterVec::new(
+            Opts::new("substrate_requests_total", "Requests"),
+            &["method"],
+        )
+        .unwrap();
+        let blocks = IntCounter::new("substrate_blocks", "Blocks").unwrap();
+        registry.register(Box::new(requests.clone())).unwrap();
+        registry.register(Box::new(blocks.clone())).unwrap();
+
+        requests.with_label_values(&["get"]).inc_by(2);
+        blocks.inc();
+
+        assert_eq!(
+            encode(&registry),
+            "# HELP substrate_blocks Blocks\n\
+            # TYPE substrate_blocks counter\n\
+            substrate_blocks_total 1\n\
+            # HELP substrate_requests Requests\n\
+            # TYPE substrate_requests counter\n\
+            substrate_requests_total{method=\"get\"} 2\n"
+        );
+    }
+
+    #[test]
+    fn histogram_inf_bucket() {
+        let registry = PrometheusRegistry::new();
+        let latency = Histogram::with_opts(
+            HistogramOpts::new("substrate_latency", "Latency").buckets(vec![1.0, 2.0]),
+        )
+        .unwrap();
+        registry.register(Box::new(latency.clone())).unwrap();
+
+        latency.observe(1.5);
+
+        assert_eq!(
+            encode(&registry),
+            "# HELP substrate_latency Latency\n\
+            # TYPE substrate_latency histogram\n\
+            substrate_latency_bucket{le=\"1\"} 0\n\
+            substrate_latency_bucket{le=\"2\"} 1\n\
+            substrate_latency_bucket{le=\"+Inf\"} 1\n\
+            substrate_latency_sum 1.5\n\
+            substrate_latency_count 1\n"
+        );
+    }
+
+    #[test]
+    fn label_escaping() {
+        let registry = PrometheusRegistry::new();
+        let peers = IntCounterVec::new(
+            Opts::new("substrate_peers_total", "Peers with \"quotes\"\nand \\"),
+            &["name"],
+        )
+        .unwrap();
+        registry.register(Box::new(peers.clone())).unwrap();
+
+        peers.with_label_values(&["a\"b\\c\nd"]).inc();
+
+        assert_eq!(
+            encode(&registry),
+            "# HELP substrate_peers Peers with \\\"quotes\\\"\\nand \\\\\n\
+            # TYPE substrate_peers counter\n\
+            substrate_peers_total{name=\"a\\\"b\\\\c\\nd\"} 1\n"
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use async_lock::RwLock as AsyncRwLock;
 use parking_lot::Mutex;
+use prometheus_client::registry::Registry;
 use std::collections::HashSet;
 use std::fmt;
 use std::hash::Hash;
@@ -11,6 +12,7 @@ use subspace_farmer::farmer_cache::FarmerCache;
 use subspace_farmer::node_client::NodeClientExt;
 use subspace_networking::libp2p::Multiaddr;
 use subspace_networking::libp2p::identity::ed25519::Keypair;
+use subspace_networking::libp2p::metrics::Metrics;
 use subspace_networking::libp2p::multiaddr::Protocol;
 use subspace_networking::protocols::request_response::handlers::cached_piece_by_index::{
     CachedPieceByIndexRequest, CachedPieceByIndexRequestHandler, CachedPieceByIndexResponse,
@@ -99,6 +101,7 @@ pub fn create_network<FarmIndex, NC>(
     weak_plotted_pieces: Weak<AsyncRwLock<PlottedPieces<FarmIndex>>>,
     node_client: NC,
     farmer_cache: FarmerCache,
+    prometheus_registry: Option<&mut Registry>,
 ) -> Result<(Node, NodeRunner), anyhow::Error>
 where
     FarmIndex: Hash + Eq + Copy + fmt::Debug + Send + Sync + 'static,
@@ -295,7 +298,7 @@ where
         bootstrap_addresses: bootstrap_nodes,
         kademlia_mode: KademliaMode::Dynamic,
         external_addresses,
-        metrics: None,
+        metrics: prometheus_registry.map(Metrics::new),
         ..default_config
     };
 
@@ -1,6 +1,9 @@
 mod utils;
 
 use crate::PosTable;
+use crate::backend::config::{
+    NetworkConfiguration, NodeConfiguration, NodePruning, RpcConfiguration, RpcMethods, SyncMode,
+};
 use crate::backend::farmer::direct_node_client::{DirectNodeClient, NodeClientConfig};
 use crate::backend::farmer::maybe_node_client::MaybeNodeClient;
 use crate::backend::node::utils::account_storage_key;
@@ -10,25 +13,33 @@ use frame_system::AccountInfo;
 use futures::{FutureExt, StreamExt, select};
 use names::{Generator, Name};
 use pallet_balances::AccountData;
-use parity_scale_codec::Decode;
+use parity_scale_codec::{Decode, Encode};
+use prometheus_client::registry::Registry;
 use sc_client_api::client::BlockchainEvents;
 use sc_client_api::{HeaderBackend, StorageProvider};
 use sc_client_db::PruningMode;
 use sc_consensus_slots::SlotProportion;
 use sc_consensus_subspace::archiver::CreateObjectMappings;
-use sc_network::config::{Ed25519Secret, NodeKeyConfig, NonReservedPeerMode, SetConfig};
-use sc_service::{BlocksPruning, Configuration, GenericChainSpec, NoExtension};
+use sc_network::config::{
+    Ed25519Secret, MultiaddrWithPeerId, NodeKeyConfig, NonReservedPeerMode, SetConfig,
+};
+use sc_service::{
+    BlocksPruning, Configuration, GenericChainSpec, NoExtension, construct_genesis_block,
+};
 use sc_storage_monitor::{StorageMonitorParams, StorageMonitorService};
 use serde_json::Value;
 use sp_api::ProvideRuntimeApi;
 use sp_consensus_subspace::{ChainConstants, SubspaceApi};
 use sp_core::H256;
 use sp_core::crypto::Ss58AddressFormat;
-use sp_core::storage::StorageKey;
-use sp_runtime::traits::Header;
+use sp_core::storage::{Storage, StorageKey};
+use sp_runtime::StateVersion;
+use sp_runtime::traits::{BlakeTwo256, Hash, Header};
+use std::borrow::Cow;
 use std::fmt;
-use std::net::{Ipv4Addr, Ipv6Addr};
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
@@ -39,12 +50,14 @@ use subspace_fake_runtime_api::RuntimeApi;
 use subspace_networking::Node;
 use subspace_networking::libp2p::Multiaddr;
 use subspace_networking::libp2p::identity::ed25519::Keypair;
+use subspace_runtime_primitives::opaque::Block;
 use subspace_runtime_primitives::{Balance, Nonce};
 use subspace_service::config::{
     ChainSyncMode, SubspaceConfiguration, SubspaceNetworking, SubstrateConfiguration,
     SubstrateNetworkConfiguration, SubstrateRpcConfiguration,
 };
 use subspace_service::{FullClient, NewFull};
+use substrate_prometheus_endpoint::Registry as SubstrateRegistry;
 use tokio::time::MissedTickBehavior;
 use tracing::{error, info, info_span};
 
@@ -54,14 +67,24 @@ const SYNC_STATUS_EVENT_INTERVAL: Duration = Duration::from_secs(5);
 const CONNECTED_PEERS_EVENT_INTERVAL: Duration = Duration::from_secs(5);
 /// Roughly 138k empty blocks can fit into one archived segment, hence we need to not allow to prune
 /// more blocks that this
-const MIN_STATE_PRUNING: BlockNumber = 140_000;
+pub const MIN_STATE_PRUNING: BlockNumber = 140_000;
+/// Number of finalized blocks to keep bodies of by default
+pub const DEFAULT_BLOCKS_PRUNING: BlockNumber = 256;
 // Substrate's default
 pub const IN_PEERS: u32 = 32;
 // Substrate's default
 pub const OUT_PEERS: u32 = 8;
 
 /// The maximum number of characters for a node name.
-const NODE_NAME_MAX_LENGTH: usize = 64;
+pub const NODE_NAME_MAX_LENGTH: usize = 64;
+/// Origins allowed to access RPC when not configured explicitly, same as Substrate's default
+const DEFAULT_RPC_CORS: &[&str] = &[
+    "http://localhost:*",
+    "http://127.0.0.1:*",
+    "https://localhost:*",
+    "https://127.0.0.1:*",
+    "https://polkadot.js.org",
+];
 
 #[derive(Debug, thiserror::Error)]
 pub(super) enum ConsensusNodeCreationError {
@@ -115,6 +138,8 @@ pub enum SyncState {
     Unknown,
     Syncing {
         kind: SyncKind,
+        /// Sync mode node was configured with
+        mode: SyncMode,
         target: BlockNumber,
     },
     Idle,
@@ -126,10 +151,12 @@ impl SyncState {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub struct BlockImportedNotification {
     pub number: BlockNumber,
     pub reward_address_balance: Balance,
+    /// Balances of reward addresses of individual farms that differ from global reward address
+    pub farm_reward_address_balances: Vec<(PublicKey, Balance)>,
     pub solution_range: SolutionRange,
     pub voting_solution_range: SolutionRange,
 }
@@ -144,8 +171,10 @@ struct Handlers {
 pub(super) struct ConsensusNode {
     full_node: NewFull<FullClient<RuntimeApi>>,
     pause_sync: Arc<AtomicBool>,
+    sync_mode: SyncMode,
     chain_info: ChainInfo,
     chain_constants: ChainConstants,
+    substrate_prometheus_registry: Option<SubstrateRegistry>,
     handlers: Handlers,
 }
 
@@ -159,19 +188,27 @@ impl ConsensusNode {
     fn new(
         full_node: NewFull<FullClient<RuntimeApi>>,
         pause_sync: Arc<AtomicBool>,
+        sync_mode: SyncMode,
         chain_info: ChainInfo,
         chain_constants: ChainConstants,
+        substrate_prometheus_registry: Option<SubstrateRegistry>,
     ) -> Self {
         Self {
             full_node,
             pause_sync,
+            sync_mode,
             chain_info,
             chain_constants,
+            substrate_prometheus_registry,
             handlers: Handlers::default(),
         }
     }
 
-    pub(super) async fn run(mut self, reward_address: &PublicKey) -> Result<(), sc_service::Error> {
+    pub(super) async fn run(
+        mut self,
+        reward_address: &PublicKey,
+        farm_reward_addresses: &[PublicKey],
+    ) -> Result<(), sc_service::Error> {
         self.full_node.network_starter.start_network();
 
         let spawn_essential_handle = self.full_node.task_manager.spawn_essential_handle();
@@ -181,6 +218,15 @@ impl ConsensusNode {
             {
                 let client = self.full_node.client.clone();
                 let reward_address_storage_key = account_storage_key(reward_address);
+                let farm_reward_address_storage_keys = farm_reward_addresses
+                    .iter()
+                    .map(|farm_reward_address| {
+                        (
+                            *farm_reward_address,
+                            account_storage_key(farm_reward_address),
+                        )
+                    })
+                    .collect::<Vec<_>>();
 
                 async move {
                     let mut block_import_stream = client.every_import_notification_stream();
@@ -202,6 +248,19 @@ impl ConsensusNode {
                                     &reward_address_storage_key,
                                 )
                                 .unwrap_or_default(),
+                                farm_reward_address_balances: farm_reward_address_storage_keys
+                                    .iter()
+                                    .map(|(farm_reward_address, storage_key)| {
+                                        let balance = get_total_account_balance(
+                                            &client,
+                                            block_import.header.hash(),
+                                            storage_key,
+                                        )
+                                        .unwrap_or_default();
+
+                                        (*farm_reward_address, balance)
+                                    })
+                                    .collect(),
                                 solution_range: solution_ranges.current,
                                 voting_solution_range: solution_ranges.voting_current,
                             };
@@ -234,6 +293,7 @@ impl ConsensusNode {
                             } else {
                                 SyncKind::Regular
                             },
+                            mode: self.sync_mode,
                             target: sync_status.best_seen_block.unwrap_or_default(),
                         }
                     } else if self.full_node.sync_service.num_connected_peers() > 0 {
@@ -314,6 +374,11 @@ impl ConsensusNode {
         &self.chain_constants
     }
 
+    /// Substrate metrics registry, present if metrics are enabled
+    pub(super) fn substrate_prometheus_registry(&self) -> Option<&SubstrateRegistry> {
+        self.substrate_prometheus_registry.as_ref()
+    }
+
     pub(super) fn on_sync_state_change(&self, callback: HandlerFn<SyncState>) -> HandlerId {
         self.handlers.sync_state_change.add(callback)
     }
@@ -357,12 +422,55 @@ fn get_total_account_balance(
     Some(account_data.free + account_data.reserved + account_data.frozen)
 }
 
-pub(super) fn load_chain_specification(chain_spec: &'static [u8]) -> Result<ChainSpec, String> {
+pub(super) fn load_chain_specification<B>(chain_spec: B) -> Result<ChainSpec, String>
+where
+    B: Into<Cow<'static, [u8]>>,
+{
     GenericChainSpec::<NoExtension, ()>::from_json_bytes(chain_spec)
         .map(|chain_spec| ChainSpec(Box::new(chain_spec)))
 }
 
-fn set_default_ss58_version(chain_spec: &ChainSpec) {
+/// Derive genesis hash from chain specification, it is used for chains that are not known in
+/// advance
+pub(super) fn genesis_hash(chain_spec: &ChainSpec) -> Result<String, String> {
+    // All Subspace runtimes use this state version
+    let state_version = StateVersion::V1;
+
+    let Storage {
+        mut top,
+        children_default,
+    } = chain_spec.0.as_storage_builder().build_storage()?;
+
+    for child_content in children_default.into_values() {
+        let child_root =
+            BlakeTwo256::trie_root(child_content.data.into_iter().collect(), state_version);
+        top.insert(
+            child_content.child_info.prefixed_storage_key().into_inner(),
+            child_root.encode(),
+        );
+    }
+
+    let state_root = BlakeTwo256::trie_root(top.into_iter().collect(), state_version);
+    let genesis_block = construct_genesis_block::<Block>(state_root, state_version);
+
+    Ok(hex::encode(genesis_block.header.hash()))
+}
+
+pub(super) fn chain_info(chain_spec: &ChainSpec) -> ChainInfo {
+    ChainInfo {
+        chain_name: chain_spec.0.name().to_string(),
+        protocol_id: chain_spec.0.protocol_id().unwrap_or_default().to_string(),
+        token_symbol: chain_spec
+            .0
+            .properties()
+            .get("tokenSymbol")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string(),
+    }
+}
+
+pub(super) fn set_default_ss58_version(chain_spec: &ChainSpec) {
     let maybe_ss58_address_format = chain_spec
         .0
         .properties()
@@ -432,15 +540,90 @@ pub(super) fn generate_node_name() -> String {
     }
 }
 
+/// Check that node name is valid, same rules as in Substrate CLI
+pub fn check_node_name(node_name: &str) -> Result<(), &'static str> {
+    if node_name.is_empty() {
+        return Err("Node name cannot be empty");
+    }
+    if node_name.chars().count() >= NODE_NAME_MAX_LENGTH {
+        return Err("Node name too long");
+    }
+    if node_name.contains(['\\', '.', '@']) {
+        return Err("Node name should not contain invalid chars such as '.' and '@'");
+    }
+    if node_name.starts_with("http:") || node_name.starts_with("https:") {
+        return Err("Node name should not contain urls");
+    }
+
+    Ok(())
+}
+
+/// Convert DSN multiaddr into Substrate's multiaddr type
+#[allow(clippy::result_large_err)]
+fn parse_substrate_address<T>(address: &Multiaddr) -> Result<T, sc_service::Error>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    address.to_string().parse().map_err(|error| {
+        sc_service::Error::Other(format!("Invalid Substrate address {address}: {error}"))
+    })
+}
+
+#[allow(clippy::result_large_err)]
 fn create_consensus_chain_config(
     keypair: &Keypair,
     base_path: PathBuf,
-    substrate_port: u16,
+    network: &NetworkConfiguration,
+    rpc: &RpcConfiguration,
+    node: &NodeConfiguration,
     chain_spec: ChainSpec,
-) -> SubstrateConfiguration {
-    let telemetry_endpoints = chain_spec.0.telemetry_endpoints().clone();
+    enable_metrics: bool,
+) -> Result<SubstrateConfiguration, sc_service::Error> {
+    let telemetry_endpoints = if node.disable_telemetry {
+        None
+    } else {
+        chain_spec.0.telemetry_endpoints().clone()
+    };
+    let substrate_port = network.substrate_port;
+    let public_addresses = network
+        .substrate_public_addresses
+        .iter()
+        .map(parse_substrate_address)
+        .collect::<Result<Vec<_>, _>>()?;
+    let reserved_nodes = network
+        .substrate_reserved_nodes
+        .iter()
+        .map(parse_substrate_address::<MultiaddrWithPeerId>)
+        .collect::<Result<Vec<_>, _>>()?;
+    let rpc_cors = if rpc.cors.is_empty() {
+        Some(
+            DEFAULT_RPC_CORS
+                .iter()
+                .map(|origin| origin.to_string())
+                .collect(),
+        )
+    } else if rpc.cors.iter().any(|origin| origin == "all") {
+        None
+    } else {
+        Some(rpc.cors.clone())
+    };
+    let (state_pruning, blocks_pruning) = match node.pruning() {
+        NodePruning::Pruned {
+            state_pruning,
+            blocks_pruning,
+        } => (
+            PruningMode::blocks_pruning(state_pruning),
+            BlocksPruning::Some(blocks_pruning),
+        ),
+        NodePruning::Archive => (PruningMode::ArchiveAll, BlocksPruning::KeepAll),
+    };
+    let sync_mode = match node.sync_mode() {
+        SyncMode::Snap => ChainSyncMode::Snap,
+        SyncMode::Full => ChainSyncMode::Full,
+    };
 
-    SubstrateConfiguration {
+    Ok(SubstrateConfiguration {
         impl_name: env!("CARGO_PKG_NAME").to_string(),
         impl_version: env!("CARGO_PKG_VERSION").to_string(),
         farmer: true,
@@ -457,7 +640,7 @@ fn create_consensus_chain_config(
                 ))
                 .with(sc_network::multiaddr::Protocol::Tcp(substrate_port)),
             ],
-            public_addresses: Vec::new(),
+            public_addresses,
             bootstrap_nodes: chain_spec.0.boot_nodes().to_vec(),
             node_key: NodeKeyConfig::Ed25519(Ed25519Secret::Input(
                 sc_network_types::ed25519::SecretKey::try_from_bytes(
@@ -468,25 +651,26 @@ fn create_consensus_chain_config(
             default_peers_set: SetConfig {
                 in_peers: IN_PEERS,
                 out_peers: OUT_PEERS,
-                reserved_nodes: Vec::new(),
+                reserved_nodes,
                 non_reserved_mode: NonReservedPeerMode::Accept,
             },
-            node_name: generate_node_name(),
-            allow_private_ips: false,
-            sync_mode: ChainSyncMode::Snap,
+            node_name: node.name.clone().unwrap_or_else(generate_node_name),
+            allow_private_ips: network.allow_private_ips,
+            sync_mode,
             force_synced: false,
         },
-        state_pruning: PruningMode::blocks_pruning(MIN_STATE_PRUNING),
-        blocks_pruning: BlocksPruning::Some(256),
-        // TODO: Make the whole `rpc_options` optional instead
+        state_pruning,
+        blocks_pruning,
         rpc_options: SubstrateRpcConfiguration {
-            listen_on: None,
+            listen_on: rpc.listen_on,
             // Substrate's default
             max_connections: 100,
-            cors: Some(Vec::new()),
-            methods: Default::default(),
-            // Substrate's default
-            rate_limit: None,
+            cors: rpc_cors,
+            methods: match rpc.methods {
+                RpcMethods::Safe => sc_service::config::RpcMethods::Safe,
+                RpcMethods::Unsafe => sc_service::config::RpcMethods::Unsafe,
+            },
+            rate_limit: rpc.rate_limit,
             rate_limit_whitelisted_ips: Vec::new(),
             rate_limit_trust_proxy_headers: false,
             max_subscriptions_per_connection: 1024,
@@ -494,43 +678,47 @@ fn create_consensus_chain_config(
             disable_batch_requests: false,
             max_batch_request_len: None,
         },
-        prometheus_listen_on: None,
+        // Substrate always starts its own endpoint when metrics are enabled, it is bound to random
+        // local port since Space Acres serves Substrate metrics together with the rest of metrics
+        prometheus_listen_on: enable_metrics
+            .then(|| SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 0)),
         telemetry_endpoints,
         force_authoring: false,
         chain_spec: chain_spec.into(),
         executor: Default::default(),
         trie_cache_size: None,
-    }
+    })
 }
 
 pub(super) async fn create_consensus_node(
     keypair: &Keypair,
     base_path: PathBuf,
-    substrate_port: u16,
+    network: &NetworkConfiguration,
+    rpc: &RpcConfiguration,
+    node_config: &NodeConfiguration,
     chain_spec: ChainSpec,
+    genesis_hash: &str,
     piece_getter: Arc<dyn PieceGetter + Send + Sync + 'static>,
     node: Node,
     maybe_node_client: &MaybeNodeClient,
+    prometheus_registry: Option<&mut Registry>,
 ) -> Result<ConsensusNode, ConsensusNodeCreationError> {
     set_default_ss58_version(&chain_spec);
 
     let pot_external_entropy = pot_external_entropy(&chain_spec)?;
     let dsn_bootstrap_nodes = dsn_bootstrap_nodes(&chain_spec)?;
 
-    let chain_info = ChainInfo {
-        chain_name: chain_spec.0.name().to_string(),
-        protocol_id: chain_spec.0.protocol_id().unwrap_or_default().to_string(),
-        token_symbol: chain_spec
-            .0
-            .properties()
-            .get("tokenSymbol")
-            .and_then(|v| v.as_str())
-            .unwrap_or_default()
-            .to_string(),
-    };
+    let chain_info = chain_info(&chain_spec);
 
-    let consensus_chain_config =
-        create_consensus_chain_config(keypair, base_path.clone(), substrate_port, chain_spec);
+    let consensus_chain_config = create_consensus_chain_config(
+        keypair,
+        base_path.clone(),
+        network,
+        rpc,
+        node_config,
+        chain_spec,
+        prometheus_registry.is_some(),
+    )?;
 
     info!(
         "📋 Chain specification: {}",
@@ -542,6 +730,7 @@ pub(super) async fn create_consensus_node(
     let sync = consensus_chain_config.network.sync_mode;
     let consensus_chain_config = Configuration::from(consensus_chain_config);
     let pause_sync = Arc::clone(&consensus_chain_config.network.pause_sync);
+    let substrate_prometheus_registry = consensus_chain_config.prometheus_registry().cloned();
 
     let (consensus_node, direct_node_client) = {
         let span = info_span!("Node");
@@ -594,7 +783,7 @@ pub(super) async fn create_consensus_node(
             }
         };
 
-        if hex::encode(partial_components.client.info().genesis_hash) != GENESIS_HASH {
+        if hex::encode(partial_components.client.info().genesis_hash) != genesis_hash {
             return Err(ConsensusNodeCreationError::IncompatibleChain {
                 compatible_chain: consensus_chain_config.base.chain_spec.name().to_string(),
             });
@@ -612,7 +801,7 @@ pub(super) async fn create_consensus_node(
         let consensus_node = subspace_service::new_full::<PosTable, _>(
             consensus_chain_config,
             partial_components,
-            None,
+            prometheus_registry,
             true,
             SlotProportion::new(3f32 / 4f32),
             None,
@@ -677,7 +866,9 @@ pub(super) async fn create_consensus_node(
     Ok(ConsensusNode::new(
         consensus_node,
         pause_sync,
+        node_config.sync_mode(),
         chain_info,
         chain_constants,
+        substrate_prometheus_registry,
     ))
 }
@@ -0,0 +1,211 @@
+//! Wiping of individual farms or parts of node data.
+//!
+//! Data can't be wiped while node and farms are using it, so wipe is scheduled to be performed on
+//! the next start before anything is opened.
+
+use crate::backend::FarmIndex;
+use crate::backend::config::{RawConfig, chain_data_path};
+use crate::backend::utils::disk_usage;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::{Path, PathBuf};
+use subspace_farmer::single_disk_farm::SingleDiskFarm;
+
+/// File next to configuration file where scheduled wipe is stored until the next start
+const PENDING_WIPE_FILE: &str = "pending-wipe.json";
+/// Piece cache file inside farm directory
+const PIECE_CACHE_FILE: &str = "piece_cache.bin";
+/// Files [`SingleDiskFarm::wipe`] deletes from farm directory, upstream doesn't expose most of
+/// these names, so the list must be kept in sync with it
+const FARM_FILES: &[&str] = &[
+    "single_disk_farm.json",
+    "identity.bin",
+    "metadata.bin",
+    "plot.bin",
+    PIECE_CACHE_FILE,
+];
+
+/// What should be wiped
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum WipeTarget {
+    /// Everything farm stores, farm will be created from scratch if still configured
+    Farm { farm_index: FarmIndex },
+    /// Piece cache of a farm, plotted sectors are preserved
+    PieceCache { farm_index: FarmIndex },
+    /// Node database, blockchain will be synced from scratch
+    NodeDatabase,
+    /// Network keypair and known peers, node will get a new peer ID
+    NodeNetwork,
+}
+
+/// Exact files and directories that will be deleted for a wipe target
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WipePlan {
+    pub target: WipeTarget,
+    /// Farm directory for [`WipeTarget::Farm`], it is wiped with [`SingleDiskFarm::wipe`] rather
+    /// than by deleting `paths` one by one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub farm_directory: Option<PathBuf>,
+    /// Existing files and directories, nothing else is touched
+    pub paths: Vec<PathBuf>,
+    /// Disk space that will be freed in bytes
+    pub size: u64,
+}
+
+impl WipePlan {
+    /// Resolve paths of the target according to configuration
+    pub async fn new(raw_config: &RawConfig, target: WipeTarget) -> anyhow::Result<Self> {
+        let chain_id = raw_config.chain().read_chain_id().await?;
+        let farm_directory = |farm_index: FarmIndex| {
+            raw_config
+                .farms()
+                .get(usize::from(farm_index))
+                .map(|farm| chain_data_path(&farm.path, chain_id.as_deref()))
+                .ok_or_else(|| anyhow::anyhow!("Farm {farm_index} is not configured"))
+        };
+        let node_directory = chain_data_path(raw_config.node_path(), chain_id.as_deref());
+
+        let (farm_directory, candidates) = match target {
+            WipeTarget::Farm { farm_index } => {
+                let directory = farm_directory(farm_index)?;
+                let candidates = FARM_FILES.iter().map(|file| directory.join(file)).collect();
+                (Some(directory), candidates)
+            }
+            WipeTarget::PieceCache { farm_index } => (
+                None,
+                vec![farm_directory(farm_index)?.join(PIECE_CACHE_FILE)],
+            ),
+            WipeTarget::NodeDatabase => (None, vec![node_directory.join("db")]),
+            WipeTarget::NodeNetwork => (None, vec![node_directory.join("network")]),
+        };
+
+        tokio::task::spawn_blocking(move || {
+            let mut paths = Vec::with_capacity(candidates.len());
+            let mut size = 0;
+            for path in candidates {
+                match path.symlink_metadata() {
+                    Ok(_) => {
+                        size += disk_usage(&path)?;
+                        paths.push(path);
+                    }
+                    Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                        // Nothing to wipe
+                    }
+                    Err(error) => {
+                        return Err(anyhow::anyhow!(
+                            "Failed to access \"{}\": {error}",
+                            path.display()
+                        ));
+                    }
+                }
+            }
+
+            Ok(Self {
+                target,
+                farm_directory,
+                paths,
+                size,
+            })
+        })
+        .await?
+    }
+
+    /// Store plan such that it is performed on the next start
+    pub async fn schedule(&self, config_file_path: &Path) -> io::Result<()> {
+        tokio::fs::write(
+            pending_wipe_path(config_file_path),
+            serde_json::to_string_pretty(self).expect("Plan serialization is infallible; qed"),
+        )
+        .await
+    }
+
+    /// Take previously scheduled plan, it is removed from disk such that failed wipe is not
+    /// retried on every start
+    pub(super) async fn take_scheduled(config_file_path: &Path) -> anyhow::Result<Option<Self>> {
+        let path = pending_wipe_path(config_file_path);
+        let contents = match tokio::fs::read_to_string(&path).await {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                return Ok(None);
+            }
+            Err(error) => {
+                return Err(anyhow::anyhow!(
+                    "Failed to read scheduled wipe from \"{}\": {error}",
+                    path.display()
+                ));
+            }
+        };
+        tokio::fs::remove_file(&path).await.map_err(|error| {
+            anyhow::anyhow!(
+                "Failed to remove scheduled wipe \"{}\": {error}",
+                path.display()
+            )
+        })?;
+
+        let plan = serde_json::from_str(&contents).map_err(|error| {
+            anyhow::anyhow!(
+                "Failed to decode scheduled wipe from \"{}\": {error}",
+                path.display()
+            )
+        })?;
+
+        Ok(Some(plan))
+    }
+}
+
+/// Wipe farm in `directory` the same way farmer does, it is not an error if farm doesn't exist
+/// anymore.
+///
+/// `paths` that were previewed to the user are removed afterwards in case upstream left any of
+/// them behind, such that exactly what was previewed is gone.
+pub(super) async fn wipe_farm(directory: &Path, paths: &[PathBuf]) -> io::Result<()> {
+    let result = tokio::task::spawn_blocking({
+        let directory = directory.to_path_buf();
+
+        move || SingleDiskFarm::wipe(&directory)
+    })
+    .await
+    .map_err(io::Error::other)?;
+
+    match result {
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+            // Nothing to wipe
+        }
+        result => result?,
+    }
+
+    for path in paths {
+        wipe_path(path).await?;
+    }
+
+    Ok(())
+}
+
+/// Delete file or directory at `path`, it is not an error if it doesn't exist anymore
+pub(super) async fn wipe_path(path: &Path) -> io::Result<()> {
+    let result = match tokio::fs::symlink_metadata(path).await {
+        Ok(metadata) => {
+            if metadata.is_dir() {
+                tokio::fs::remove_dir_all(path).await
+            } else {
+                tokio::fs::remove_file(path).await
+            }
+        }
+        Err(error) => Err(error),
+    };
+
+    match result {
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+fn pending_wipe_path(config_file_path: &Path) -> PathBuf {
+    config_file_path.with_file_name(PENDING_WIPE_FILE)
+}
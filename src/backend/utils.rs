@@ -1,5 +1,37 @@
 use event_listener_primitives::Bag;
+use std::path::Path;
 use std::sync::Arc;
+use std::{fs, io};
 
 pub(super) type HandlerFn<A> = Arc<dyn Fn(&A) + Send + Sync + 'static>;
 pub(super) type Handler<A> = Bag<HandlerFn<A>, A>;
+
+/// Total size of files at `path`, which is either a file or a directory.
+///
+/// Directories are traversed recursively without following symlinks, files that are removed in the
+/// meantime are skipped since node creates and removes database files all the time.
+pub fn disk_usage(path: &Path) -> io::Result<u64> {
+    let metadata = path.symlink_metadata()?;
+    if !metadata.is_dir() {
+        return Ok(if metadata.is_file() {
+            metadata.len()
+        } else {
+            0
+        });
+    }
+
+    let mut size = 0;
+    for entry in fs::read_dir(path)? {
+        match entry.and_then(|entry| disk_usage(&entry.path())) {
+            Ok(entry_size) => {
+                size += entry_size;
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+            Err(error) => {
+                return Err(error);
+            }
+        }
+    }
+
+    Ok(size)
+}
@@ -0,0 +1,60 @@
+mod backup;
+mod config;
+mod inspect;
+mod scrub;
+
+use crate::commands::backup::BackupCommand;
+use crate::commands::config::ConfigCommand;
+use crate::commands::inspect::InspectArgs;
+use crate::commands::scrub::ScrubArgs;
+use clap::Subcommand;
+use std::process::ExitCode;
+
+/// Commands that can be used without starting GUI, for example in provisioning scripts
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Export or import configuration, network keypair and identities of farms, for example to
+    /// restore them after reinstalling operating system
+    #[command(subcommand)]
+    Backup(BackupCommand),
+    /// Inspect, validate and edit configuration
+    #[command(subcommand)]
+    Config(ConfigCommand),
+    /// Print information about farms without starting the node, can be used while application is
+    /// running
+    Inspect(InspectArgs),
+    /// Check integrity of farms and repair them, application must not be running at the same time
+    Scrub(ScrubArgs),
+}
+
+impl Command {
+    pub fn run(self) -> ExitCode {
+        let runtime = match tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        {
+            Ok(runtime) => runtime,
+            Err(error) => {
+                eprintln!("Failed to create Tokio runtime: {error}");
+                return ExitCode::FAILURE;
+            }
+        };
+
+        let result = runtime.block_on(async move {
+            match self {
+                Command::Backup(backup_command) => backup_command.run().await,
+                Command::Config(config_command) => config_command.run().await,
+                Command::Inspect(inspect_args) => inspect_args.run().await,
+                Command::Scrub(scrub_args) => scrub_args.run().await,
+            }
+        });
+
+        match result {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(error) => {
+                eprintln!("Error: {error}");
+                ExitCode::FAILURE
+            }
+        }
+    }
+}
@@ -0,0 +1,407 @@
+use crate::backend::config::{Config, RawConfig, chain_data_path, parse_chain_id};
+use clap::{Args, Subcommand};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+
+/// Version of archive format, increased on incompatible changes
+const ARCHIVE_VERSION: u8 = 1;
+/// Files inside node directory that define node identity
+const NODE_FILES: &[&str] = &["network/secret_ed25519"];
+/// Files inside farm directory that define farm identity, plot itself is not included
+const FARM_FILES: &[&str] = &["single_disk_farm.json", "identity.bin"];
+
+#[derive(Debug, Subcommand)]
+pub enum BackupCommand {
+    /// Write configuration, chain specification of custom chain, network keypair and identities of
+    /// all farms into a single archive.
+    ///
+    /// Archive contains secret keys and must be stored securely.
+    Export(ExportArgs),
+    /// Restore configuration, network keypair and identities of farms from archive, application
+    /// must not be running at the same time
+    Import(ImportArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    /// Path to archive file
+    archive: PathBuf,
+    /// Overwrite archive file if it already exists
+    #[arg(long)]
+    force: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ImportArgs {
+    /// Path to archive file
+    archive: PathBuf,
+    /// Replace path prefix in restored configuration, like `/mnt/old-disk=/mnt/new-disk` when
+    /// mount points are different on this machine. Can be specified multiple times, the longest
+    /// matching prefix wins.
+    #[arg(long = "remap", value_parser = parse_remap)]
+    remaps: Vec<(PathBuf, PathBuf)>,
+    /// Overwrite existing configuration and identity files that differ from archive
+    #[arg(long)]
+    force: bool,
+}
+
+/// Everything needed to bring node and farms back with the same identity
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Archive {
+    version: u8,
+    raw_config: RawConfig,
+    /// Hex-encoded node files by path relative to node data directory
+    node_files: BTreeMap<PathBuf, String>,
+    /// Hex-encoded files of each farm in configuration order by path relative to farm data
+    /// directory
+    farm_files: Vec<BTreeMap<PathBuf, String>>,
+    /// Hex-encoded chain specification file of custom chain, restored to the path from
+    /// configuration since chain ID is needed to find node and farm data
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    chain_spec: Option<String>,
+}
+
+impl BackupCommand {
+    pub async fn run(self) -> anyhow::Result<()> {
+        match self {
+            BackupCommand::Export(export_args) => export_args.run().await,
+            BackupCommand::Import(import_args) => import_args.run().await,
+        }
+    }
+}
+
+impl ExportArgs {
+    async fn run(self) -> anyhow::Result<()> {
+        let config_file_path = RawConfig::default_path().await?;
+        let raw_config = RawConfig::read_from_path(&config_file_path)
+            .await?
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Configuration file \"{}\" doesn't exist",
+                    config_file_path.display()
+                )
+            })?;
+        let chain_id = raw_config.chain().read_chain_id().await?;
+
+        let chain_spec = match &raw_config.chain().chain_spec {
+            Some(chain_spec_path) => {
+                let contents = fs::read(chain_spec_path).await.map_err(|error| {
+                    anyhow::anyhow!(
+                        "Failed to read chain specification \"{}\": {error}",
+                        chain_spec_path.display()
+                    )
+                })?;
+                println!("Chain specification:");
+                println!("  Added {}", chain_spec_path.display());
+                Some(hex::encode(contents))
+            }
+            None => None,
+        };
+
+        let node_directory = chain_data_path(raw_config.node_path(), chain_id.as_deref());
+        println!("Node {}:", node_directory.display());
+        let node_files = read_files(&node_directory, NODE_FILES).await?;
+
+        let mut farm_files = Vec::with_capacity(raw_config.farms().len());
+        for farm in raw_config.farms() {
+            let farm_directory = chain_data_path(&farm.path, chain_id.as_deref());
+            println!("Farm {}:", farm_directory.display());
+            farm_files.push(read_files(&farm_directory, FARM_FILES).await?);
+        }
+
+        let archive = Archive {
+            version: ARCHIVE_VERSION,
+            raw_config,
+            node_files,
+            farm_files,
+            chain_spec,
+        };
+
+        let mut options = fs::OpenOptions::new();
+        options.write(true);
+        if self.force {
+            options.create(true).truncate(true);
+        } else {
+            options.create_new(true);
+        }
+        // Archive contains secret keys
+        #[cfg(unix)]
+        options.mode(0o600);
+        options
+            .open(&self.archive)
+            .await
+            .map_err(|error| {
+                anyhow::anyhow!(
+                    "Failed to create archive \"{}\": {error}",
+                    self.archive.display()
+                )
+            })?
+            .write_all(
+                serde_json::to_string_pretty(&archive)
+                    .expect("Archive serialization is infallible; qed")
+                    .as_bytes(),
+            )
+            .await?;
+
+        println!("Archive written to \"{}\"", self.archive.display());
+
+        Ok(())
+    }
+}
+
+impl ImportArgs {
+    async fn run(self) -> anyhow::Result<()> {
+        let contents = fs::read_to_string(&self.archive).await.map_err(|error| {
+            anyhow::anyhow!(
+                "Failed to read archive \"{}\": {error}",
+                self.archive.display()
+            )
+        })?;
+        let Archive {
+            version,
+            mut raw_config,
+            node_files,
+            farm_files,
+            chain_spec,
+        } = serde_json::from_str(&contents).map_err(|error| {
+            anyhow::anyhow!(
+                "Failed to decode archive \"{}\": {error}",
+                self.archive.display()
+            )
+        })?;
+        if version != ARCHIVE_VERSION {
+            return Err(anyhow::anyhow!(
+                "Archive version {version} is not supported, expected {ARCHIVE_VERSION}"
+            ));
+        }
+
+        raw_config = raw_config.upgrade();
+        self.remap_paths(&mut raw_config);
+
+        let mut files = Vec::new();
+        // Chain specification might not exist on this machine yet, so chain ID is read from archive
+        let chain_id = match (raw_config.chain().chain_spec, chain_spec) {
+            (Some(chain_spec_path), Some(contents)) => {
+                let contents = hex::decode(&contents).map_err(|error| {
+                    anyhow::anyhow!(
+                        "Archive contains invalid contents of chain specification: {error}"
+                    )
+                })?;
+                let chain_id =
+                    tokio::task::block_in_place(|| parse_chain_id(&chain_spec_path, &contents))?;
+                files.push((chain_spec_path, contents));
+                Some(chain_id)
+            }
+            _ => raw_config.chain().read_chain_id().await?,
+        };
+        let node_directory = chain_data_path(raw_config.node_path(), chain_id.as_deref());
+        decode_files(&node_directory, node_files, &mut files)?;
+        if farm_files.len() != raw_config.farms().len() {
+            return Err(anyhow::anyhow!(
+                "Archive contains files of {} farms, but configuration has {} farms",
+                farm_files.len(),
+                raw_config.farms().len()
+            ));
+        }
+        for (farm, farm_files) in raw_config.farms().iter().zip(farm_files) {
+            let farm_directory = chain_data_path(&farm.path, chain_id.as_deref());
+            decode_files(&farm_directory, farm_files, &mut files)?;
+        }
+
+        let config_file_path = RawConfig::default_path().await?;
+
+        // Check everything before writing anything, such that restore is not left half-way
+        if !self.force {
+            let mut conflicts = Vec::new();
+            if let Some(existing_raw_config) = RawConfig::read_from_path(&config_file_path).await?
+                && existing_raw_config != raw_config
+            {
+                conflicts.push(config_file_path.clone());
+            }
+            for (path, contents) in &files {
+                if is_different(path, contents).await? {
+                    conflicts.push(path.clone());
+                }
+            }
+
+            if !conflicts.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "Files already exist and differ from archive, use `--force` to overwrite \
+                    them:\n{}",
+                    conflicts
+                        .iter()
+                        .map(|path| format!("  {}", path.display()))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                ));
+            }
+        }
+
+        for (path, contents) in &files {
+            write_file(path, contents).await?;
+            println!("Restored {}", path.display());
+        }
+        raw_config
+            .write_to_path(&config_file_path)
+            .await
+            .map_err(|error| {
+                anyhow::anyhow!(
+                    "Failed to write config to \"{}\": {error}",
+                    config_file_path.display()
+                )
+            })?;
+        println!("Restored {}", config_file_path.display());
+
+        // Farm disks might not be mounted yet, so this is not an error
+        if let Err(error) = Config::try_from_raw_config(&raw_config).await {
+            println!("Warning: restored configuration is not valid yet: {error}");
+        }
+
+        Ok(())
+    }
+
+    fn remap_paths(&self, raw_config: &mut RawConfig) {
+        let RawConfig::V1 {
+            node_path,
+            farms,
+            chain,
+            ..
+        } = raw_config
+        else {
+            unreachable!("Config was upgraded to the latest version above; qed");
+        };
+
+        for path in [node_path]
+            .into_iter()
+            .chain(farms.iter_mut().map(|farm| &mut farm.path))
+            .chain(chain.chain_spec.as_mut())
+        {
+            if let Some(new_path) = self.remap_path(path) {
+                println!("Remapped {} to {}", path.display(), new_path.display());
+                *path = new_path;
+            }
+        }
+    }
+
+    fn remap_path(&self, path: &Path) -> Option<PathBuf> {
+        self.remaps
+            .iter()
+            .filter_map(|(from, to)| {
+                let suffix = path.strip_prefix(from).ok()?;
+                Some((from.components().count(), to.join(suffix)))
+            })
+            .max_by_key(|(prefix_length, _new_path)| *prefix_length)
+            .map(|(_prefix_length, new_path)| new_path)
+    }
+}
+
+/// Read existing files relative to `directory`, missing files are skipped
+async fn read_files(
+    directory: &Path,
+    file_names: &[&str],
+) -> anyhow::Result<BTreeMap<PathBuf, String>> {
+    let mut files = BTreeMap::new();
+
+    for file_name in file_names {
+        let path = directory.join(file_name);
+        match fs::read(&path).await {
+            Ok(contents) => {
+                println!("  Added {}", path.display());
+                files.insert(PathBuf::from(file_name), hex::encode(contents));
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                println!("  Skipped {}, it doesn't exist", path.display());
+            }
+            Err(error) => {
+                return Err(anyhow::anyhow!(
+                    "Failed to read \"{}\": {error}",
+                    path.display()
+                ));
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// Decode archived files and resolve their paths relative to `directory`
+fn decode_files(
+    directory: &Path,
+    archived_files: BTreeMap<PathBuf, String>,
+    files: &mut Vec<(PathBuf, Vec<u8>)>,
+) -> anyhow::Result<()> {
+    for (relative_path, contents) in archived_files {
+        // Archive might be crafted to write outside of data directories
+        if !relative_path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(anyhow::anyhow!(
+                "Archive contains invalid path \"{}\"",
+                relative_path.display()
+            ));
+        }
+
+        let contents = hex::decode(&contents).map_err(|error| {
+            anyhow::anyhow!(
+                "Archive contains invalid contents of \"{}\": {error}",
+                relative_path.display()
+            )
+        })?;
+        files.push((directory.join(relative_path), contents));
+    }
+
+    Ok(())
+}
+
+async fn is_different(path: &Path, contents: &[u8]) -> anyhow::Result<bool> {
+    match fs::read(path).await {
+        Ok(existing_contents) => Ok(existing_contents != contents),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(error) => Err(anyhow::anyhow!(
+            "Failed to read \"{}\": {error}",
+            path.display()
+        )),
+    }
+}
+
+async fn write_file(path: &Path, contents: &[u8]) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await.map_err(|error| {
+            anyhow::anyhow!(
+                "Failed to create directory \"{}\": {error}",
+                parent.display()
+            )
+        })?;
+    }
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).truncate(true).create(true);
+    // Identity files contain secret keys
+    #[cfg(unix)]
+    options.mode(0o600);
+    let result: io::Result<()> = try {
+        options.open(path).await?.write_all(contents).await?;
+    };
+
+    result.map_err(|error| anyhow::anyhow!("Failed to write \"{}\": {error}", path.display()))
+}
+
+fn parse_remap(s: &str) -> Result<(PathBuf, PathBuf), String> {
+    let Some((from, to)) = s.split_once('=') else {
+        return Err(format!(
+            "Remap \"{s}\" must be in format `old-path=new-path`"
+        ));
+    };
+
+    if from.is_empty() || to.is_empty() {
+        return Err(format!("Remap \"{s}\" must have both old and new path"));
+    }
+
+    Ok((PathBuf::from(from), PathBuf::from(to)))
+}
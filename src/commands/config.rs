@@ -0,0 +1,640 @@
+use crate::backend::config::{
+    Config, FARM_SIZE_ALLOCATION_MARGIN, FARM_SIZE_DIFF_MARGIN, Farm, NodePruning,
+    PlottingCpuConfiguration, PlottingThreadPriority, PlottingWindow, RawConfig, RpcMethods,
+    SyncMode, format_cpu_cores, resolve_farm_size,
+};
+use clap::{Args, Subcommand};
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::num::{NonZeroU32, NonZeroUsize};
+use std::path::{Path, PathBuf};
+use subspace_core_primitives::BlockNumber;
+use subspace_farmer::utils::thread_pool_core_indices;
+use subspace_networking::libp2p::Multiaddr;
+
+/// Substrate's default RPC port on localhost
+const DEFAULT_RPC_LISTEN_ON: SocketAddr =
+    SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 9944));
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// Check that configuration is valid, exits with non-zero status code if it is not
+    Validate,
+    /// Print effective configuration, including farm sizes resolved from percentages
+    Show,
+    /// Update configuration fields, configuration is created if it doesn't exist yet
+    Set(SetArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct SetArgs {
+    /// Reward address in SS58 format
+    #[arg(long)]
+    reward_address: Option<String>,
+    /// Path where node data is stored
+    #[arg(long)]
+    node_path: Option<PathBuf>,
+    /// Substrate (blockchain) P2P port
+    #[arg(long)]
+    substrate_port: Option<u16>,
+    /// Subspace (DSN) P2P port
+    #[arg(long)]
+    subspace_port: Option<u16>,
+    /// Allow connecting to peers on private IP addresses, useful for farms on the same LAN
+    #[arg(long)]
+    allow_private_ips: Option<bool>,
+    /// Additional DSN bootstrap node multiaddr, can be specified multiple times, replaces existing
+    /// ones
+    #[arg(long = "dsn-bootstrap-node")]
+    dsn_bootstrap_nodes: Vec<Multiaddr>,
+    /// DSN reserved peer multiaddr with `/p2p/<peer-id>`, can be specified multiple times, replaces
+    /// existing ones
+    #[arg(long = "dsn-reserved-peer")]
+    dsn_reserved_peers: Vec<Multiaddr>,
+    /// DSN external address to announce (like public IP with forwarded port), can be specified
+    /// multiple times, replaces existing ones
+    #[arg(long = "dsn-external-address")]
+    dsn_external_addresses: Vec<Multiaddr>,
+    /// Substrate reserved node multiaddr with `/p2p/<peer-id>`, can be specified multiple times,
+    /// replaces existing ones
+    #[arg(long = "substrate-reserved-node")]
+    substrate_reserved_nodes: Vec<Multiaddr>,
+    /// Substrate public address to announce (like public IP with forwarded port), can be specified
+    /// multiple times, replaces existing ones
+    #[arg(long = "substrate-public-address")]
+    substrate_public_addresses: Vec<Multiaddr>,
+    /// Remove all custom bootstrap nodes, reserved peers and external/public addresses
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "dsn_bootstrap_nodes",
+            "dsn_reserved_peers",
+            "dsn_external_addresses",
+            "substrate_reserved_nodes",
+            "substrate_public_addresses",
+        ]
+    )]
+    clear_network_addresses: bool,
+    /// Address to serve Prometheus metrics on (like `127.0.0.1:9616`)
+    #[arg(long)]
+    prometheus_listen_on: Option<SocketAddr>,
+    /// Disable Prometheus metrics
+    #[arg(long, conflicts_with = "prometheus_listen_on")]
+    disable_prometheus: bool,
+    /// Enable node RPC on default localhost address (`127.0.0.1:9944`) unless already enabled
+    #[arg(long, conflicts_with_all = ["rpc_listen_on", "disable_rpc"])]
+    enable_rpc: bool,
+    /// Address to serve node RPC on (like `127.0.0.1:9944`)
+    #[arg(long)]
+    rpc_listen_on: Option<SocketAddr>,
+    /// Disable node RPC
+    #[arg(long, conflicts_with = "rpc_listen_on")]
+    disable_rpc: bool,
+    /// RPC methods to expose: `safe` or `unsafe`, unsafe methods are only allowed on localhost
+    #[arg(long, value_parser = parse_rpc_methods)]
+    rpc_methods: Option<RpcMethods>,
+    /// Origin allowed to access RPC from browsers, can be specified multiple times, replaces
+    /// existing ones. Use `all` to allow any origin.
+    #[arg(long = "rpc-cors")]
+    rpc_cors: Vec<String>,
+    /// Maximum number of RPC calls per minute for each connection, `0` to remove the limit
+    #[arg(long)]
+    rpc_rate_limit: Option<u32>,
+    /// Node name shown in telemetry
+    #[arg(long)]
+    node_name: Option<String>,
+    /// Send node telemetry to endpoints from chain specification
+    #[arg(long)]
+    telemetry: Option<bool>,
+    /// Keep state and blocks of the whole chain (archive node), useful for indexers. Switching
+    /// between archive and pruned node requires wiping node data.
+    #[arg(long)]
+    archive: Option<bool>,
+    /// Number of recent blocks to keep state of, at least the default of 140000
+    #[arg(long)]
+    state_pruning: Option<BlockNumber>,
+    /// Number of recent finalized blocks to keep bodies of, default is 256
+    #[arg(long)]
+    blocks_pruning: Option<BlockNumber>,
+    /// Reset state and blocks pruning to defaults
+    #[arg(long, conflicts_with_all = ["state_pruning", "blocks_pruning"])]
+    default_pruning: bool,
+    /// Sync mode: `snap` (default) or `full` to download and verify all blocks since genesis, only
+    /// applies when node is synced from scratch. Archive node always uses full sync.
+    #[arg(long, value_parser = parse_sync_mode)]
+    sync_mode: Option<SyncMode>,
+    /// Farm through existing node's farmer RPC at `ws://` or `wss://` URL instead of running
+    /// embedded node, node must run with `--rpc-methods unsafe` for farmer RPC to be available
+    #[arg(long)]
+    external_rpc_url: Option<String>,
+    /// Switch back to embedded node from external node
+    #[arg(long, conflicts_with = "external_rpc_url")]
+    embedded_node: bool,
+    /// Path to chain specification JSON file of a custom chain (testnet or devnet), node and farm
+    /// data of custom chains is stored in `chains/<chain-id>` subdirectories
+    #[arg(long)]
+    chain_spec: Option<PathBuf>,
+    /// Switch back to mainnet from a custom chain
+    #[arg(long, conflicts_with = "chain_spec")]
+    mainnet: bool,
+    /// Local time window when plotting is allowed in format `[days ]HH:MM-HH:MM`, like
+    /// `Mon-Fri 18:00-08:00` or `Sat,Sun 00:00-00:00` (whole day). Plotting is paused outside of
+    /// windows. Can be specified multiple times, replaces existing ones.
+    #[arg(long = "plotting-window")]
+    plotting_windows: Vec<PlottingWindow>,
+    /// Remove plotting schedule, such that plotting is never paused automatically
+    #[arg(long, conflicts_with = "plotting_windows")]
+    clear_plotting_schedule: bool,
+    /// CPU cores used for plotting, like `0,2-5 6-11`: groups of cores are separated by spaces and
+    /// each group gets its own thread pool. Cores are grouped by L3 cache by default.
+    #[arg(long)]
+    plotting_cpu_cores: Option<String>,
+    /// CPU cores used for replotting in the same format, plotting CPU cores are used by default
+    #[arg(long)]
+    replotting_cpu_cores: Option<String>,
+    /// Number of threads in each plotting thread pool, ignored if CPU cores are set
+    #[arg(long)]
+    plotting_thread_pool_size: Option<NonZeroUsize>,
+    /// Number of threads in each replotting thread pool, ignored if CPU cores are set
+    #[arg(long)]
+    replotting_thread_pool_size: Option<NonZeroUsize>,
+    /// Number of records encoded concurrently within a sector
+    #[arg(long)]
+    record_encoding_concurrency: Option<NonZeroUsize>,
+    /// Number of sectors downloaded concurrently for plotting
+    #[arg(long)]
+    sector_downloading_concurrency: Option<NonZeroUsize>,
+    /// Priority of plotting threads: `min` (default), `default` or `max`
+    #[arg(long, value_parser = parse_plotting_thread_priority)]
+    plotting_thread_priority: Option<PlottingThreadPriority>,
+    /// Reset plotting CPU cores, thread pools, concurrency and thread priority to defaults
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "plotting_cpu_cores",
+            "replotting_cpu_cores",
+            "plotting_thread_pool_size",
+            "replotting_thread_pool_size",
+            "record_encoding_concurrency",
+            "sector_downloading_concurrency",
+            "plotting_thread_priority",
+        ]
+    )]
+    default_plotting_cpu: bool,
+    /// Farm in format `path=size`, where size is either absolute (like `2TB`) or percentage of
+    /// free disk space (like `80%`). Can be specified multiple times, replaces all existing farms.
+    /// Farms with the same path as existing ones keep their other options, like cache percentage
+    /// and reward address, only size is updated.
+    #[arg(long = "farm", value_parser = parse_farm)]
+    farms: Vec<Farm>,
+}
+
+impl ConfigCommand {
+    pub async fn run(self) -> anyhow::Result<()> {
+        let config_file_path = RawConfig::default_path().await?;
+        let maybe_raw_config = RawConfig::read_from_path(&config_file_path).await?;
+
+        match self {
+            ConfigCommand::Validate => {
+                let raw_config = maybe_raw_config.ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Configuration file \"{}\" doesn't exist",
+                        config_file_path.display()
+                    )
+                })?;
+
+                Config::try_from_raw_config(&raw_config).await?;
+
+                println!("Configuration is valid");
+            }
+            ConfigCommand::Show => {
+                let raw_config = maybe_raw_config.ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Configuration file \"{}\" doesn't exist",
+                        config_file_path.display()
+                    )
+                })?;
+
+                let config = Config::try_from_raw_config(&raw_config).await?;
+
+                print_config(&config_file_path, &raw_config, &config).await?;
+            }
+            ConfigCommand::Set(set_args) => {
+                let mut raw_config = maybe_raw_config.unwrap_or_default();
+                set_args.apply(&mut raw_config);
+
+                // Do not write configuration that will not work
+                Config::try_from_raw_config(&raw_config).await?;
+
+                raw_config
+                    .write_to_path(&config_file_path)
+                    .await
+                    .map_err(|error| {
+                        anyhow::anyhow!(
+                            "Failed to write config to \"{}\": {}",
+                            config_file_path.display(),
+                            error
+                        )
+                    })?;
+
+                println!(
+                    "Configuration written to \"{}\"",
+                    config_file_path.display()
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl SetArgs {
+    fn apply(self, raw_config: &mut RawConfig) {
+        let RawConfig::V1 {
+            reward_address,
+            node_path,
+            farms,
+            farmer,
+            network,
+            metrics,
+            chain,
+            rpc,
+            node,
+            ..
+        } = raw_config
+        else {
+            unreachable!("Config is upgraded to the latest version when read; qed");
+        };
+
+        if let Some(new_reward_address) = self.reward_address {
+            *reward_address = new_reward_address;
+        }
+        if let Some(new_node_path) = self.node_path {
+            *node_path = new_node_path;
+        }
+        if let Some(substrate_port) = self.substrate_port {
+            network.substrate_port = substrate_port;
+        }
+        if let Some(subspace_port) = self.subspace_port {
+            network.subspace_port = subspace_port;
+        }
+        if let Some(allow_private_ips) = self.allow_private_ips {
+            network.allow_private_ips = allow_private_ips;
+        }
+        if self.clear_network_addresses {
+            network.dsn_bootstrap_nodes.clear();
+            network.dsn_reserved_peers.clear();
+            network.dsn_external_addresses.clear();
+            network.substrate_reserved_nodes.clear();
+            network.substrate_public_addresses.clear();
+        }
+        if !self.dsn_bootstrap_nodes.is_empty() {
+            network.dsn_bootstrap_nodes = self.dsn_bootstrap_nodes;
+        }
+        if !self.dsn_reserved_peers.is_empty() {
+            network.dsn_reserved_peers = self.dsn_reserved_peers;
+        }
+        if !self.dsn_external_addresses.is_empty() {
+            network.dsn_external_addresses = self.dsn_external_addresses;
+        }
+        if !self.substrate_reserved_nodes.is_empty() {
+            network.substrate_reserved_nodes = self.substrate_reserved_nodes;
+        }
+        if !self.substrate_public_addresses.is_empty() {
+            network.substrate_public_addresses = self.substrate_public_addresses;
+        }
+        if self.clear_plotting_schedule {
+            farmer.plotting_schedule.windows.clear();
+        }
+        if !self.plotting_windows.is_empty() {
+            farmer.plotting_schedule.windows = self.plotting_windows;
+        }
+        if self.default_plotting_cpu {
+            farmer.plotting_cpu = PlottingCpuConfiguration::default();
+        }
+        if let Some(plotting_cpu_cores) = self.plotting_cpu_cores {
+            farmer.plotting_cpu.plotting_cpu_cores = Some(plotting_cpu_cores);
+        }
+        if let Some(replotting_cpu_cores) = self.replotting_cpu_cores {
+            farmer.plotting_cpu.replotting_cpu_cores = Some(replotting_cpu_cores);
+        }
+        if let Some(plotting_thread_pool_size) = self.plotting_thread_pool_size {
+            farmer.plotting_cpu.plotting_thread_pool_size = Some(plotting_thread_pool_size);
+        }
+        if let Some(replotting_thread_pool_size) = self.replotting_thread_pool_size {
+            farmer.plotting_cpu.replotting_thread_pool_size = Some(replotting_thread_pool_size);
+        }
+        if let Some(record_encoding_concurrency) = self.record_encoding_concurrency {
+            farmer.plotting_cpu.record_encoding_concurrency = Some(record_encoding_concurrency);
+        }
+        if let Some(sector_downloading_concurrency) = self.sector_downloading_concurrency {
+            farmer.plotting_cpu.sector_downloading_concurrency =
+                Some(sector_downloading_concurrency);
+        }
+        if let Some(plotting_thread_priority) = self.plotting_thread_priority {
+            farmer.plotting_cpu.thread_priority = plotting_thread_priority;
+        }
+        if let Some(prometheus_listen_on) = self.prometheus_listen_on {
+            metrics.prometheus_listen_on = Some(prometheus_listen_on);
+        }
+        if self.disable_prometheus {
+            metrics.prometheus_listen_on = None;
+        }
+        if self.enable_rpc && rpc.listen_on.is_none() {
+            rpc.listen_on = Some(DEFAULT_RPC_LISTEN_ON);
+        }
+        if let Some(rpc_listen_on) = self.rpc_listen_on {
+            rpc.listen_on = Some(rpc_listen_on);
+        }
+        if self.disable_rpc {
+            rpc.listen_on = None;
+        }
+        if let Some(rpc_methods) = self.rpc_methods {
+            rpc.methods = rpc_methods;
+        }
+        if !self.rpc_cors.is_empty() {
+            rpc.cors = self.rpc_cors;
+        }
+        if let Some(rpc_rate_limit) = self.rpc_rate_limit {
+            rpc.rate_limit = NonZeroU32::new(rpc_rate_limit);
+        }
+        if let Some(node_name) = self.node_name {
+            node.name = Some(node_name);
+        }
+        if let Some(telemetry) = self.telemetry {
+            node.disable_telemetry = !telemetry;
+        }
+        if let Some(archive) = self.archive {
+            node.archive = archive;
+        }
+        if self.default_pruning {
+            node.state_pruning = None;
+            node.blocks_pruning = None;
+        }
+        if let Some(state_pruning) = self.state_pruning {
+            node.state_pruning = Some(state_pruning);
+        }
+        if let Some(blocks_pruning) = self.blocks_pruning {
+            node.blocks_pruning = Some(blocks_pruning);
+        }
+        if let Some(sync_mode) = self.sync_mode {
+            node.sync_mode = sync_mode;
+        }
+        if let Some(external_rpc_url) = self.external_rpc_url {
+            node.external_rpc_url = Some(external_rpc_url);
+        }
+        if self.embedded_node {
+            node.external_rpc_url = None;
+        }
+        if let Some(chain_spec) = self.chain_spec {
+            chain.chain_spec = Some(chain_spec);
+        }
+        if self.mainnet {
+            chain.chain_spec = None;
+        }
+        if !self.farms.is_empty() {
+            *farms = self
+                .farms
+                .into_iter()
+                .map(
+                    |new_farm| match farms.iter().find(|farm| farm.path == new_farm.path) {
+                        Some(farm) => Farm {
+                            size: new_farm.size,
+                            ..farm.clone()
+                        },
+                        None => new_farm,
+                    },
+                )
+                .collect();
+        }
+    }
+}
+
+fn parse_rpc_methods(s: &str) -> Result<RpcMethods, String> {
+    match s {
+        "safe" => Ok(RpcMethods::Safe),
+        "unsafe" => Ok(RpcMethods::Unsafe),
+        _ => Err(format!(
+            "RPC methods \"{s}\" must be either `safe` or `unsafe`"
+        )),
+    }
+}
+
+fn parse_sync_mode(s: &str) -> Result<SyncMode, String> {
+    match s {
+        "snap" => Ok(SyncMode::Snap),
+        "full" => Ok(SyncMode::Full),
+        _ => Err(format!("Sync mode \"{s}\" must be either `snap` or `full`")),
+    }
+}
+
+fn parse_plotting_thread_priority(s: &str) -> Result<PlottingThreadPriority, String> {
+    match s {
+        "min" => Ok(PlottingThreadPriority::Min),
+        "default" => Ok(PlottingThreadPriority::Default),
+        "max" => Ok(PlottingThreadPriority::Max),
+        _ => Err(format!(
+            "Plotting thread priority \"{s}\" must be one of `min`, `default` or `max`"
+        )),
+    }
+}
+
+fn parse_farm(s: &str) -> Result<Farm, String> {
+    let Some((path, size)) = s.rsplit_once('=') else {
+        return Err(format!("Farm \"{s}\" must be in format `path=size`"));
+    };
+
+    if path.is_empty() || size.is_empty() {
+        return Err(format!("Farm \"{s}\" must have both path and size"));
+    }
+
+    Ok(Farm {
+        path: PathBuf::from(path),
+        size: size.to_string(),
+        ..Farm::default()
+    })
+}
+
+async fn print_config(
+    config_file_path: &Path,
+    raw_config: &RawConfig,
+    config: &Config,
+) -> anyhow::Result<()> {
+    println!("Configuration file: {}", config_file_path.display());
+    println!("Reward address: {}", raw_config.reward_address());
+    match (&config.chain.chain_spec, &config.chain_id) {
+        (Some(chain_spec), Some(chain_id)) => {
+            println!("Chain: {chain_id} ({})", chain_spec.display());
+        }
+        _ => {
+            println!("Chain: mainnet");
+        }
+    }
+    println!("Node path: {}", config.node_path.display());
+    if let Some(node_name) = &config.node.name {
+        println!("Node name: {node_name}");
+    }
+    println!("Telemetry: {}", !config.node.disable_telemetry);
+    match config.node.pruning() {
+        NodePruning::Pruned {
+            state_pruning,
+            blocks_pruning,
+        } => {
+            println!(
+                "Node pruning: state of last {state_pruning} blocks, bodies of last \
+                {blocks_pruning} finalized blocks"
+            );
+        }
+        NodePruning::Archive => {
+            println!("Node pruning: archive (nothing is pruned)");
+        }
+    }
+    println!("Node sync mode: {:?}", config.node.sync_mode());
+    if let Some(external_rpc_url) = &config.node.external_rpc_url {
+        println!("External node RPC: {external_rpc_url}");
+    }
+    println!("Substrate port: {}", config.network.substrate_port);
+    println!("Subspace port: {}", config.network.subspace_port);
+    println!("Faster networking: {}", config.network.faster_networking);
+    println!("Allow private IPs: {}", config.network.allow_private_ips);
+    for (name, addresses) in [
+        ("DSN bootstrap nodes", &config.network.dsn_bootstrap_nodes),
+        ("DSN reserved peers", &config.network.dsn_reserved_peers),
+        (
+            "DSN external addresses",
+            &config.network.dsn_external_addresses,
+        ),
+        (
+            "Substrate reserved nodes",
+            &config.network.substrate_reserved_nodes,
+        ),
+        (
+            "Substrate public addresses",
+            &config.network.substrate_public_addresses,
+        ),
+    ] {
+        if !addresses.is_empty() {
+            println!("{name}:");
+            for address in addresses {
+                println!("  {address}");
+            }
+        }
+    }
+    println!(
+        "Reduce plotting CPU load: {}",
+        config.reduce_plotting_cpu_load
+    );
+    println!(
+        "Detected L3 cache groups: {}",
+        format_cpu_cores(&thread_pool_core_indices(None, None))
+    );
+    let plotting_cpu = &config.plotting_cpu;
+    for (name, cpu_cores) in [
+        ("Plotting CPU cores", &plotting_cpu.plotting_cpu_cores),
+        ("Replotting CPU cores", &plotting_cpu.replotting_cpu_cores),
+    ] {
+        if let Some(cpu_cores) = cpu_cores {
+            println!("{name}: {cpu_cores}");
+        }
+    }
+    for (name, value) in [
+        (
+            "Plotting thread pool size",
+            plotting_cpu.plotting_thread_pool_size,
+        ),
+        (
+            "Replotting thread pool size",
+            plotting_cpu.replotting_thread_pool_size,
+        ),
+        (
+            "Record encoding concurrency",
+            plotting_cpu.record_encoding_concurrency,
+        ),
+        (
+            "Sector downloading concurrency",
+            plotting_cpu.sector_downloading_concurrency,
+        ),
+    ] {
+        if let Some(value) = value {
+            println!("{name}: {value}");
+        }
+    }
+    println!(
+        "Plotting thread priority: {:?}",
+        plotting_cpu.thread_priority
+    );
+    if config.plotting_schedule.is_empty() {
+        println!("Plotting schedule: not restricted");
+    } else {
+        println!("Plotting schedule (local time, paused outside of these windows):");
+        for window in &config.plotting_schedule.windows {
+            println!("  {window}");
+        }
+    }
+    match config.rpc.listen_on {
+        Some(rpc_listen_on) => {
+            println!(
+                "Node RPC: {rpc_listen_on} ({:?} methods)",
+                config.rpc.methods
+            );
+            if !config.rpc.cors.is_empty() {
+                println!("  CORS: {}", config.rpc.cors.join(", "));
+            }
+            if let Some(rate_limit) = config.rpc.rate_limit {
+                println!("  Rate limit: {rate_limit} calls/minute");
+            }
+        }
+        None => {
+            println!("Node RPC: disabled");
+        }
+    }
+    match config.metrics.prometheus_listen_on {
+        Some(prometheus_listen_on) => {
+            println!("Prometheus metrics: {prometheus_listen_on}");
+        }
+        None => {
+            println!("Prometheus metrics: disabled");
+        }
+    }
+    println!(
+        "Farms (percentage sizes exclude {} allocation margin, changes within {} are ignored):",
+        bytesize::to_string(FARM_SIZE_ALLOCATION_MARGIN, true),
+        bytesize::to_string(FARM_SIZE_DIFF_MARGIN, true),
+    );
+
+    for (farm_index, (farm, disk_farm)) in raw_config.farms().iter().zip(&config.farms).enumerate()
+    {
+        let resolved_farm_size = resolve_farm_size(farm, config.chain_id.as_deref()).await?;
+
+        println!("  {farm_index}: {}", disk_farm.directory.display());
+        println!("    Configured size: {}", farm.size);
+        println!(
+            "    Available space (including farm): {}",
+            bytesize::to_string(resolved_farm_size.available_space, true)
+        );
+        println!(
+            "    Currently used by farm: {}",
+            bytesize::to_string(resolved_farm_size.effective_disk_usage, true)
+        );
+        println!(
+            "    Target size: {}",
+            bytesize::to_string(resolved_farm_size.target_size, true)
+        );
+        println!(
+            "    Allocated space: {} ({} bytes)",
+            bytesize::to_string(resolved_farm_size.allocated_space, true),
+            resolved_farm_size.allocated_space
+        );
+        println!("    Piece cache percentage: {}%", farm.cache_percentage());
+        println!("    Read mode: {:?}", farm.read_mode());
+        println!("    Farming threads: {}", farm.farming_threads());
+        println!(
+            "    Max plotting sectors concurrently: {}",
+            farm.max_plotting_sectors()
+        );
+        if let Some(reward_address) = &farm.reward_address {
+            println!("    Reward address: {reward_address}");
+        }
+    }
+
+    Ok(())
+}
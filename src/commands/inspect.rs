@@ -0,0 +1,168 @@
+use crate::backend::config::{Config, RawConfig, chain_data_path};
+use crate::backend::configured_genesis_hash;
+use crate::backend::utils::disk_usage;
+use clap::Args;
+use std::fs;
+use std::path::{Path, PathBuf};
+use subspace_farmer::single_disk_farm::{
+    SingleDiskFarm, SingleDiskFarmInfo, SingleDiskFarmSummary,
+};
+use subspace_farmer_components::sector::sector_size;
+
+/// File that is preallocated for all sectors farm can hold
+const PLOT_FILE: &str = "plot.bin";
+
+#[derive(Debug, Args)]
+pub struct InspectArgs {
+    /// Farm directories to inspect, all configured farms are inspected by default
+    farms: Vec<PathBuf>,
+}
+
+impl InspectArgs {
+    pub async fn run(self) -> anyhow::Result<()> {
+        let config_file_path = RawConfig::default_path().await?;
+        let maybe_raw_config = RawConfig::read_from_path(&config_file_path).await?;
+        // Configuration is only used for reference, so farms can still be inspected when some of
+        // the configured paths are missing or not writable
+        let maybe_config = match &maybe_raw_config {
+            Some(raw_config) => match Config::try_from_raw_config(raw_config).await {
+                Ok(config) => Some(config),
+                Err(error) => {
+                    println!(
+                        "Warning: configuration is not valid, farms are not compared with \
+                        configured chain: {error}"
+                    );
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let directories = if self.farms.is_empty() {
+            let raw_config = maybe_raw_config.as_ref().ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Configuration file \"{}\" doesn't exist, specify farm directories explicitly",
+                    config_file_path.display()
+                )
+            })?;
+
+            match &maybe_config {
+                Some(config) => config
+                    .farms
+                    .iter()
+                    .map(|farm| farm.directory.clone())
+                    .collect(),
+                None => {
+                    let chain_id = raw_config.chain().read_chain_id().await?;
+
+                    raw_config
+                        .farms()
+                        .iter()
+                        .map(|farm| chain_data_path(&farm.path, chain_id.as_deref()))
+                        .collect()
+                }
+            }
+        } else {
+            self.farms
+        };
+
+        if let Some(raw_config) = &maybe_raw_config {
+            // Farms are not tied to reward address, it is printed for reference only
+            println!("Configured reward address: {}", raw_config.reward_address());
+        }
+
+        let maybe_genesis_hash = match &maybe_config {
+            Some(config) => match configured_genesis_hash(config).await {
+                Ok(genesis_hash) => Some(genesis_hash),
+                Err(error) => {
+                    println!(
+                        "Warning: failed to derive genesis hash of configured chain, farms are not \
+                        compared with it: {error}"
+                    );
+                    None
+                }
+            },
+            None => None,
+        };
+
+        for directory in directories {
+            match SingleDiskFarm::collect_summary(directory) {
+                SingleDiskFarmSummary::Found { info, directory } => {
+                    print_farm_info(&directory, &info, maybe_genesis_hash.as_deref());
+                }
+                SingleDiskFarmSummary::NotFound { directory } => {
+                    println!("Farm {}:", directory.display());
+                    println!("  No farm found in this directory");
+                }
+                SingleDiskFarmSummary::Error { directory, error } => {
+                    println!("Farm {}:", directory.display());
+                    println!("  Failed to read farm info: {error}");
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn print_farm_info(directory: &Path, info: &SingleDiskFarmInfo, maybe_genesis_hash: Option<&str>) {
+    println!("Farm {}:", directory.display());
+    println!("  ID: {}", info.id());
+
+    let genesis_hash = hex::encode(info.genesis_hash());
+    match maybe_genesis_hash {
+        Some(expected_genesis_hash) if expected_genesis_hash == genesis_hash => {
+            println!("  Genesis hash: 0x{genesis_hash} (matches configured chain)");
+        }
+        Some(expected_genesis_hash) => {
+            println!(
+                "  Genesis hash: 0x{genesis_hash} (does NOT match configured chain \
+                0x{expected_genesis_hash})"
+            );
+        }
+        None => {
+            println!("  Genesis hash: 0x{genesis_hash}");
+        }
+    }
+
+    println!("  Public key: 0x{}", hex::encode(info.public_key()));
+    println!(
+        "  Allocated space: {} ({})",
+        bytesize::to_string(info.allocated_space(), true),
+        bytesize::to_string(info.allocated_space(), false)
+    );
+
+    match SingleDiskFarm::read_all_sectors_metadata(directory) {
+        Ok(sectors_metadata) => {
+            println!("  Plotted sectors: {}", sectors_metadata.len());
+        }
+        Err(error) => {
+            println!("  Plotted sectors: failed to read sectors metadata: {error}");
+        }
+    }
+    // Plot file is preallocated for all sectors when farm is created or resized
+    match fs::metadata(directory.join(PLOT_FILE)) {
+        Ok(metadata) => {
+            println!(
+                "  Total sectors: {}",
+                metadata.len() / sector_size(info.pieces_in_sector()) as u64
+            );
+        }
+        Err(error) => {
+            println!("  Total sectors: failed to read plot file: {error}");
+        }
+    }
+
+    match disk_usage(directory) {
+        Ok(disk_usage) => {
+            println!(
+                "  Size on disk: {} ({})",
+                bytesize::to_string(disk_usage, true),
+                bytesize::to_string(disk_usage, false)
+            );
+        }
+        Err(error) => {
+            println!("  Size on disk: failed to read farm directory: {error}");
+        }
+    }
+}
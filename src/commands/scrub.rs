@@ -0,0 +1,110 @@
+use crate::backend::config::{Config, RawConfig};
+use crate::backend::farmer::scrub::{ScrubStep, scrub_farm};
+use clap::Args;
+use std::path::PathBuf;
+
+#[derive(Debug, Args)]
+pub struct ScrubArgs {
+    /// Only report problems, do not repair anything, exits with non-zero status code if problems
+    /// were found
+    #[arg(long)]
+    dry_run: bool,
+    /// Farm directories to check, all configured farms are checked by default
+    farms: Vec<PathBuf>,
+}
+
+impl ScrubArgs {
+    pub async fn run(self) -> anyhow::Result<()> {
+        let directories = if self.farms.is_empty() {
+            let config_file_path = RawConfig::default_path().await?;
+            let raw_config = RawConfig::read_from_path(&config_file_path)
+                .await?
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Configuration file \"{}\" doesn't exist",
+                        config_file_path.display()
+                    )
+                })?;
+
+            Config::try_from_raw_config(&raw_config)
+                .await?
+                .farms
+                .into_iter()
+                .map(|farm| farm.directory)
+                .collect()
+        } else {
+            self.farms
+        };
+
+        let mut failed_farms = Vec::new();
+        let mut unhealthy_farms = Vec::new();
+
+        for directory in directories {
+            println!("Checking farm {}", directory.display());
+
+            let result = scrub_farm(directory.clone(), self.dry_run, |step| match step {
+                ScrubStep::Waiting => {
+                    println!("  Waiting for the check of another farm to finish");
+                }
+                ScrubStep::CheckingPlot => {
+                    println!("  Checking metadata and plotted sectors");
+                }
+                ScrubStep::CheckingCache => {
+                    println!("  Checking piece cache");
+                }
+                _ => {
+                    // Not applicable to offline check
+                }
+            })
+            .await;
+
+            let summary = match result {
+                Ok(summary) => summary,
+                Err(error) => {
+                    println!("  Check failed: {error}");
+                    failed_farms.push(directory);
+                    continue;
+                }
+            };
+
+            for (kind, issues) in [
+                ("metadata and plotted sectors", &summary.plot_issues),
+                ("piece cache", &summary.cache_issues),
+            ] {
+                let action = if self.dry_run { "found" } else { "repaired" };
+                println!("  Problems with {kind} {action}: {}", issues.len());
+                for issue in issues {
+                    println!("    {issue}");
+                }
+            }
+
+            if !summary.is_healthy() {
+                unhealthy_farms.push(directory);
+            }
+        }
+
+        if !failed_farms.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Failed to check farms: {}",
+                failed_farms
+                    .iter()
+                    .map(|directory| directory.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+
+        if self.dry_run && !unhealthy_farms.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Problems found in farms, run without `--dry-run` to repair them: {}",
+                unhealthy_farms
+                    .iter()
+                    .map(|directory| directory.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+
+        Ok(())
+    }
+}
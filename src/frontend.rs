@@ -5,15 +5,18 @@ pub mod running;
 pub mod translations;
 mod tray_icon;
 mod widgets;
+pub mod wipe_dialog;
 
 use crate::backend::config::RawConfig;
-use crate::backend::farmer::FarmerAction;
-use crate::backend::{BackendAction, BackendNotification, wipe};
+use crate::backend::farmer::{FarmerAction, FarmerNotification, InitialFarmState};
+use crate::backend::selective_wipe::WipePlan;
+use crate::backend::{BackendAction, BackendNotification, ConfigSaveOutcome, FarmIndex, wipe};
 use crate::frontend::configuration::{ConfigurationInput, ConfigurationOutput, ConfigurationView};
 use crate::frontend::loading::{LoadingInput, LoadingView};
 use crate::frontend::new_version::NewVersion;
 use crate::frontend::running::{RunningInit, RunningInput, RunningOutput, RunningView};
 use crate::frontend::translations::{AsDefaultStr, T};
+use crate::frontend::wipe_dialog::{WipeDialog, WipeDialogInput, WipeDialogOutput};
 use crate::{AppStatusCode, icon_names};
 use futures::channel::mpsc;
 use futures::{SinkExt, StreamExt};
@@ -25,11 +28,13 @@ use relm4::prelude::*;
 use relm4::{Sender, ShutdownReceiver};
 use std::any::Any;
 use std::cell::{Cell, LazyCell};
+use std::collections::HashMap;
 use std::future::Future;
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::atomic::{AtomicI32, Ordering};
 use std::{env, fmt};
+use subspace_farmer::farm::{SectorPlottingDetails, SectorUpdate};
 use tracing::{debug, error, warn};
 
 pub const GLOBAL_CSS: &str = include_str!("../res/app.css");
@@ -110,6 +115,8 @@ pub enum AppInput {
     Running(RunningOutput),
     OpenLogsFolder,
     ChangeConfiguration,
+    ShowWipeDialog,
+    WipeDialog(WipeDialogOutput),
     OpenFeedbackLink,
     OpenCommunityHelpLink,
     ShowAboutDialog,
@@ -229,7 +236,7 @@ pub struct AppInit {
     pub exit_status_code: Rc<Cell<AppStatusCode>>,
     pub minimize_on_start: bool,
     pub crash_notification: bool,
-    pub run_backend: fn() -> RunBackendResult,
+    pub run_backend: Box<dyn FnOnce() -> RunBackendResult>,
 }
 
 relm4::new_action_group!(MainMenu, "main_menu");
@@ -239,6 +246,7 @@ relm4::new_stateless_action!(
     MainMenu,
     "change_configuration"
 );
+relm4::new_stateless_action!(MainMenuWipeData, MainMenu, "wipe_data");
 relm4::new_stateless_action!(MainMenuShareFeedback, MainMenu, "share_feedback");
 relm4::new_stateless_action!(MainMenuAbout, MainMenu, "about");
 relm4::new_stateless_action!(MainMenuExit, MainMenu, "exit");
@@ -249,6 +257,9 @@ pub struct App {
     current_view: View,
     current_raw_config: Option<RawConfig>,
     status_bar_contents: StatusBarContents,
+    /// Directories and states of running farms, used to preview effect of resizing farms
+    #[do_not_track]
+    farm_states: HashMap<FarmIndex, (PathBuf, InitialFarmState)>,
     #[do_not_track]
     backend_action_sender: mpsc::Sender<BackendAction>,
     #[do_not_track]
@@ -260,6 +271,8 @@ pub struct App {
     #[do_not_track]
     running_view: Controller<RunningView>,
     #[do_not_track]
+    wipe_dialog: Controller<WipeDialog>,
+    #[do_not_track]
     about_dialog: gtk::AboutDialog,
     #[do_not_track]
     app_data_dir: Option<PathBuf>,
@@ -508,6 +521,7 @@ impl AsyncComponent for App {
         main_menu: {
             &T.main_menu_show_logs() => MainMenuShowLogs,
             &T.main_menu_change_configuration() => MainMenuChangeConfiguration,
+            &T.main_menu_wipe_data() => MainMenuWipeData,
             &T.main_menu_share_feedback() => MainMenuShareFeedback,
             &T.main_menu_about() => MainMenuAbout,
             &T.main_menu_exit() => MainMenuExit,
@@ -563,6 +577,11 @@ impl AsyncComponent for App {
             })
             .forward(sender.input_sender(), AppInput::Running);
 
+        let wipe_dialog = WipeDialog::builder()
+            .transient_for(&root)
+            .launch(())
+            .forward(sender.input_sender(), AppInput::WipeDialog);
+
         let about_dialog = gtk::AboutDialog::builder()
             .title("About")
             .program_name("Space Acres")
@@ -618,11 +637,13 @@ impl AsyncComponent for App {
             } else {
                 StatusBarContents::None
             },
+            farm_states: HashMap::new(),
             backend_action_sender,
             new_version,
             loading_view,
             configuration_view,
             running_view,
+            wipe_dialog,
             about_dialog,
             app_data_dir,
             exit_status_code,
@@ -649,6 +670,13 @@ impl AsyncComponent for App {
                 sender.input(AppInput::ChangeConfiguration);
             }
         }));
+        menu_actions_group.add_action(RelmAction::<MainMenuWipeData>::new_stateless({
+            let sender = sender.clone();
+
+            move |_| {
+                sender.input(AppInput::ShowWipeDialog);
+            }
+        }));
         menu_actions_group.add_action(RelmAction::<MainMenuShareFeedback>::new_stateless({
             let sender = sender.clone();
 
@@ -776,10 +804,20 @@ impl AsyncComponent for App {
                         .emit(ConfigurationInput::Reinitialize {
                             raw_config,
                             reconfiguration: true,
+                            farm_states: self.farm_states_by_directory(),
                         });
                     self.set_current_view(View::Reconfiguration);
                 }
             }
+            AppInput::ShowWipeDialog => {
+                if let Some(raw_config) = self.current_raw_config.clone() {
+                    self.wipe_dialog.emit(WipeDialogInput::Show { raw_config });
+                }
+            }
+            AppInput::WipeDialog(wipe_dialog_output) => {
+                self.process_wipe_dialog_output(wipe_dialog_output, sender)
+                    .await;
+            }
             AppInput::OpenFeedbackLink => {
                 if let Err(error) = open::that_detached("https://linktr.ee/autonomys_network") {
                     error!(%error, "Failed to open share feedback page in default browser");
@@ -916,6 +954,35 @@ impl App {
         }
     }
 
+    async fn process_wipe_dialog_output(
+        &mut self,
+        wipe_dialog_output: WipeDialogOutput,
+        sender: AsyncComponentSender<Self>,
+    ) {
+        match wipe_dialog_output {
+            WipeDialogOutput::Wipe(wipe_plan) => {
+                if let Err(error) = Self::schedule_wipe(&wipe_plan).await {
+                    error!(%error, "Failed to schedule wipe");
+                    self.set_status_bar_contents(StatusBarContents::Error(
+                        T.status_bar_message_failed_to_schedule_wipe(error.to_string())
+                            .to_string(),
+                    ));
+                    return;
+                }
+
+                // Data is wiped on the next start before it is used
+                sender.input(AppInput::Restart);
+            }
+        }
+    }
+
+    async fn schedule_wipe(wipe_plan: &WipePlan) -> anyhow::Result<()> {
+        let config_file_path = RawConfig::default_path().await?;
+        wipe_plan.schedule(&config_file_path).await?;
+
+        Ok(())
+    }
+
     async fn process_running_output(&mut self, running_output: RunningOutput) {
         match running_output {
             RunningOutput::PausePlotting(pause_plotting) => {
@@ -932,6 +999,39 @@ impl App {
                     ));
                 }
             }
+            RunningOutput::PauseFarmPlotting { farm_index, paused } => {
+                if let Err(error) = self
+                    .backend_action_sender
+                    .send(BackendAction::Farmer(FarmerAction::PauseFarmPlotting {
+                        farm_index,
+                        paused,
+                    }))
+                    .await
+                {
+                    self.set_current_view(View::Error(
+                        T.error_message_failed_to_send_pause_plotting_to_backend(error.to_string())
+                            .to_string(),
+                    ));
+                }
+            }
+            RunningOutput::ScrubFarm {
+                farm_index,
+                dry_run,
+            } => {
+                if let Err(error) = self
+                    .backend_action_sender
+                    .send(BackendAction::Farmer(FarmerAction::ScrubFarm {
+                        farm_index,
+                        dry_run,
+                    }))
+                    .await
+                {
+                    self.set_current_view(View::Error(
+                        T.error_message_failed_to_send_scrub_farm_to_backend(error.to_string())
+                            .to_string(),
+                    ));
+                }
+            }
         }
     }
 
@@ -989,6 +1089,7 @@ impl App {
                         .emit(ConfigurationInput::Reinitialize {
                             raw_config,
                             reconfiguration: false,
+                            farm_states: self.farm_states_by_directory(),
                         });
                 }
                 self.set_status_bar_contents(StatusBarContents::Warning {
@@ -1000,7 +1101,13 @@ impl App {
                 });
             }
             BackendNotification::ConfigSaveResult(result) => match result {
-                Ok(()) => {
+                Ok(ConfigSaveOutcome::Applied) => {
+                    self.set_status_bar_contents(StatusBarContents::Warning {
+                        message: T.status_bar_message_configuration_applied().to_string(),
+                        button: StatusBarButton::Ok,
+                    });
+                }
+                Ok(ConfigSaveOutcome::RestartRequired) => {
                     self.set_status_bar_contents(StatusBarContents::Warning {
                         message: T
                             .status_bar_message_restart_is_needed_for_configuration()
@@ -1020,24 +1127,40 @@ impl App {
                 raw_config,
                 best_block_number,
                 reward_address_balance,
+                farm_reward_address_balances,
                 initial_farm_states,
-                cache_percentage,
                 chain_info,
                 chain_constants,
+                history,
             } => {
                 self.loaded = true;
+                self.farm_states = config
+                    .farms
+                    .iter()
+                    .zip(initial_farm_states.iter().copied())
+                    .enumerate()
+                    .map(|(farm_index, (farm, initial_farm_state))| {
+                        let farm_index = FarmIndex::try_from(farm_index).expect(
+                            "More than 256 plots are not supported, this is checked on \
+                            backend; qed",
+                        );
+
+                        (farm_index, (farm.directory.clone(), initial_farm_state))
+                    })
+                    .collect();
                 self.get_mut_current_raw_config()
                     .replace(raw_config.clone());
                 self.set_current_view(View::Running);
                 self.running_view.emit(RunningInput::Initialize {
                     best_block_number,
                     reward_address_balance,
+                    farm_reward_address_balances,
                     initial_farm_states,
-                    cache_percentage,
                     config,
                     raw_config,
                     chain_info,
                     chain_constants,
+                    history,
                 });
             }
             BackendNotification::Node(node_notification) => {
@@ -1045,6 +1168,7 @@ impl App {
                     .emit(RunningInput::NodeNotification(node_notification));
             }
             BackendNotification::Farmer(farmer_notification) => {
+                self.update_farm_states(&farmer_notification);
                 self.running_view
                     .emit(RunningInput::FarmerNotification(farmer_notification));
             }
@@ -1070,6 +1194,41 @@ impl App {
         }
     }
 
+    fn update_farm_states(&mut self, farmer_notification: &FarmerNotification<FarmIndex>) {
+        match farmer_notification {
+            FarmerNotification::SectorUpdate {
+                farm_index,
+                update:
+                    SectorUpdate::Plotting(SectorPlottingDetails::Finished {
+                        old_plotted_sector: None,
+                        ..
+                    }),
+                ..
+            } => {
+                if let Some((_directory, farm_state)) = self.farm_states.get_mut(farm_index) {
+                    farm_state.plotted_sectors_count += 1;
+                }
+            }
+            FarmerNotification::FarmAdded {
+                farm_index,
+                farm,
+                initial_farm_state,
+                ..
+            } => {
+                self.farm_states
+                    .insert(*farm_index, (farm.directory.clone(), *initial_farm_state));
+            }
+            FarmerNotification::FarmRemoved { farm_index } => {
+                self.farm_states.remove(farm_index);
+            }
+            _ => {}
+        }
+    }
+
+    fn farm_states_by_directory(&self) -> HashMap<PathBuf, InitialFarmState> {
+        self.farm_states.values().cloned().collect()
+    }
+
     async fn do_upgrade(
         sender: Sender<AppCommandOutput>,
         shutdown_receiver: ShutdownReceiver,
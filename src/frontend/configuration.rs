@@ -1,7 +1,13 @@
 mod farm;
 mod utils;
 
-use crate::backend::config::{NetworkConfiguration, RawConfig};
+use crate::backend::config::{
+    ChainConfiguration, FarmerConfiguration, MetricsConfiguration, NetworkConfiguration,
+    NodeConfiguration, PlottingCpuConfiguration, PlottingSchedule, RawConfig, RpcConfiguration,
+    SyncMode, chain_data_path, format_cpu_cores,
+};
+use crate::backend::farmer::InitialFarmState;
+use crate::backend::node::{NODE_NAME_MAX_LENGTH, check_node_name};
 use crate::frontend::configuration::farm::{
     FarmWidget, FarmWidgetInit, FarmWidgetInput, FarmWidgetOutput,
 };
@@ -15,9 +21,11 @@ use relm4::prelude::*;
 use relm4_components::open_dialog::{
     OpenDialog, OpenDialogMsg, OpenDialogResponse, OpenDialogSettings,
 };
+use std::collections::HashMap;
 use std::ops::Deref;
 use std::path::PathBuf;
 use subspace_farmer::utils::ss58::parse_ss58_reward_address;
+use subspace_farmer::utils::thread_pool_core_indices;
 use tracing::{debug, error, warn};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -30,6 +38,8 @@ pub enum DirectoryKind {
 pub enum ConfigurationInput {
     AddFarm,
     RewardAddressChanged(String),
+    NodeNameChanged(String),
+    PlottingCpuCoresChanged(String),
     CreateWallet,
     OpenDirectory(DirectoryKind),
     DirectorySelected(PathBuf),
@@ -37,16 +47,24 @@ pub enum ConfigurationInput {
     SubspacePortChanged(u16),
     FasterNetworkingChanged(bool),
     ReducePlottingCpuLoadChanged(bool),
+    FullSyncChanged(bool),
+    TelemetryChanged(bool),
     Delete(DynamicIndex),
     Reinitialize {
         raw_config: RawConfig,
         reconfiguration: bool,
+        /// States of running farms by farm directory
+        farm_states: HashMap<PathBuf, InitialFarmState>,
     },
     Help,
     Start,
+    /// Start after user confirmed discarding plotted sectors (if any)
+    StartConfirmed,
     Back,
     Cancel,
     Save,
+    /// Save after user confirmed discarding plotted sectors (if any)
+    SaveConfirmed,
     UpdateFarms,
     Ignore,
 }
@@ -117,6 +135,9 @@ struct NetworkConfigurationWrapper {
     substrate_port: u16,
     subspace_port: u16,
     faster_networking: bool,
+    /// Settings that are not editable in GUI, preserved as is
+    #[do_not_track]
+    preserved: NetworkConfiguration,
 }
 
 impl Default for NetworkConfigurationWrapper {
@@ -131,6 +152,7 @@ impl From<NetworkConfiguration> for NetworkConfigurationWrapper {
             substrate_port: config.substrate_port,
             subspace_port: config.subspace_port,
             faster_networking: config.faster_networking,
+            preserved: config,
             tracker: u8::MAX,
         }
     }
@@ -147,13 +169,44 @@ pub struct ConfigurationView {
     farms: AsyncFactoryVecDeque<FarmWidget>,
     #[do_not_track]
     network_configuration: NetworkConfigurationWrapper,
+    /// Only plotting CPU cores are editable in GUI, the rest is preserved as is
+    #[do_not_track]
+    plotting_cpu_configuration: PlottingCpuConfiguration,
+    /// Empty CPU cores mean they will be derived from CPU topology
+    #[do_not_track]
+    plotting_cpu_cores: MaybeValid<String>,
+    /// Detected L3 cache groups, shown to help with choosing plotting CPU cores
+    #[do_not_track]
+    l3_cache_groups: String,
+    /// Not editable in GUI, preserved as is
+    #[do_not_track]
+    plotting_schedule: PlottingSchedule,
+    /// Not editable in GUI, preserved as is
+    #[do_not_track]
+    metrics_configuration: MetricsConfiguration,
+    /// Not editable in GUI, preserved as is
+    #[do_not_track]
+    chain_configuration: ChainConfiguration,
+    /// Not editable in GUI, preserved as is
+    #[do_not_track]
+    rpc_configuration: RpcConfiguration,
+    /// Only node name, telemetry and sync mode are editable in GUI, the rest is preserved as is
+    #[do_not_track]
+    node_configuration: NodeConfiguration,
+    /// Empty name means it will be generated automatically
+    #[do_not_track]
+    node_name: MaybeValid<String>,
     reduce_plotting_cpu_load: bool,
+    telemetry: bool,
+    full_sync: bool,
     #[do_not_track]
     pending_directory_selection: Option<DirectoryKind>,
     #[do_not_track]
     open_dialog: Controller<OpenDialog>,
     #[do_not_track]
     reconfiguration: bool,
+    #[do_not_track]
+    parent_window: gtk::Window,
 }
 
 #[relm4::component(pub async)]
@@ -368,6 +421,130 @@ impl AsyncComponent for ConfigurationView {
                                             set_tooltip: &T.configuration_advanced_farmer_reduce_plotting_cpu_load_tooltip(),
                                         },
                                     },
+
+                                    gtk::Box {
+                                        set_spacing: 10,
+
+                                        gtk::Label {
+                                            set_label: &T.configuration_advanced_farmer_plotting_cpu_cores(),
+                                        },
+                                        gtk::Entry {
+                                            connect_changed[sender] => move |entry| {
+                                                sender.input(ConfigurationInput::PlottingCpuCoresChanged(
+                                                    entry.text().into()
+                                                ));
+                                            },
+                                            #[track = "model.plotting_cpu_cores.changed_is_valid()"]
+                                            set_css_classes: if model.plotting_cpu_cores.is_valid {
+                                                &["valid-input"]
+                                            } else {
+                                                &["invalid-input"]
+                                            },
+                                            set_hexpand: true,
+                                            set_placeholder_text: Some(model.l3_cache_groups.as_str()),
+                                            #[track = "model.plotting_cpu_cores.changed_is_valid()"]
+                                            set_secondary_icon_name: model.plotting_cpu_cores.icon(),
+                                            set_secondary_icon_activatable: false,
+                                            set_secondary_icon_sensitive: false,
+                                            #[track = "model.plotting_cpu_cores.changed_value()"]
+                                            set_text: &model.plotting_cpu_cores,
+                                            set_tooltip: &T.configuration_advanced_farmer_plotting_cpu_cores_tooltip(),
+                                        },
+                                    },
+
+                                    gtk::Label {
+                                        add_css_class: "dim-label",
+                                        set_halign: gtk::Align::Start,
+                                        set_label: &T.configuration_advanced_farmer_l3_cache_groups(
+                                            model.l3_cache_groups.as_str()
+                                        ),
+                                        set_wrap: true,
+                                    },
+                                },
+
+                                gtk::Label {
+                                    add_css_class: "heading",
+                                    set_halign: gtk::Align::Start,
+                                    set_label: &T.configuration_advanced_node(),
+                                },
+
+                                gtk::Box {
+                                    set_orientation: gtk::Orientation::Vertical,
+                                    set_spacing: 10,
+
+                                    gtk::Box {
+                                        set_spacing: 10,
+
+                                        gtk::Label {
+                                            set_label: &T.configuration_advanced_node_name(),
+                                        },
+                                        gtk::Entry {
+                                            connect_changed[sender] => move |entry| {
+                                                sender.input(ConfigurationInput::NodeNameChanged(
+                                                    entry.text().into()
+                                                ));
+                                            },
+                                            #[track = "model.node_name.changed_is_valid()"]
+                                            set_css_classes: if model.node_name.is_valid {
+                                                &["valid-input"]
+                                            } else {
+                                                &["invalid-input"]
+                                            },
+                                            set_hexpand: true,
+                                            set_max_length: NODE_NAME_MAX_LENGTH as i32 - 1,
+                                            set_placeholder_text: Some(T.configuration_advanced_node_name_placeholder().as_str()),
+                                            #[track = "model.node_name.changed_is_valid()"]
+                                            set_secondary_icon_name: model.node_name.icon(),
+                                            set_secondary_icon_activatable: false,
+                                            set_secondary_icon_sensitive: false,
+                                            #[track = "model.node_name.changed_value()"]
+                                            set_text: &model.node_name,
+                                            set_tooltip: &T.configuration_advanced_node_name_tooltip(),
+                                        },
+                                    },
+
+                                    gtk::Box {
+                                        set_spacing: 10,
+
+                                        gtk::Label {
+                                            set_label: &T.configuration_advanced_node_telemetry(),
+                                        },
+                                        gtk::Switch {
+                                            connect_state_set[sender] => move |_switch, state| {
+                                                sender.input(ConfigurationInput::TelemetryChanged(
+                                                    state
+                                                ));
+
+                                                glib::Propagation::Proceed
+                                            },
+                                            #[track = "model.changed_telemetry()"]
+                                            set_active: model.telemetry,
+                                            set_tooltip: &T.configuration_advanced_node_telemetry_tooltip(),
+                                        },
+                                    },
+
+                                    gtk::Box {
+                                        set_spacing: 10,
+
+                                        gtk::Label {
+                                            set_label: &T.configuration_advanced_node_full_sync(),
+                                        },
+                                        gtk::Switch {
+                                            connect_state_set[sender] => move |_switch, state| {
+                                                sender.input(ConfigurationInput::FullSyncChanged(
+                                                    state
+                                                ));
+
+                                                glib::Propagation::Proceed
+                                            },
+                                            #[track = "model.changed_full_sync()"]
+                                            set_active: model.full_sync,
+                                            // Archive node always uses full sync
+                                            #[track = "model.changed_full_sync()"]
+                                            set_sensitive: !model.node_configuration.archive,
+                                            set_tooltip: &T.configuration_advanced_node_full_sync_tooltip(),
+                                        },
+                                    },
                                 },
 
                                 gtk::Label {
@@ -508,10 +685,12 @@ impl AsyncComponent for ConfigurationView {
                                 gtk::Button {
                                     add_css_class: "suggested-action",
                                     connect_clicked => ConfigurationInput::Save,
-                                    #[track = "model.reward_address.changed_is_valid() || model.node_path.changed_is_valid() || model.changed_farms()"]
+                                    #[track = "model.reward_address.changed_is_valid() || model.node_path.changed_is_valid() || model.node_name.changed_is_valid() || model.plotting_cpu_cores.changed_is_valid() || model.changed_farms()"]
                                     set_sensitive:
                                         model.reward_address.is_valid
                                             && model.node_path.is_valid
+                                            && model.node_name.is_valid
+                                            && model.plotting_cpu_cores.is_valid
                                             && !model.farms.is_empty()
                                             && model.farms.iter().all(|maybe_farm| maybe_farm.map(FarmWidget::valid).unwrap_or_default()),
 
@@ -547,10 +726,12 @@ impl AsyncComponent for ConfigurationView {
                                 gtk::Button {
                                     add_css_class: "suggested-action",
                                     connect_clicked => ConfigurationInput::Start,
-                                    #[track = "model.reward_address.changed_is_valid() || model.node_path.changed_is_valid() || model.changed_farms()"]
+                                    #[track = "model.reward_address.changed_is_valid() || model.node_path.changed_is_valid() || model.node_name.changed_is_valid() || model.plotting_cpu_cores.changed_is_valid() || model.changed_farms()"]
                                     set_sensitive:
                                         model.reward_address.is_valid
                                             && model.node_path.is_valid
+                                            && model.node_name.is_valid
+                                            && model.plotting_cpu_cores.is_valid
                                             && !model.farms.is_empty()
                                             && model.farms.iter().all(|maybe_farm| maybe_farm.map(FarmWidget::valid).unwrap_or_default()),
 
@@ -602,10 +783,22 @@ impl AsyncComponent for ConfigurationView {
             node_path: MaybeValid::no(PathBuf::new()),
             farms,
             network_configuration: Default::default(),
+            plotting_cpu_configuration: Default::default(),
+            plotting_cpu_cores: MaybeValid::yes(String::new()),
+            l3_cache_groups: format_cpu_cores(&thread_pool_core_indices(None, None)),
+            plotting_schedule: Default::default(),
+            metrics_configuration: Default::default(),
+            chain_configuration: Default::default(),
+            rpc_configuration: Default::default(),
+            node_configuration: Default::default(),
+            node_name: MaybeValid::yes(String::new()),
             reduce_plotting_cpu_load: false,
+            telemetry: true,
+            full_sync: false,
             pending_directory_selection: Default::default(),
             open_dialog,
             reconfiguration: false,
+            parent_window: parent_root,
             tracker: u8::MAX,
         };
 
@@ -625,6 +818,8 @@ impl AsyncComponent for ConfigurationView {
         self.reset();
         self.reward_address.reset();
         self.node_path.reset();
+        self.node_name.reset();
+        self.plotting_cpu_cores.reset();
         self.network_configuration.reset();
 
         self.process_input(input, sender).await;
@@ -682,6 +877,12 @@ impl ConfigurationView {
             ConfigurationInput::ReducePlottingCpuLoadChanged(reduce_plotting_cpu_load) => {
                 self.reduce_plotting_cpu_load = reduce_plotting_cpu_load;
             }
+            ConfigurationInput::FullSyncChanged(full_sync) => {
+                self.full_sync = full_sync;
+            }
+            ConfigurationInput::TelemetryChanged(telemetry) => {
+                self.telemetry = telemetry;
+            }
             ConfigurationInput::Delete(index) => {
                 let mut farms = self.get_mut_farms().guard();
                 farms.remove(index.current_index());
@@ -704,9 +905,32 @@ impl ConfigurationView {
                     parse_ss58_reward_address(new_reward_address).is_ok();
                 self.reward_address.value = new_reward_address.to_string();
             }
+            ConfigurationInput::NodeNameChanged(new_node_name) => {
+                let new_node_name = new_node_name.trim();
+                // Don't set value to avoid resetting cursor position in the entry
+                self.node_name.set_is_valid(
+                    new_node_name.is_empty() || check_node_name(new_node_name).is_ok(),
+                );
+                self.node_name.value = new_node_name.to_string();
+            }
+            ConfigurationInput::PlottingCpuCoresChanged(new_plotting_cpu_cores) => {
+                let new_plotting_cpu_cores = new_plotting_cpu_cores.trim();
+                // Don't set value to avoid resetting cursor position in the entry
+                self.plotting_cpu_cores.set_is_valid(
+                    PlottingCpuConfiguration {
+                        plotting_cpu_cores: (!new_plotting_cpu_cores.is_empty())
+                            .then(|| new_plotting_cpu_cores.to_string()),
+                        ..self.plotting_cpu_configuration.clone()
+                    }
+                    .cpu_cores()
+                    .is_ok(),
+                );
+                self.plotting_cpu_cores.value = new_plotting_cpu_cores.to_string();
+            }
             ConfigurationInput::Reinitialize {
                 raw_config,
                 reconfiguration,
+                farm_states,
             } => {
                 let new_reward_address = raw_config.reward_address().trim();
                 self.reward_address
@@ -720,18 +944,44 @@ impl ConfigurationView {
                     MaybeValid::no(raw_config.node_path().clone())
                 };
                 {
+                    let chain_id = raw_config.chain().read_chain_id().await.unwrap_or_default();
                     let mut farms = self.get_mut_farms().guard();
                     farms.clear();
                     for farm in raw_config.farms() {
                         farms.push_back(FarmWidgetInit {
-                            path: farm.path.clone(),
-                            size: farm.size.clone(),
+                            current_state: farm_states
+                                .get(&chain_data_path(&farm.path, chain_id.as_deref()))
+                                .copied(),
+                            chain_id: chain_id.clone(),
+                            ..FarmWidgetInit::from(farm)
                         });
                     }
                 }
                 self.set_reduce_plotting_cpu_load(raw_config.reduce_plotting_cpu_load());
                 self.network_configuration =
                     NetworkConfigurationWrapper::from(raw_config.network());
+                let farmer = raw_config.farmer();
+                self.plotting_cpu_configuration = farmer.plotting_cpu;
+                let plotting_cpu_cores = self
+                    .plotting_cpu_configuration
+                    .plotting_cpu_cores
+                    .clone()
+                    .unwrap_or_default();
+                self.plotting_cpu_cores = if self.plotting_cpu_configuration.cpu_cores().is_ok() {
+                    MaybeValid::yes(plotting_cpu_cores)
+                } else {
+                    MaybeValid::no(plotting_cpu_cores)
+                };
+                self.plotting_schedule = farmer.plotting_schedule;
+                self.metrics_configuration = raw_config.metrics();
+                self.chain_configuration = raw_config.chain();
+                self.rpc_configuration = raw_config.rpc();
+                self.node_configuration = raw_config.node();
+                self.node_name =
+                    MaybeValid::yes(self.node_configuration.name.clone().unwrap_or_default());
+                self.set_telemetry(!self.node_configuration.disable_telemetry);
+                // Force change so switch sensitivity is updated even if sync mode is the same
+                *self.get_mut_full_sync() = self.node_configuration.sync_mode() == SyncMode::Full;
                 self.reconfiguration = reconfiguration;
             }
             ConfigurationInput::Help => {
@@ -742,6 +992,9 @@ impl ConfigurationView {
                 }
             }
             ConfigurationInput::Start => {
+                self.confirm_discarded_sectors(sender, ConfigurationInput::StartConfirmed);
+            }
+            ConfigurationInput::StartConfirmed => {
                 if let Some(raw_config) = self.create_raw_config()
                     && sender
                         .output(ConfigurationOutput::StartWithNewConfig(raw_config))
@@ -761,6 +1014,9 @@ impl ConfigurationView {
                 }
             }
             ConfigurationInput::Save => {
+                self.confirm_discarded_sectors(sender, ConfigurationInput::SaveConfirmed);
+            }
+            ConfigurationInput::SaveConfirmed => {
                 if let Some(raw_config) = self.create_raw_config()
                     && sender
                         .output(ConfigurationOutput::ConfigUpdate(raw_config))
@@ -779,9 +1035,55 @@ impl ConfigurationView {
         }
     }
 
+    /// Ask user to confirm shrinking farms below the number of already plotted sectors before
+    /// proceeding with `confirmed` input, proceeds immediately if no sectors will be discarded
+    fn confirm_discarded_sectors(
+        &self,
+        sender: AsyncComponentSender<Self>,
+        confirmed: ConfigurationInput,
+    ) {
+        let discarded_sectors_count = self
+            .farms
+            .iter()
+            .flatten()
+            .map(|farm_widget| u64::from(farm_widget.discarded_sectors_count()))
+            .sum::<u64>();
+
+        if discarded_sectors_count == 0 {
+            sender.input(confirmed);
+            return;
+        }
+
+        let dialog = gtk::AlertDialog::builder()
+            .modal(true)
+            .message(T.configuration_discard_sectors_title().to_string())
+            .detail(
+                T.configuration_discard_sectors_detail(discarded_sectors_count)
+                    .to_string(),
+            )
+            .buttons([
+                &*T.configuration_discard_sectors_button_cancel(),
+                &*T.configuration_discard_sectors_button_confirm(),
+            ])
+            .cancel_button(0)
+            .default_button(0)
+            .build();
+
+        dialog.choose(
+            Some(&self.parent_window),
+            None::<&gtk::gio::Cancellable>,
+            move |result| {
+                // Index of confirmation button
+                if let Ok(1) = result {
+                    sender.input(confirmed);
+                }
+            },
+        );
+    }
+
     /// Create raw config from own state
     fn create_raw_config(&self) -> Option<RawConfig> {
-        Some(RawConfig::V0 {
+        Some(RawConfig::V1 {
             reward_address: String::clone(&self.reward_address),
             node_path: PathBuf::clone(&self.node_path),
             farms: self
@@ -789,11 +1091,36 @@ impl ConfigurationView {
                 .iter()
                 .map(|maybe_farm_widget| Some(maybe_farm_widget?.farm()))
                 .collect::<Option<Vec<_>>>()?,
-            reduce_plotting_cpu_load: self.reduce_plotting_cpu_load,
+            farmer: FarmerConfiguration {
+                reduce_plotting_cpu_load: self.reduce_plotting_cpu_load,
+                plotting_cpu: PlottingCpuConfiguration {
+                    plotting_cpu_cores: (!self.plotting_cpu_cores.is_empty())
+                        .then(|| String::clone(&self.plotting_cpu_cores)),
+                    ..self.plotting_cpu_configuration.clone()
+                },
+                plotting_schedule: self.plotting_schedule.clone(),
+            },
             network: NetworkConfiguration {
                 substrate_port: self.network_configuration.substrate_port,
                 subspace_port: self.network_configuration.subspace_port,
                 faster_networking: self.network_configuration.faster_networking,
+                ..self.network_configuration.preserved.clone()
+            },
+            metrics: self.metrics_configuration,
+            chain: self.chain_configuration.clone(),
+            rpc: self.rpc_configuration.clone(),
+            node: NodeConfiguration {
+                sync_mode: if self.node_configuration.archive {
+                    // Switch is not editable for archive node, keep original value
+                    self.node_configuration.sync_mode
+                } else if self.full_sync {
+                    SyncMode::Full
+                } else {
+                    SyncMode::Snap
+                },
+                name: (!self.node_name.is_empty()).then(|| String::clone(&self.node_name)),
+                disable_telemetry: !self.telemetry,
+                ..self.node_configuration.clone()
             },
         })
     }
@@ -1,9 +1,15 @@
-use crate::backend::config::{Farm, MIN_FARM_SIZE};
+use crate::backend::config::{
+    Farm, FarmReadMode, MAX_CACHE_PERCENTAGE, MIN_FARM_SIZE, resolve_farm_size,
+};
+use crate::backend::farmer::{
+    CACHE_PERCENTAGE, FarmResize, InitialFarmState, MAX_PLOTTING_SECTORS_PER_FARM,
+};
 use crate::frontend::configuration::MaybeValid;
 use crate::icon_names;
 use bytesize::ByteSize;
 use gtk::prelude::*;
 use std::fmt;
+use std::num::{NonZeroU8, NonZeroUsize};
 // TODO: Remove import once in prelude: https://github.com/Relm4/Relm4/issues/662
 use relm4::factory::AsyncFactoryComponent;
 use relm4::prelude::*;
@@ -14,6 +20,9 @@ use relm4::AsyncFactorySender;
 use relm4_components::simple_combo_box::SimpleComboBox;
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::Duration;
+use subspace_core_primitives::sectors::SectorIndex;
+use subspace_farmer::utils::ss58::parse_ss58_reward_address;
 use tracing::warn;
 
 fn is_fixed_size_valid(size: &str) -> bool {
@@ -22,6 +31,11 @@ fn is_fixed_size_valid(size: &str) -> bool {
         .unwrap_or_default()
 }
 
+/// Empty reward address means global reward address is used
+fn is_reward_address_valid(reward_address: &str) -> bool {
+    reward_address.is_empty() || parse_ss58_reward_address(reward_address).is_ok()
+}
+
 fn is_free_percentage_size_valid(size: &str) -> bool {
     size.ends_with("%")
         && f32::from_str(size.trim_end_matches('%'))
@@ -51,10 +65,74 @@ impl SizeKind {
     }
 }
 
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum ReadMode {
+    ConcurrentChunks,
+    WholeSector,
+}
+
+impl fmt::Display for ReadMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&match self {
+            Self::ConcurrentChunks => T.configuration_farm_read_mode_concurrent_chunks(),
+            Self::WholeSector => T.configuration_farm_read_mode_whole_sector(),
+        })
+    }
+}
+
+impl From<FarmReadMode> for ReadMode {
+    fn from(read_mode: FarmReadMode) -> Self {
+        match read_mode {
+            FarmReadMode::ConcurrentChunks => Self::ConcurrentChunks,
+            FarmReadMode::WholeSector => Self::WholeSector,
+        }
+    }
+}
+
+impl From<ReadMode> for FarmReadMode {
+    fn from(read_mode: ReadMode) -> Self {
+        match read_mode {
+            ReadMode::ConcurrentChunks => Self::ConcurrentChunks,
+            ReadMode::WholeSector => Self::WholeSector,
+        }
+    }
+}
+
+impl ReadMode {
+    fn all() -> [ReadMode; std::mem::variant_count::<ReadMode>()] {
+        [Self::ConcurrentChunks, Self::WholeSector]
+    }
+}
+
+/// Expected effect of new size on existing farm
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+struct ResizePreview {
+    plotted_sectors_count: SectorIndex,
+    old_total_sectors_count: SectorIndex,
+    new_total_sectors_count: SectorIndex,
+    /// Conservative estimate of how long resizing will take
+    estimated_duration: Duration,
+}
+
+impl ResizePreview {
+    fn discarded_sectors_count(&self) -> SectorIndex {
+        self.plotted_sectors_count
+            .saturating_sub(self.new_total_sectors_count)
+    }
+}
+
 #[derive(Debug)]
 pub(super) struct FarmWidgetInit {
     pub(super) path: PathBuf,
     pub(super) size: String,
+    pub(super) cache_percentage: Option<NonZeroU8>,
+    pub(super) read_mode: Option<FarmReadMode>,
+    pub(super) farming_threads: Option<NonZeroUsize>,
+    pub(super) max_plotting_sectors: Option<NonZeroUsize>,
+    pub(super) reward_address: Option<String>,
+    /// State of the farm if it is already running, used to preview effect of resizing
+    pub(super) current_state: Option<InitialFarmState>,
+    pub(super) chain_id: Option<String>,
 }
 
 impl Default for FarmWidgetInit {
@@ -62,6 +140,29 @@ impl Default for FarmWidgetInit {
         Self {
             path: PathBuf::new(),
             size: String::new(),
+            cache_percentage: None,
+            read_mode: None,
+            farming_threads: None,
+            max_plotting_sectors: None,
+            reward_address: None,
+            current_state: None,
+            chain_id: None,
+        }
+    }
+}
+
+impl From<&Farm> for FarmWidgetInit {
+    fn from(farm: &Farm) -> Self {
+        Self {
+            path: farm.path.clone(),
+            size: farm.size.clone(),
+            cache_percentage: farm.cache_percentage,
+            read_mode: farm.read_mode,
+            farming_threads: farm.farming_threads,
+            max_plotting_sectors: farm.max_plotting_sectors,
+            reward_address: farm.reward_address.clone(),
+            current_state: None,
+            chain_id: None,
         }
     }
 }
@@ -72,6 +173,11 @@ pub(super) enum FarmWidgetInput {
     SizeKindChanged(usize),
     FarmFixedSizeChanged(String),
     FarmFreePercentageSizeChanged(String),
+    CachePercentageChanged(u8),
+    ReadModeChanged(usize),
+    FarmingThreadsChanged(usize),
+    MaxPlottingSectorsChanged(usize),
+    RewardAddressChanged(String),
 }
 
 #[derive(Debug)]
@@ -97,6 +203,25 @@ pub(super) struct FarmWidget {
     /// 0.0%..=100.0%
     #[do_not_track]
     free_percentage_size: MaybeValid<String>,
+    #[do_not_track]
+    cache_percentage: NonZeroU8,
+    #[do_not_track]
+    read_mode: ReadMode,
+    #[do_not_track]
+    read_mode_selector: Controller<SimpleComboBox<ReadMode>>,
+    /// `None` means number of threads is chosen automatically
+    #[do_not_track]
+    farming_threads: Option<NonZeroUsize>,
+    #[do_not_track]
+    max_plotting_sectors: NonZeroUsize,
+    /// Empty means global reward address is used
+    #[do_not_track]
+    reward_address: MaybeValid<String>,
+    #[do_not_track]
+    current_state: Option<InitialFarmState>,
+    #[do_not_track]
+    chain_id: Option<String>,
+    resize_preview: Option<ResizePreview>,
 }
 
 #[relm4::factory(pub(super) async)]
@@ -266,6 +391,167 @@ impl AsyncFactoryComponent for FarmWidget {
                     #[track = "self.path.changed_is_valid()"]
                     set_visible: !self.path.is_valid && self.path.value != PathBuf::new(),
                 },
+
+                gtk::Label {
+                    #[track = "self.changed_resize_preview()"]
+                    set_css_classes: if self
+                        .resize_preview
+                        .is_some_and(|resize_preview| resize_preview.discarded_sectors_count() > 0)
+                    {
+                        &["warning-label"]
+                    } else {
+                        &[]
+                    },
+                    set_halign: gtk::Align::Start,
+                    #[track = "self.changed_resize_preview()"]
+                    set_label: &self.resize_preview_message(),
+                    #[track = "self.changed_resize_preview()"]
+                    set_visible: self.resize_preview.is_some(),
+                    set_wrap: true,
+                },
+
+                gtk::Expander {
+                    set_label: Some(&T.configuration_farm_advanced()),
+
+                    gtk::Box {
+                        set_margin_top: 10,
+                        set_orientation: gtk::Orientation::Vertical,
+                        set_spacing: 10,
+
+                        gtk::Box {
+                            set_spacing: 10,
+
+                            gtk::Label {
+                                set_label: &T.configuration_farm_cache_percentage(),
+                            },
+                            gtk::SpinButton {
+                                connect_value_changed[sender] => move |entry| {
+                                    sender.input(FarmWidgetInput::CachePercentageChanged(
+                                        entry.value().round() as u8
+                                    ));
+                                },
+                                set_adjustment: &gtk::Adjustment::new(
+                                    0.0,
+                                    1.0,
+                                    MAX_CACHE_PERCENTAGE as f64,
+                                    1.0,
+                                    0.0,
+                                    0.0,
+                                ),
+                                set_tooltip: T
+                                    .configuration_farm_cache_percentage_tooltip(
+                                        CACHE_PERCENTAGE.get()
+                                    )
+                                    .as_str(),
+                                set_value: self.cache_percentage.get() as f64,
+                                set_width_chars: 3,
+                            },
+                        },
+
+                        gtk::Box {
+                            set_spacing: 10,
+
+                            gtk::Label {
+                                set_label: &T.configuration_farm_read_mode(),
+                                set_tooltip: &T.configuration_farm_read_mode_tooltip(),
+                            },
+                            self.read_mode_selector.widget().clone(),
+                        },
+
+                        gtk::Box {
+                            set_spacing: 10,
+
+                            gtk::Label {
+                                set_label: &T.configuration_farm_farming_threads(),
+                            },
+                            gtk::SpinButton {
+                                connect_value_changed[sender] => move |entry| {
+                                    sender.input(FarmWidgetInput::FarmingThreadsChanged(
+                                        entry.value().round() as usize
+                                    ));
+                                },
+                                set_adjustment: &gtk::Adjustment::new(
+                                    0.0,
+                                    0.0,
+                                    u8::MAX as f64,
+                                    1.0,
+                                    0.0,
+                                    0.0,
+                                ),
+                                set_tooltip: &T.configuration_farm_farming_threads_tooltip(),
+                                set_value: self
+                                    .farming_threads
+                                    .map(NonZeroUsize::get)
+                                    .unwrap_or_default() as f64,
+                                set_width_chars: 3,
+                            },
+                        },
+
+                        gtk::Box {
+                            set_spacing: 10,
+
+                            gtk::Label {
+                                set_label: &T.configuration_farm_plotting_concurrency(),
+                            },
+                            gtk::SpinButton {
+                                connect_value_changed[sender] => move |entry| {
+                                    sender.input(FarmWidgetInput::MaxPlottingSectorsChanged(
+                                        entry.value().round() as usize
+                                    ));
+                                },
+                                set_adjustment: &gtk::Adjustment::new(
+                                    0.0,
+                                    1.0,
+                                    u8::MAX as f64,
+                                    1.0,
+                                    0.0,
+                                    0.0,
+                                ),
+                                set_tooltip: T
+                                    .configuration_farm_plotting_concurrency_tooltip(
+                                        MAX_PLOTTING_SECTORS_PER_FARM.get()
+                                    )
+                                    .as_str(),
+                                set_value: self.max_plotting_sectors.get() as f64,
+                                set_width_chars: 3,
+                            },
+                        },
+
+                        gtk::Box {
+                            set_spacing: 10,
+
+                            gtk::Label {
+                                set_label: &T.configuration_farm_reward_address(),
+                            },
+                            gtk::Entry {
+                                connect_changed[sender] => move |entry| {
+                                    sender.input(FarmWidgetInput::RewardAddressChanged(
+                                        entry.text().into()
+                                    ));
+                                },
+                                #[track = "self.reward_address.changed_is_valid()"]
+                                set_css_classes: if self.reward_address.is_valid {
+                                    &["valid-input"]
+                                } else {
+                                    &["invalid-input"]
+                                },
+                                set_hexpand: true,
+                                set_placeholder_text: Some(
+                                    &T.configuration_farm_reward_address_placeholder(),
+                                ),
+                                set_primary_icon_name: Some(icon_names::WALLET2),
+                                set_primary_icon_activatable: false,
+                                set_primary_icon_sensitive: false,
+                                #[track = "self.reward_address.changed_is_valid()"]
+                                set_secondary_icon_name: self.reward_address.icon(),
+                                set_secondary_icon_activatable: false,
+                                set_secondary_icon_sensitive: false,
+                                set_text: &self.reward_address,
+                                set_tooltip: &T.configuration_farm_reward_address_tooltip(),
+                            },
+                        },
+                    },
+                },
             },
         }
     }
@@ -311,7 +597,22 @@ impl AsyncFactoryComponent for FarmWidget {
             })
             .forward(sender.input_sender(), FarmWidgetInput::SizeKindChanged);
 
-        let instance = Self {
+        let read_mode = ReadMode::from(value.read_mode.unwrap_or(FarmReadMode::ConcurrentChunks));
+        let read_mode_selector = SimpleComboBox::builder()
+            .launch({
+                let variants = ReadMode::all().to_vec();
+                let active_index = variants
+                    .iter()
+                    .position(|candidate| *candidate == read_mode);
+
+                SimpleComboBox {
+                    variants,
+                    active_index,
+                }
+            })
+            .forward(sender.input_sender(), FarmWidgetInput::ReadModeChanged);
+
+        let mut instance = Self {
             index: index.clone(),
             path: if is_directory_writable(value.path.clone()).await {
                 MaybeValid::yes(value.path)
@@ -322,8 +623,27 @@ impl AsyncFactoryComponent for FarmWidget {
             size_kind_selector,
             fixed_size,
             free_percentage_size,
+            cache_percentage: value.cache_percentage.unwrap_or(CACHE_PERCENTAGE),
+            read_mode,
+            read_mode_selector,
+            farming_threads: value.farming_threads,
+            max_plotting_sectors: value
+                .max_plotting_sectors
+                .unwrap_or(MAX_PLOTTING_SECTORS_PER_FARM),
+            reward_address: {
+                let reward_address = value.reward_address.unwrap_or_default();
+                if is_reward_address_valid(&reward_address) {
+                    MaybeValid::yes(reward_address)
+                } else {
+                    MaybeValid::no(reward_address)
+                }
+            },
+            current_state: value.current_state,
+            chain_id: value.chain_id,
+            resize_preview: None,
             tracker: u8::MAX,
         };
+        instance.update_resize_preview().await;
 
         // Send notification up that validity was updated, such that parent view can re-render
         // view if necessary, this is necessary due to async initialization of the model
@@ -340,11 +660,23 @@ impl AsyncFactoryComponent for FarmWidget {
         self.path.reset();
         self.fixed_size.reset();
         self.free_percentage_size.reset();
+        self.reward_address.reset();
 
         let was_valid = self.valid();
+        // Resolving farm size touches the disk, only do it when inputs affecting it have changed
+        let affects_resize_preview = matches!(
+            input,
+            FarmWidgetInput::DirectorySelected(_)
+                | FarmWidgetInput::SizeKindChanged(_)
+                | FarmWidgetInput::FarmFixedSizeChanged(_)
+                | FarmWidgetInput::FarmFreePercentageSizeChanged(_)
+                | FarmWidgetInput::CachePercentageChanged(_)
+        );
 
         match input {
             FarmWidgetInput::DirectorySelected(path) => {
+                // Different directory is a different farm
+                self.current_state = None;
                 self.path = if is_directory_writable(path.clone()).await {
                     MaybeValid::yes(path)
                 } else {
@@ -361,6 +693,33 @@ impl AsyncFactoryComponent for FarmWidget {
                     .set_is_valid(is_free_percentage_size_valid(&size));
                 self.free_percentage_size.value = size;
             }
+            FarmWidgetInput::CachePercentageChanged(cache_percentage) => {
+                if let Some(cache_percentage) = NonZeroU8::new(cache_percentage) {
+                    self.cache_percentage = cache_percentage;
+                }
+            }
+            FarmWidgetInput::ReadModeChanged(index) => {
+                self.read_mode = ReadMode::all()[index];
+            }
+            FarmWidgetInput::FarmingThreadsChanged(farming_threads) => {
+                self.farming_threads = NonZeroUsize::new(farming_threads);
+            }
+            FarmWidgetInput::MaxPlottingSectorsChanged(max_plotting_sectors) => {
+                if let Some(max_plotting_sectors) = NonZeroUsize::new(max_plotting_sectors) {
+                    self.max_plotting_sectors = max_plotting_sectors;
+                }
+            }
+            FarmWidgetInput::RewardAddressChanged(reward_address) => {
+                let reward_address = reward_address.trim();
+                // Don't set value to avoid resetting cursor position in the entry
+                self.reward_address
+                    .set_is_valid(is_reward_address_valid(reward_address));
+                self.reward_address.value = reward_address.to_string();
+            }
+        }
+
+        if affects_resize_preview {
+            self.update_resize_preview().await;
         }
 
         let is_valid = self.valid();
@@ -376,16 +735,23 @@ impl AsyncFactoryComponent for FarmWidget {
 
 impl FarmWidget {
     pub(super) fn valid(&self) -> bool {
-        if !self.path.is_valid {
-            return false;
-        }
+        self.path.is_valid && self.reward_address.is_valid && self.size_valid()
+    }
 
+    fn size_valid(&self) -> bool {
         match self.size_kind {
             SizeKind::Fixed => self.fixed_size.is_valid,
             SizeKind::FreePercentage => self.free_percentage_size.is_valid,
         }
     }
 
+    /// Number of already plotted sectors that will be discarded by shrinking the farm
+    pub(super) fn discarded_sectors_count(&self) -> SectorIndex {
+        self.resize_preview
+            .map(|resize_preview| resize_preview.discarded_sectors_count())
+            .unwrap_or_default()
+    }
+
     pub(super) fn farm(&self) -> Farm {
         Farm {
             path: PathBuf::clone(&self.path),
@@ -393,6 +759,75 @@ impl FarmWidget {
                 SizeKind::Fixed => &self.fixed_size,
                 SizeKind::FreePercentage => &self.free_percentage_size,
             }),
+            // Defaults are not stored explicitly, such that they can be changed in the future
+            cache_percentage: (self.cache_percentage != CACHE_PERCENTAGE)
+                .then_some(self.cache_percentage),
+            read_mode: (self.read_mode != ReadMode::ConcurrentChunks)
+                .then_some(self.read_mode.into()),
+            farming_threads: self.farming_threads,
+            max_plotting_sectors: (self.max_plotting_sectors != MAX_PLOTTING_SECTORS_PER_FARM)
+                .then_some(self.max_plotting_sectors),
+            reward_address: (!self.reward_address.is_empty())
+                .then(|| String::clone(&self.reward_address)),
+        }
+    }
+
+    /// Estimate how existing farm is affected by size and cache percentage changes, `None` if it
+    /// is not affected or there is no existing farm
+    async fn update_resize_preview(&mut self) {
+        let resize_preview = match self.current_state {
+            Some(current_state) if self.path.is_valid && self.size_valid() => {
+                resolve_farm_size(&self.farm(), self.chain_id.as_deref())
+                    .await
+                    .ok()
+                    .map(|resolved_farm_size| ResizePreview {
+                        plotted_sectors_count: current_state.plotted_sectors_count,
+                        old_total_sectors_count: current_state.total_sectors_count,
+                        new_total_sectors_count: current_state.estimate_total_sectors_count(
+                            resolved_farm_size.allocated_space,
+                            self.cache_percentage.get(),
+                        ),
+                        estimated_duration: FarmResize {
+                            old_allocated_space: current_state.allocated_space,
+                            new_allocated_space: resolved_farm_size.allocated_space,
+                        }
+                        .estimated_duration(),
+                    })
+                    .filter(|resize_preview| {
+                        resize_preview.new_total_sectors_count
+                            != resize_preview.old_total_sectors_count
+                    })
+            }
+            _ => None,
+        };
+
+        self.set_resize_preview(resize_preview);
+    }
+
+    fn resize_preview_message(&self) -> String {
+        let Some(resize_preview) = self.resize_preview else {
+            return String::new();
+        };
+
+        let discarded_sectors_count = resize_preview.discarded_sectors_count();
+        if discarded_sectors_count > 0 {
+            T.configuration_farm_resize_discards_sectors(
+                resize_preview.old_total_sectors_count,
+                resize_preview.new_total_sectors_count,
+                discarded_sectors_count,
+            )
+            .to_string()
+        } else {
+            T.configuration_farm_resize(
+                resize_preview.old_total_sectors_count,
+                resize_preview.new_total_sectors_count,
+                resize_preview
+                    .estimated_duration
+                    .as_secs()
+                    .div_ceil(60)
+                    .max(1),
+            )
+            .to_string()
         }
     }
 }
@@ -1,5 +1,6 @@
 use crate::backend::LoadingStep;
 use crate::frontend::translations::{AsDefaultStr, T};
+use bytesize::ByteSize;
 use gtk::prelude::*;
 use relm4::prelude::*;
 
@@ -104,8 +105,11 @@ impl LoadingView {
                     | LoadingStep::ConsensusNodeCreatedSuccessfully => {
                         T.loading_consensus_node_title()
                     }
+                    LoadingStep::ConnectingToExternalNode { .. }
+                    | LoadingStep::ConnectedToExternalNode => T.loading_external_node_title(),
                     LoadingStep::InitializingFarms { .. }
                     | LoadingStep::FarmInitialized { .. }
+                    | LoadingStep::ResizingFarm { .. }
                     | LoadingStep::FarmerCreatedSuccessfully => T.loading_farmer_title(),
                     LoadingStep::WipingFarm { .. } | LoadingStep::WipedFarmsSuccessfully => {
                         T.loading_wiping_farmer_data_title()
@@ -113,6 +117,10 @@ impl LoadingView {
                     LoadingStep::WipingNode { .. } | LoadingStep::WipedNodeSuccessfully => {
                         T.loading_wiping_node_data_title()
                     }
+                    LoadingStep::WipingScheduledData { .. }
+                    | LoadingStep::WipedScheduledDataSuccessfully => {
+                        T.loading_wiping_scheduled_data_title()
+                    }
                 };
                 self.set_title(title.to_string());
                 self.set_progress(step.progress());
@@ -179,6 +187,12 @@ impl LoadingView {
                     LoadingStep::ConsensusNodeCreatedSuccessfully => T
                         .loading_consensus_node_step_created_successfully()
                         .to_string(),
+                    LoadingStep::ConnectingToExternalNode { url } => {
+                        T.loading_external_node_step_connecting(url).to_string()
+                    }
+                    LoadingStep::ConnectedToExternalNode => {
+                        T.loading_external_node_step_connected().to_string()
+                    }
                     LoadingStep::InitializingFarms { farms_total } => T
                         .loading_farmer_step_initializing(0, farms_total)
                         .to_string(),
@@ -188,6 +202,20 @@ impl LoadingView {
                     } => T
                         .loading_farmer_step_initializing(u16::from(farm_index) + 1, farms_total)
                         .to_string(),
+                    LoadingStep::ResizingFarm {
+                        farm_index,
+                        farms_total,
+                        farm_resize,
+                        progress,
+                    } => T
+                        .loading_farmer_step_resizing_farm(
+                            u16::from(farm_index) + 1,
+                            farms_total,
+                            ByteSize::b(farm_resize.old_allocated_space).to_string_as(true),
+                            ByteSize::b(farm_resize.new_allocated_space).to_string_as(true),
+                            progress,
+                        )
+                        .to_string(),
                     LoadingStep::FarmerCreatedSuccessfully => {
                         T.loading_farmer_step_created_successfully().to_string()
                     }
@@ -211,6 +239,12 @@ impl LoadingView {
                     LoadingStep::WipedNodeSuccessfully => {
                         T.loading_wiping_node_data_step_success().to_string()
                     }
+                    LoadingStep::WipingScheduledData { path } => T
+                        .loading_wiping_scheduled_data_step_wiping(path.display().to_string())
+                        .to_string(),
+                    LoadingStep::WipedScheduledDataSuccessfully => {
+                        T.loading_wiping_scheduled_data_step_success().to_string()
+                    }
                 };
                 self.set_message(message);
             }
@@ -2,30 +2,36 @@ mod farm;
 mod node;
 
 use crate::backend::config::{Config, RawConfig};
-use crate::backend::farmer::{FarmerNotification, InitialFarmState};
+use crate::backend::farmer::{DiskFarm, FarmerNotification, InitialFarmState};
+use crate::backend::history::{FarmingHistory, HistoryStats};
 use crate::backend::node::ChainInfo;
 use crate::backend::{FarmIndex, NodeNotification};
 use crate::frontend::NotificationExt;
-use crate::frontend::running::farm::{FarmWidget, FarmWidgetInit, FarmWidgetInput};
+use crate::frontend::running::farm::{
+    FarmWidget, FarmWidgetInit, FarmWidgetInput, FarmWidgetOutput,
+};
 use crate::frontend::running::node::{NodeInput, NodeView};
 use crate::frontend::translations::{AsDefaultStr, T};
 use crate::frontend::widgets::progress_circle::{
     ProgressCircle, ProgressCircleInit, ProgressCircleInput,
 };
 use crate::icon_names;
+use chrono::{Days, NaiveDateTime};
 use gtk::prelude::*;
 use notify_rust::Notification;
+use relm4::RelmRemoveAllExt;
 use relm4::factory::FactoryHashMap;
 use relm4::prelude::*;
 use sp_consensus_subspace::ChainConstants;
-use std::num::NonZeroU8;
+use std::collections::HashMap;
 use std::time::{Duration, Instant};
-use subspace_core_primitives::BlockNumber;
 use subspace_core_primitives::pieces::Piece;
 use subspace_core_primitives::solutions::{SolutionRange, solution_range_to_pieces};
+use subspace_core_primitives::{BlockNumber, PublicKey};
 use subspace_farmer::farm::{
     FarmingNotification, ProvingResult, SectorPlottingDetails, SectorUpdate,
 };
+use subspace_farmer::utils::ss58::parse_ss58_reward_address;
 use subspace_runtime_primitives::{AI3, Balance};
 use tracing::{debug, warn};
 
@@ -38,25 +44,68 @@ pub struct RunningInit {
 #[allow(clippy::large_enum_variant)]
 pub enum RunningInput {
     Initialize {
-        best_block_number: BlockNumber,
-        reward_address_balance: Balance,
+        /// Not known when connected to external node
+        best_block_number: Option<BlockNumber>,
+        /// Not known when connected to external node
+        reward_address_balance: Option<Balance>,
+        /// Not known when connected to external node
+        farm_reward_address_balances: Option<Vec<(PublicKey, Balance)>>,
         initial_farm_states: Vec<InitialFarmState>,
-        cache_percentage: NonZeroU8,
         config: Config,
         raw_config: RawConfig,
         chain_info: ChainInfo,
-        chain_constants: ChainConstants,
+        /// Not known when connected to external node
+        chain_constants: Option<ChainConstants>,
+        history: FarmingHistory,
     },
     NodeNotification(NodeNotification),
     FarmerNotification(FarmerNotification<FarmIndex>),
     ToggleFarmDetails,
     TogglePausePlotting,
+    PauseFarmPlotting {
+        farm_index: FarmIndex,
+        paused: bool,
+    },
+    ScrubFarm {
+        farm_index: FarmIndex,
+        dry_run: bool,
+    },
+    ShowHistory,
     WindowResized,
 }
 
 #[derive(Debug)]
 pub enum RunningOutput {
     PausePlotting(bool),
+    PauseFarmPlotting {
+        farm_index: FarmIndex,
+        paused: bool,
+    },
+    ScrubFarm {
+        farm_index: FarmIndex,
+        dry_run: bool,
+    },
+}
+
+/// Farm details necessary for reward ETA estimation
+#[derive(Debug, Copy, Clone)]
+struct FarmStats {
+    /// Space pledged to the farm, excluding piece cache
+    space_pledged: u64,
+    sectors_total: u32,
+    sectors_plotted: u32,
+}
+
+impl FarmStats {
+    fn new(farm: &DiskFarm, initial_farm_state: InitialFarmState) -> Self {
+        Self {
+            // Space dedicated to piece cache doesn't contribute to rewards
+            space_pledged: farm.allocated_space * (100 - u64::from(farm.cache_percentage.get()))
+                / 100,
+            sectors_total: u32::from(initial_farm_state.total_sectors_count),
+            sectors_plotted: u32::from(initial_farm_state.plotted_sectors_count),
+        }
+    }
 }
 
 #[tracker::track]
@@ -66,30 +115,70 @@ struct FarmerState {
     reward_address_balance: Balance,
     piece_cache_sync_progress: f32,
     reward_address_url: String,
+    /// Balances of reward addresses of individual farms that differ from global reward address
+    farm_reward_address_balances: Vec<(String, Balance)>,
+    #[do_not_track]
+    explorer_accounts_url: String,
     token_symbol: String,
-    local_space_pledged: u64,
-    sectors_total: u32,
-    sectors_plotted: u32,
-    cache_percentage: NonZeroU8,
+    #[do_not_track]
+    farm_stats: HashMap<FarmIndex, FarmStats>,
     network_space_pledged: u128,
     slot_probability: (u64, u64),
     slot_duration: Duration,
     last_reward_received_time: Instant,
+    /// Recorded by backend, `None` until initialized
+    #[do_not_track]
+    history: Option<FarmingHistory>,
     #[do_not_track]
     reward_eta_progress_circle: Controller<ProgressCircle>,
 }
 
+impl FarmerState {
+    /// One link to block explorer per farm reward address followed by its balance
+    fn farm_reward_address_balances_markup(&self) -> String {
+        self.farm_reward_address_balances
+            .iter()
+            .map(|(reward_address, balance)| {
+                let balance = (balance / (AI3 / 100)) as f32 / 100.0;
+                let short_reward_address = match (
+                    reward_address.get(..6),
+                    reward_address.get(reward_address.len().saturating_sub(4)..),
+                ) {
+                    (Some(prefix), Some(suffix)) if reward_address.len() > 10 => {
+                        format!("{prefix}…{suffix}")
+                    }
+                    _ => reward_address.clone(),
+                };
+
+                format!(
+                    "<a href=\"{}/{reward_address}\">{short_reward_address}</a> {balance:.2} {}",
+                    self.explorer_accounts_url, self.token_symbol
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+    }
+}
+
 #[tracker::track]
 #[derive(Debug)]
 pub struct RunningView {
     #[do_not_track]
     node_view: Controller<NodeView>,
     node_synced: bool,
+    /// Balances and rewards are not known when farming through external node
+    external_node: bool,
     #[do_not_track]
     farmer_state: FarmerState,
     #[do_not_track]
     farms: FactoryHashMap<u8, FarmWidget>,
     plotting_paused: bool,
+    /// Local time when plotting will be resumed if it is paused by schedule
+    plotting_paused_until: Option<NaiveDateTime>,
+    #[do_not_track]
+    block_authoring_delay: Duration,
+    #[do_not_track]
+    history_grid: gtk::Grid,
 }
 
 #[relm4::component(pub)]
@@ -150,18 +239,58 @@ impl Component for RunningView {
                                     T.running_farmer_button_pause_plotting()
                                 },
                         },
+                        gtk::MenuButton {
+                            set_cursor_from_name: Some("pointer"),
+                            set_direction: gtk::ArrowType::None,
+                            set_has_frame: false,
+                            set_icon_name: icon_names::HISTORY,
+                            set_tooltip: &T.running_farmer_button_history(),
+
+                            #[wrap(Some)]
+                            set_popover = &gtk::Popover {
+                                connect_show => RunningInput::ShowHistory,
+
+                                model.history_grid.clone(),
+                            },
+                        },
+                    },
+                    gtk::Label {
+                        add_css_class: "dim-label",
+                        #[track = "model.changed_plotting_paused_until()"]
+                        set_label: &model
+                            .plotting_paused_until
+                            .map(|paused_until| {
+                                T
+                                    .running_farmer_plotting_paused_by_schedule(
+                                        paused_until.format("%Y-%m-%d %H:%M").to_string()
+                                    )
+                                    .to_string()
+                            })
+                            .unwrap_or_default(),
+                        #[track = "model.changed_plotting_paused_until()"]
+                        set_visible: model.plotting_paused_until.is_some(),
                     },
                     gtk::Box {
                         set_halign: gtk::Align::End,
                         set_hexpand: true,
 
                         gtk::Box {
-                            #[track = "model.changed_node_synced()"]
-                            set_visible: model.node_synced,
+                            #[track = "model.changed_node_synced() || model.changed_external_node()"]
+                            set_visible: model.node_synced && !model.external_node,
 
                             model.farmer_state.reward_eta_progress_circle.widget().clone(),
                         },
 
+                        gtk::Label {
+                            set_margin_end: 10,
+                            #[track = "model.farmer_state.changed_farm_reward_address_balances() || model.farmer_state.changed_token_symbol()"]
+                            set_label: &model.farmer_state.farm_reward_address_balances_markup(),
+                            set_tooltip: &T.running_farmer_farm_reward_address_balances_tooltip(),
+                            set_use_markup: true,
+                            #[track = "model.farmer_state.changed_farm_reward_address_balances()"]
+                            set_visible: !model.farmer_state.farm_reward_address_balances.is_empty(),
+                        },
+
                         gtk::LinkButton {
                             remove_css_class: "link",
                             set_cursor_from_name: Some("pointer"),
@@ -169,6 +298,8 @@ impl Component for RunningView {
                             #[track = "model.farmer_state.changed_reward_address_url()"]
                             set_uri: &model.farmer_state.reward_address_url,
                             set_use_underline: false,
+                            #[track = "model.changed_external_node()"]
+                            set_visible: !model.external_node,
 
                             gtk::Label {
                                 #[track = "model.farmer_state.changed_reward_address_balance() || model.farmer_state.changed_initial_reward_address_balance() || model.farmer_state.changed_token_symbol()"]
@@ -243,12 +374,23 @@ impl Component for RunningView {
     fn init(
         init: Self::Init,
         _root: Self::Root,
-        _sender: ComponentSender<Self>,
+        sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
         let node_view = NodeView::builder().launch(()).detach();
         let farms = FactoryHashMap::builder()
             .launch(gtk::Box::default())
-            .detach();
+            .forward(sender.input_sender(), |output| match output {
+                FarmWidgetOutput::PausePlotting { farm_index, paused } => {
+                    RunningInput::PauseFarmPlotting { farm_index, paused }
+                }
+                FarmWidgetOutput::ScrubFarm {
+                    farm_index,
+                    dry_run,
+                } => RunningInput::ScrubFarm {
+                    farm_index,
+                    dry_run,
+                },
+            });
 
         let reward_eta_progress_circle = ProgressCircle::builder()
             .launch(ProgressCircleInit {
@@ -260,25 +402,36 @@ impl Component for RunningView {
         let model = Self {
             node_view,
             node_synced: false,
+            external_node: false,
             farmer_state: FarmerState {
                 initial_reward_address_balance: 0,
                 reward_address_balance: 0,
                 piece_cache_sync_progress: 0.0,
                 reward_address_url: String::new(),
+                farm_reward_address_balances: Vec::new(),
+                explorer_accounts_url: String::new(),
                 token_symbol: String::new(),
-                local_space_pledged: 0,
-                sectors_total: 0,
-                sectors_plotted: 0,
-                cache_percentage: NonZeroU8::MIN,
+                farm_stats: HashMap::new(),
                 network_space_pledged: 1,
                 slot_probability: (1, 1),
                 slot_duration: Duration::from_secs(1),
                 last_reward_received_time: Instant::now(),
+                history: None,
                 reward_eta_progress_circle,
                 tracker: u16::MAX,
             },
             farms,
             plotting_paused: init.plotting_paused,
+            plotting_paused_until: None,
+            block_authoring_delay: Duration::ZERO,
+            history_grid: gtk::Grid::builder()
+                .column_spacing(10)
+                .row_spacing(5)
+                .margin_top(5)
+                .margin_bottom(5)
+                .margin_start(5)
+                .margin_end(5)
+                .build(),
             tracker: u8::MAX,
         };
 
@@ -303,74 +456,91 @@ impl RunningView {
             RunningInput::Initialize {
                 best_block_number,
                 reward_address_balance,
+                farm_reward_address_balances,
                 initial_farm_states,
-                cache_percentage,
                 config,
                 raw_config,
                 chain_info,
                 chain_constants,
+                history,
             } => {
+                self.farmer_state.history.replace(history);
+                if let Some(chain_constants) = chain_constants {
+                    self.farmer_state.slot_probability = chain_constants.slot_probability();
+                    self.farmer_state.slot_duration = chain_constants.slot_duration().as_duration();
+                    self.block_authoring_delay = chain_constants.slot_duration().as_duration()
+                        * u64::from(chain_constants.block_authoring_delay()) as u32;
+                }
+                self.set_external_node(config.node.external_rpc_url.is_some());
+
                 for (farm_index, (initial_farm_state, farm)) in initial_farm_states
                     .iter()
                     .copied()
                     .zip(config.farms.iter().cloned())
                     .enumerate()
                 {
-                    self.farms.insert(
+                    self.add_farm(
                         u8::try_from(farm_index).expect(
                             "More than 256 plots are not supported, this is checked on \
                             backend; qed",
                         ),
-                        FarmWidgetInit {
-                            farm,
-                            total_sectors: initial_farm_state.total_sectors_count,
-                            plotted_total_sectors: initial_farm_state.plotted_sectors_count,
-                            plotting_paused: self.plotting_paused,
-                            slot_duration: chain_constants.slot_duration().as_duration(),
-                            block_authoring_delay: chain_constants.slot_duration().as_duration()
-                                * u64::from(chain_constants.block_authoring_delay()) as u32,
-                        },
+                        farm,
+                        initial_farm_state,
+                        // Nothing is paused when farmer starts
+                        false,
                     );
                 }
 
-                self.farmer_state
-                    .set_initial_reward_address_balance(reward_address_balance);
-                self.farmer_state
-                    .set_reward_address_balance(reward_address_balance);
+                // Balance is hidden when connected to external node
+                if let Some(reward_address_balance) = reward_address_balance {
+                    self.farmer_state
+                        .set_initial_reward_address_balance(reward_address_balance);
+                    self.farmer_state
+                        .set_reward_address_balance(reward_address_balance);
+                }
                 // TODO: Would be great to have `gemini-3h` in chain spec, but it is
                 //  not available in there in clean form
-                self.farmer_state.set_reward_address_url(format!(
-                    "https://explorer.subspace.network/{}/consensus/accounts/{}",
+                self.farmer_state.explorer_accounts_url = format!(
+                    "https://explorer.subspace.network/{}/consensus/accounts",
                     chain_info
                         .protocol_id
                         .strip_prefix("subspace-")
                         .unwrap_or(&chain_info.protocol_id),
+                );
+                self.farmer_state.set_reward_address_url(format!(
+                    "{}/{}",
+                    self.farmer_state.explorer_accounts_url,
                     raw_config.reward_address()
                 ));
+                // Show addresses the way user has configured them
+                let farm_reward_address_balances = farm_reward_address_balances
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(public_key, balance)| {
+                        let reward_address = raw_config
+                            .farms()
+                            .iter()
+                            .filter_map(|farm| farm.reward_address.as_deref())
+                            .find(|reward_address| {
+                                parse_ss58_reward_address(reward_address).ok() == Some(public_key)
+                            })
+                            .unwrap_or_default()
+                            .to_string();
+
+                        (reward_address, balance)
+                    })
+                    .collect();
+                self.farmer_state
+                    .set_farm_reward_address_balances(farm_reward_address_balances);
                 self.farmer_state
                     .get_mut_token_symbol()
                     .clone_from(&chain_info.token_symbol);
-                self.farmer_state.local_space_pledged =
-                    config.farms.iter().map(|farm| farm.allocated_space).sum();
-                let (total_sectors_count, plotted_sectors_count) = initial_farm_states.iter().fold(
-                    (0, 0),
-                    |(total_sectors_count, plotted_sectors_count), initial_farm_state| {
-                        (
-                            total_sectors_count + u32::from(initial_farm_state.total_sectors_count),
-                            plotted_sectors_count
-                                + u32::from(initial_farm_state.plotted_sectors_count),
-                        )
-                    },
-                );
-                self.farmer_state.sectors_total = total_sectors_count;
-                self.farmer_state.sectors_plotted = plotted_sectors_count;
-                self.farmer_state.cache_percentage = cache_percentage;
-                self.farmer_state.slot_probability = chain_constants.slot_probability();
-                self.farmer_state.slot_duration = chain_constants.slot_duration().as_duration();
                 self.node_view.emit(NodeInput::Initialize {
                     best_block_number,
                     chain_info,
-                    node_path: raw_config.node_path().clone(),
+                    node_path: config.node_path.clone(),
+                    node_pruning: config.node.pruning(),
+                    external_rpc_url: config.node.external_rpc_url.clone(),
                 });
             }
             RunningInput::NodeNotification(node_notification) => {
@@ -416,6 +586,9 @@ impl RunningView {
                                 .set_reward_address_balance(imported_block.reward_address_balance);
                             self.farmer_state.last_reward_received_time = Instant::now();
                         }
+                        self.update_farm_reward_address_balances(
+                            &imported_block.farm_reward_address_balances,
+                        );
 
                         let network_space_pledged_pieces = solution_range_to_pieces(
                             imported_block.solution_range,
@@ -441,8 +614,9 @@ impl RunningView {
                             old_plotted_sector: None,
                             ..
                         })
-                    ) {
-                        self.farmer_state.sectors_plotted += 1;
+                    ) && let Some(farm_stats) = self.farmer_state.farm_stats.get_mut(&farm_index)
+                    {
+                        farm_stats.sectors_plotted += 1;
                     }
                     self.farms.send(
                         &farm_index,
@@ -491,6 +665,44 @@ impl RunningView {
                     self.farms
                         .send(&farm_index, FarmWidgetInput::Error { error });
                 }
+                FarmerNotification::FarmAdded {
+                    farm_index,
+                    farm,
+                    initial_farm_state,
+                    plotting_paused,
+                } => {
+                    self.add_farm(farm_index, farm, initial_farm_state, plotting_paused);
+                }
+                FarmerNotification::FarmRemoved { farm_index } => {
+                    self.farms.remove(&farm_index);
+                    self.farmer_state.farm_stats.remove(&farm_index);
+                }
+                FarmerNotification::PlottingScheduleUpdate { paused_until } => {
+                    self.set_plotting_paused_until(paused_until);
+                    self.farms
+                        .broadcast(FarmWidgetInput::PausePlotting(self.is_plotting_paused()));
+                }
+                FarmerNotification::FarmScrub { farm_index, step } => {
+                    self.farms
+                        .send(&farm_index, FarmWidgetInput::ScrubStep(step));
+                }
+                FarmerNotification::FarmRestart { farm_index, step } => {
+                    self.farms
+                        .send(&farm_index, FarmWidgetInput::RestartStep(step));
+                }
+                FarmerNotification::FarmResize {
+                    farm_index,
+                    farm_resize,
+                    progress,
+                } => {
+                    self.farms.send(
+                        &farm_index,
+                        FarmWidgetInput::ResizeProgress {
+                            farm_resize,
+                            progress,
+                        },
+                    );
+                }
             },
             RunningInput::ToggleFarmDetails => {
                 self.farms.broadcast(FarmWidgetInput::ToggleFarmDetails);
@@ -498,7 +710,7 @@ impl RunningView {
             RunningInput::TogglePausePlotting => {
                 self.set_plotting_paused(!self.plotting_paused);
                 self.farms
-                    .broadcast(FarmWidgetInput::PausePlotting(self.plotting_paused));
+                    .broadcast(FarmWidgetInput::PausePlotting(self.is_plotting_paused()));
                 if sender
                     .output(RunningOutput::PausePlotting(self.plotting_paused))
                     .is_err()
@@ -506,12 +718,178 @@ impl RunningView {
                     debug!("Failed to send RunningOutput::TogglePausePlotting");
                 }
             }
+            RunningInput::PauseFarmPlotting { farm_index, paused } => {
+                if sender
+                    .output(RunningOutput::PauseFarmPlotting { farm_index, paused })
+                    .is_err()
+                {
+                    debug!("Failed to send RunningOutput::PauseFarmPlotting");
+                }
+            }
+            RunningInput::ScrubFarm {
+                farm_index,
+                dry_run,
+            } => {
+                if sender
+                    .output(RunningOutput::ScrubFarm {
+                        farm_index,
+                        dry_run,
+                    })
+                    .is_err()
+                {
+                    debug!("Failed to send RunningOutput::ScrubFarm");
+                }
+            }
+            RunningInput::ShowHistory => {
+                self.update_history_grid();
+            }
             RunningInput::WindowResized => {
                 self.farms.broadcast(FarmWidgetInput::WindowResized);
             }
         }
     }
 
+    fn update_history_grid(&self) {
+        let Some(history) = &self.farmer_state.history else {
+            return;
+        };
+        let today = FarmingHistory::today();
+
+        let mut rows = vec![(
+            T.running_farmer_history_today().to_string(),
+            history.day(today),
+        )];
+        rows.extend((1..7).filter_map(|days_ago| {
+            let day = today.checked_sub_days(Days::new(days_ago))?;
+            Some((day.format("%Y-%m-%d").to_string(), history.day(day)))
+        }));
+        rows.push((
+            T.running_farmer_history_last_7_days().to_string(),
+            history.last_days(7),
+        ));
+        rows.push((
+            T.running_farmer_history_last_30_days().to_string(),
+            history.last_days(30),
+        ));
+        rows.push((
+            T.running_farmer_history_lifetime().to_string(),
+            history.lifetime(),
+        ));
+
+        let grid = &self.history_grid;
+        grid.remove_all();
+
+        let header = [
+            T.running_farmer_history_period(),
+            T.running_farmer_history_rewards(),
+            T.running_farmer_history_missed_rewards(),
+            T.running_farmer_history_sectors_plotted(),
+            T.running_farmer_history_sector_plotting_time(),
+            T.running_farmer_history_auditing_time(),
+            T.running_farmer_history_proving_time(),
+        ];
+        for (column, title) in header.iter().enumerate() {
+            let label = gtk::Label::builder()
+                .css_classes(["heading"])
+                .halign(gtk::Align::Start)
+                .label(title.as_str())
+                .build();
+            grid.attach(&label, column as i32, 0, 1, 1);
+        }
+
+        let token_symbol = &self.farmer_state.token_symbol;
+        for (row, (period, stats)) in rows.into_iter().enumerate() {
+            let cells = Self::history_row(period, &stats, token_symbol);
+            for (column, cell) in cells.into_iter().enumerate() {
+                let label = gtk::Label::builder()
+                    .halign(gtk::Align::Start)
+                    .label(cell)
+                    .build();
+                grid.attach(&label, column as i32, row as i32 + 1, 1, 1);
+            }
+        }
+    }
+
+    fn history_row(period: String, stats: &HistoryStats, token_symbol: &str) -> [String; 7] {
+        let format_time = |time: Option<Duration>| {
+            time.map(|time| format!("{:.2}s", time.as_secs_f32()))
+                .unwrap_or_else(|| "-".to_string())
+        };
+        let reward_amount = (stats.reward_amount / (AI3 / 100)) as f32 / 100.0;
+
+        [
+            period,
+            format!("{} ({reward_amount:.2} {token_symbol})", stats.rewards),
+            stats.missed_proofs.to_string(),
+            format!("{}/{}", stats.sectors_plotted, stats.sectors_replotted),
+            stats
+                .average_sector_plotting_time()
+                .map(|time| format!("{:.2}m", time.as_secs_f32() / 60.0))
+                .unwrap_or_else(|| "-".to_string()),
+            format_time(stats.average_auditing_time()),
+            format_time(stats.average_proving_time()),
+        ]
+    }
+
+    /// Whether plotting is paused either by user or by schedule
+    fn is_plotting_paused(&self) -> bool {
+        self.plotting_paused || self.plotting_paused_until.is_some()
+    }
+
+    fn add_farm(
+        &mut self,
+        farm_index: FarmIndex,
+        farm: DiskFarm,
+        initial_farm_state: InitialFarmState,
+        farm_plotting_paused: bool,
+    ) {
+        self.farmer_state
+            .farm_stats
+            .insert(farm_index, FarmStats::new(&farm, initial_farm_state));
+        self.farms.insert(
+            farm_index,
+            FarmWidgetInit {
+                farm,
+                total_sectors: initial_farm_state.total_sectors_count,
+                plotted_total_sectors: initial_farm_state.plotted_sectors_count,
+                plotting_paused: self.is_plotting_paused(),
+                farm_plotting_paused,
+                slot_duration: self.farmer_state.slot_duration,
+                block_authoring_delay: self.block_authoring_delay,
+            },
+        );
+        if self.node_synced {
+            self.farms
+                .send(&farm_index, FarmWidgetInput::NodeSynced(true));
+        }
+    }
+
+    fn update_farm_reward_address_balances(
+        &mut self,
+        farm_reward_address_balances: &[(PublicKey, Balance)],
+    ) {
+        let new_farm_reward_address_balances = self
+            .farmer_state
+            .farm_reward_address_balances
+            .iter()
+            .zip(farm_reward_address_balances)
+            .map(|((reward_address, balance), (_public_key, new_balance))| {
+                // Rewards received during sync are not related to farming
+                if self.node_synced
+                    && let Some(increased_by) = new_balance.checked_sub(*balance)
+                    && increased_by > 0
+                {
+                    self.farmer_state.last_reward_received_time = Instant::now();
+                }
+
+                (reward_address.clone(), *new_balance)
+            })
+            .collect();
+
+        self.farmer_state
+            .set_farm_reward_address_balances(new_farm_reward_address_balances);
+    }
+
     fn update_reward_eta_progress(&self, voting_solution_range: SolutionRange) {
         // Space pledged derived from voting solution range is not real, but it is useful to
         // identify reward ETA because it is wider than regular solution range and will result
@@ -524,14 +902,16 @@ impl RunningView {
             u128::from(network_voting_space_pledged_pieces) * Piece::SIZE as u128;
 
         // Take into consideration how much space was plotted so far
-        let local_space_pledged = if self.farmer_state.sectors_total == 0 {
-            0
-        } else {
-            self.farmer_state.local_space_pledged * u64::from(self.farmer_state.sectors_plotted)
-                / u64::from(self.farmer_state.sectors_total)
-                * (100 - u64::from(self.farmer_state.cache_percentage.get()))
-                / 100
-        };
+        let local_space_pledged = self
+            .farmer_state
+            .farm_stats
+            .values()
+            .filter(|farm_stats| farm_stats.sectors_total != 0)
+            .map(|farm_stats| {
+                farm_stats.space_pledged * u64::from(farm_stats.sectors_plotted)
+                    / u64::from(farm_stats.sectors_total)
+            })
+            .sum::<u64>();
 
         // network_voting_space_pledged/local_space_pledged is a time multiplier based on how much
         // smaller space pledged is comparing to network space pledged, then we also account for
@@ -1,4 +1,6 @@
-use crate::backend::farmer::DiskFarm;
+use crate::backend::FarmIndex;
+use crate::backend::farmer::scrub::ScrubStep;
+use crate::backend::farmer::{DiskFarm, FarmResize, FarmRestartStep};
 use crate::frontend::NotificationExt;
 use crate::frontend::translations::{AsDefaultStr, T};
 use crate::icon_names;
@@ -17,7 +19,7 @@ use subspace_farmer::farm::{
     FarmingError, FarmingNotification, ProvingResult, SectorExpirationDetails,
     SectorPlottingDetails, SectorUpdate,
 };
-use tracing::{error, warn};
+use tracing::{debug, error, warn};
 
 const INVALID_SCORE_VALUE: f64 = -1.0;
 /// Number of samples over which to track auditing time, 1 minute in slots
@@ -30,6 +32,8 @@ const PROVING_TIME_TRACKING_WINDOW: usize = 10;
 const EXCELLENT_PROVING_TIME: Duration = Duration::from_millis(1800);
 /// Number of samples over which to track sector plotting time
 const SECTOR_PLOTTING_TIME_TRACKING_WINDOW: usize = 10;
+/// Farm check issues shown in tooltip, the rest can be found in logs
+const SCRUB_ISSUES_IN_TOOLTIP: usize = 20;
 
 fn format_size(bytes: u64) -> String {
     ByteSize::b(bytes).to_string_as(bytes % ByteSize::mb(1).as_u64() != 0)
@@ -80,6 +84,7 @@ pub(super) struct FarmWidgetInit {
     pub(super) total_sectors: SectorIndex,
     pub(super) plotted_total_sectors: SectorIndex,
     pub(super) plotting_paused: bool,
+    pub(super) farm_plotting_paused: bool,
     pub(super) slot_duration: Duration,
     pub(super) block_authoring_delay: Duration,
 }
@@ -92,6 +97,16 @@ pub(super) enum FarmWidgetInput {
     },
     FarmingNotification(FarmingNotification),
     PausePlotting(bool),
+    TogglePauseFarmPlotting,
+    ScrubDryRun(bool),
+    ScrubFarm,
+    ScrubStep(ScrubStep),
+    DismissScrubResult,
+    RestartStep(FarmRestartStep),
+    ResizeProgress {
+        farm_resize: FarmResize,
+        progress: f32,
+    },
     OpenFarmFolder,
     NodeSynced(bool),
     ToggleFarmDetails,
@@ -102,9 +117,23 @@ pub(super) enum FarmWidgetInput {
     RecalculateSectorRows,
 }
 
+#[derive(Debug)]
+pub(super) enum FarmWidgetOutput {
+    PausePlotting {
+        farm_index: FarmIndex,
+        paused: bool,
+    },
+    ScrubFarm {
+        farm_index: FarmIndex,
+        dry_run: bool,
+    },
+}
+
 #[tracker::track]
 #[derive(Debug)]
 pub(super) struct FarmWidget {
+    #[do_not_track]
+    farm_index: FarmIndex,
     path: PathBuf,
     size: String,
     #[do_not_track]
@@ -130,7 +159,22 @@ pub(super) struct FarmWidget {
     non_fatal_farming_error: Option<Arc<FarmingError>>,
     farm_details: bool,
     encoding_sectors: usize,
+    /// Plotting is paused for all farms
     plotting_paused: bool,
+    /// Plotting is paused for this farm only
+    farm_plotting_paused: bool,
+    #[do_not_track]
+    scrub_dry_run: bool,
+    /// Progress or result of the latest farm check
+    #[no_eq]
+    scrub_step: Option<ScrubStep>,
+    /// Automatic restart after farm has crashed, farm widget is replaced once farm is started
+    #[no_eq]
+    restart_step: Option<FarmRestartStep>,
+    /// Estimated progress in % of farm resizing after configuration update, farm widget is
+    /// replaced once farm is started
+    #[no_eq]
+    resize_progress: Option<(FarmResize, f32)>,
     slot_duration: Duration,
     block_authoring_delay: Duration,
     #[no_eq]
@@ -141,10 +185,10 @@ pub(super) struct FarmWidget {
 impl FactoryComponent for FarmWidget {
     type Init = FarmWidgetInit;
     type Input = FarmWidgetInput;
-    type Output = ();
+    type Output = FarmWidgetOutput;
     type CommandOutput = ();
     type ParentWidget = gtk::Box;
-    type Index = u8;
+    type Index = FarmIndex;
 
     view! {
         #[root]
@@ -287,8 +331,150 @@ impl FactoryComponent for FarmWidget {
                                     set_visible: self.non_fatal_farming_error.is_some(),
                                 },
                             },
+
+                            gtk::ToggleButton {
+                                connect_clicked => FarmWidgetInput::TogglePauseFarmPlotting,
+                                set_active: self.farm_plotting_paused,
+                                set_cursor_from_name: Some("pointer"),
+                                set_has_frame: false,
+                                #[track = "self.changed_scrub_step()"]
+                                set_sensitive: !self.is_scrubbing(),
+                                #[track = "self.changed_farm_plotting_paused()"]
+                                set_icon_name:
+                                    if self.farm_plotting_paused {
+                                        icon_names::PLAY
+                                    } else {
+                                        icon_names::PAUSE
+                                    },
+                                #[track = "self.changed_farm_plotting_paused()"]
+                                set_tooltip:
+                                    &if self.farm_plotting_paused {
+                                        T.running_farmer_farm_button_resume_plotting()
+                                    } else {
+                                        T.running_farmer_farm_button_pause_plotting()
+                                    },
+                            },
+
+                            gtk::MenuButton {
+                                set_cursor_from_name: Some("pointer"),
+                                set_direction: gtk::ArrowType::None,
+                                set_has_frame: false,
+                                set_icon_name: icon_names::MENU_LARGE,
+                                set_tooltip: &T.running_farmer_farm_button_maintenance(),
+                                #[track = "self.changed_scrub_step()"]
+                                set_sensitive: !self.is_scrubbing(),
+
+                                #[wrap(Some)]
+                                set_popover: maintenance_popover = &gtk::Popover {
+                                    gtk::Box {
+                                        set_orientation: gtk::Orientation::Vertical,
+                                        set_spacing: 10,
+
+                                        gtk::Button {
+                                            set_label: &T.running_farmer_farm_button_scrub(),
+                                            set_tooltip: &T.running_farmer_farm_button_scrub_tooltip(),
+                                            connect_clicked[sender, maintenance_popover] => move |_button| {
+                                                maintenance_popover.popdown();
+                                                sender.input(FarmWidgetInput::ScrubFarm);
+                                            },
+                                        },
+
+                                        gtk::CheckButton {
+                                            set_label: Some(&T.running_farmer_farm_scrub_dry_run()),
+                                            connect_toggled[sender] => move |check_button| {
+                                                sender.input(FarmWidgetInput::ScrubDryRun(
+                                                    check_button.is_active()
+                                                ));
+                                            },
+                                        },
+                                    },
+                                },
+                            },
+                        }
+                    },
+                },
+            },
+
+            gtk::Box {
+                set_spacing: 5,
+                #[track = "self.changed_scrub_step()"]
+                set_visible: self.scrub_step.is_some(),
+
+                gtk::Label {
+                    set_halign: gtk::Align::Start,
+                    set_wrap: true,
+                    #[track = "self.changed_scrub_step()"]
+                    set_css_classes: match &self.scrub_step {
+                        Some(ScrubStep::Finished { summary }) => {
+                            if summary.is_healthy() {
+                                &["success-label"]
+                            } else {
+                                &["warning-label"]
+                            }
                         }
+                        Some(ScrubStep::Failed { .. }) => &["error-label"],
+                        _ => &[],
                     },
+                    #[track = "self.changed_scrub_step()"]
+                    set_label: &self.scrub_status(),
+                    #[track = "self.changed_scrub_step()"]
+                    set_tooltip_text: self.scrub_issues().as_deref(),
+                },
+
+                gtk::Spinner {
+                    start: (),
+                    #[track = "self.changed_scrub_step()"]
+                    set_visible: self.is_scrubbing(),
+                },
+
+                gtk::Button {
+                    connect_clicked => FarmWidgetInput::DismissScrubResult,
+                    set_cursor_from_name: Some("pointer"),
+                    set_has_frame: false,
+                    set_icon_name: icon_names::CROSS_SMALL,
+                    set_tooltip: &T.running_farmer_farm_scrub_dismiss(),
+                    #[track = "self.changed_scrub_step()"]
+                    set_visible: !self.is_scrubbing(),
+                },
+            },
+
+            gtk::Box {
+                set_spacing: 5,
+                #[track = "self.changed_restart_step()"]
+                set_visible: self.restart_step.is_some(),
+
+                gtk::Label {
+                    add_css_class: "warning-label",
+                    set_halign: gtk::Align::Start,
+                    set_wrap: true,
+                    #[track = "self.changed_restart_step()"]
+                    set_label: &self.restart_status(),
+                },
+
+                gtk::Spinner {
+                    start: (),
+                    #[track = "self.changed_restart_step()"]
+                    set_visible: matches!(
+                        self.restart_step,
+                        Some(FarmRestartStep::Restarting { .. })
+                    ),
+                },
+            },
+
+            gtk::Box {
+                set_spacing: 5,
+                #[track = "self.changed_resize_progress()"]
+                set_visible: self.resize_progress.is_some(),
+
+                gtk::Label {
+                    set_halign: gtk::Align::Start,
+                    set_wrap: true,
+                    #[track = "self.changed_resize_progress()"]
+                    set_label: &self.resize_status(),
+                },
+
+                gtk::Spinner {
+                    start: (),
                 },
             },
 
@@ -312,9 +498,9 @@ impl FactoryComponent for FarmWidget {
                         gtk::Label {
                             set_halign: gtk::Align::Start,
 
-                            #[track = "self.changed_plotting_state() || self.changed_encoding_sectors() || self.changed_plotting_paused() || self.changed_is_node_synced()"]
+                            #[track = "self.changed_plotting_state() || self.changed_encoding_sectors() || self.changed_plotting_paused() || self.changed_farm_plotting_paused() || self.changed_is_node_synced()"]
                             set_label: {
-                                let pausing_state = if self.plotting_paused {
+                                let pausing_state = if self.plotting_paused || self.farm_plotting_paused {
                                     if self.encoding_sectors > 0 {
                                         "pausing"
                                     } else {
@@ -391,7 +577,7 @@ impl FactoryComponent for FarmWidget {
         },
     }
 
-    fn init_model(init: Self::Init, _index: &Self::Index, _sender: FactorySender<Self>) -> Self {
+    fn init_model(init: Self::Init, index: &Self::Index, _sender: FactorySender<Self>) -> Self {
         let mut sectors = Vec::with_capacity(usize::from(init.total_sectors));
         for sector_index in 0..init.total_sectors {
             let sector = gtk::Box::builder()
@@ -409,6 +595,7 @@ impl FactoryComponent for FarmWidget {
         let sector_rows = gtk::Box::new(gtk::Orientation::Vertical, 0);
 
         Self {
+            farm_index: *index,
             path: init.farm.directory,
             size: format_size(init.farm.allocated_space),
             auditing_time: SingleSumSMA::from_zero(Duration::ZERO),
@@ -428,6 +615,11 @@ impl FactoryComponent for FarmWidget {
             farm_details: false,
             encoding_sectors: 0,
             plotting_paused: init.plotting_paused,
+            farm_plotting_paused: init.farm_plotting_paused,
+            scrub_dry_run: false,
+            scrub_step: None,
+            restart_step: None,
+            resize_progress: None,
             slot_duration: init.slot_duration,
             block_authoring_delay: init.block_authoring_delay,
             error: None,
@@ -571,6 +763,64 @@ impl FarmWidget {
             FarmWidgetInput::PausePlotting(plotting_paused) => {
                 self.set_plotting_paused(plotting_paused);
             }
+            FarmWidgetInput::TogglePauseFarmPlotting => {
+                self.set_farm_plotting_paused(!self.farm_plotting_paused);
+                if sender
+                    .output(FarmWidgetOutput::PausePlotting {
+                        farm_index: self.farm_index,
+                        paused: self.farm_plotting_paused,
+                    })
+                    .is_err()
+                {
+                    debug!("Failed to send FarmWidgetOutput::PausePlotting");
+                }
+            }
+            FarmWidgetInput::ScrubDryRun(dry_run) => {
+                self.scrub_dry_run = dry_run;
+            }
+            FarmWidgetInput::ScrubFarm => {
+                if sender
+                    .output(FarmWidgetOutput::ScrubFarm {
+                        farm_index: self.farm_index,
+                        dry_run: self.scrub_dry_run,
+                    })
+                    .is_err()
+                {
+                    debug!("Failed to send FarmWidgetOutput::ScrubFarm");
+                }
+            }
+            FarmWidgetInput::ScrubStep(step) => {
+                let finished =
+                    matches!(step, ScrubStep::Finished { .. } | ScrubStep::Failed { .. });
+                self.get_mut_scrub_step().replace(step);
+
+                if finished {
+                    let body = self.scrub_status();
+                    sender.spawn_command(move |_sender| {
+                        let mut notification = Notification::new();
+                        notification
+                            .summary(&T.notification_farm_scrub_finished())
+                            .body(&body)
+                            .with_typical_options();
+                        if let Err(error) = notification.show() {
+                            warn!(%error, "Failed to show desktop notification");
+                        }
+                    });
+                }
+            }
+            FarmWidgetInput::DismissScrubResult => {
+                self.get_mut_scrub_step().take();
+            }
+            FarmWidgetInput::RestartStep(step) => {
+                self.get_mut_restart_step().replace(step);
+            }
+            FarmWidgetInput::ResizeProgress {
+                farm_resize,
+                progress,
+            } => {
+                self.get_mut_resize_progress()
+                    .replace((farm_resize, progress));
+            }
             FarmWidgetInput::OpenFarmFolder => {
                 if let Err(error) = open::that_detached(&self.path) {
                     error!(%error, path = %self.path.display(), "Failed to open farm folder");
@@ -597,6 +847,7 @@ impl FarmWidget {
                     }
                 });
 
+                self.get_mut_restart_step().take();
                 self.get_mut_error().replace(error);
             }
             FarmWidgetInput::WindowResized => {
@@ -682,6 +933,112 @@ impl FarmWidget {
         sector.set_tooltip_text(Some(tooltip.as_str()));
     }
 
+    /// Farm check is in progress, farm is stopped or about to be stopped
+    fn is_scrubbing(&self) -> bool {
+        !matches!(
+            self.scrub_step,
+            None | Some(ScrubStep::Finished { .. } | ScrubStep::Failed { .. })
+        )
+    }
+
+    fn scrub_status(&self) -> String {
+        let Some(step) = &self.scrub_step else {
+            return String::new();
+        };
+
+        match step {
+            ScrubStep::StoppingFarm => T.running_farmer_farm_scrub_stopping_farm(),
+            ScrubStep::Waiting => T.running_farmer_farm_scrub_waiting(),
+            ScrubStep::CheckingPlot => T.running_farmer_farm_scrub_checking_plot(),
+            ScrubStep::CheckingCache => T.running_farmer_farm_scrub_checking_cache(),
+            ScrubStep::StartingFarm => T.running_farmer_farm_scrub_starting_farm(),
+            ScrubStep::Finished { summary } => {
+                if summary.dry_run {
+                    T.running_farmer_farm_scrub_finished_dry_run(
+                        summary.plot_issues.len(),
+                        summary.cache_issues.len(),
+                    )
+                } else {
+                    T.running_farmer_farm_scrub_finished(
+                        summary.plot_issues.len(),
+                        summary.cache_issues.len(),
+                    )
+                }
+            }
+            ScrubStep::Failed { error } => T.running_farmer_farm_scrub_failed(error.to_string()),
+        }
+        .to_string()
+    }
+
+    fn restart_status(&self) -> String {
+        let Some(step) = &self.restart_step else {
+            return String::new();
+        };
+
+        match step {
+            FarmRestartStep::Scheduled {
+                error,
+                attempt,
+                max_attempts,
+                delay,
+            } => T.running_farmer_farm_restart_scheduled(
+                error.to_string(),
+                delay.as_secs(),
+                *attempt,
+                *max_attempts,
+            ),
+            FarmRestartStep::Restarting {
+                attempt,
+                max_attempts,
+            } => T.running_farmer_farm_restarting(*attempt, *max_attempts),
+        }
+        .to_string()
+    }
+
+    fn resize_status(&self) -> String {
+        let Some((farm_resize, progress)) = &self.resize_progress else {
+            return String::new();
+        };
+
+        T.running_farmer_farm_resizing(
+            format_size(farm_resize.old_allocated_space),
+            format_size(farm_resize.new_allocated_space),
+            *progress,
+        )
+        .to_string()
+    }
+
+    /// Issues found by the latest farm check, `None` if there were none
+    fn scrub_issues(&self) -> Option<String> {
+        let Some(ScrubStep::Finished { summary }) = &self.scrub_step else {
+            return None;
+        };
+
+        let issues = summary
+            .plot_issues
+            .iter()
+            .chain(&summary.cache_issues)
+            .collect::<Vec<_>>();
+        if issues.is_empty() {
+            return None;
+        }
+
+        let mut tooltip = issues
+            .iter()
+            .take(SCRUB_ISSUES_IN_TOOLTIP)
+            .map(|issue| issue.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if issues.len() > SCRUB_ISSUES_IN_TOOLTIP {
+            tooltip.push('\n');
+            tooltip.push_str(
+                &T.running_farmer_farm_scrub_more_issues(issues.len() - SCRUB_ISSUES_IN_TOOLTIP),
+            );
+        }
+
+        Some(tooltip)
+    }
+
     /// 0.0..=1.0
     fn farm_score(&self) -> f64 {
         // 95% success rate with signing is good
@@ -1,5 +1,7 @@
 use crate::backend::NodeNotification;
+use crate::backend::config::{NodePruning, SyncMode};
 use crate::backend::node::{ChainInfo, IN_PEERS, OUT_PEERS, SyncState};
+use crate::backend::utils::disk_usage;
 use crate::frontend::translations::{AsDefaultStr, T};
 use crate::icon_names;
 use bytesize::ByteSize;
@@ -19,6 +21,9 @@ use tracing::error;
 const MAX_IMPORTING_BLOCKS: BlockNumber = 2048;
 /// How frequently to check for free disk space
 const FREE_DISK_SPACE_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+/// How frequently to check disk space used by node, this is much more expensive than checking free
+/// disk space since it walks the whole node directory
+const NODE_DISK_USAGE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
 /// Free disk space below which warning must be shown
 const FREE_DISK_SPACE_CHECK_WARNING_THRESHOLD: u64 = ByteSize::gib(10).as_u64();
 /// Number of samples over which to track block import time, 1 minute in slots
@@ -29,9 +34,12 @@ const ALMOST_ALL_PEERS: u32 = OUT_PEERS + IN_PEERS - IN_PEERS / 5;
 #[derive(Debug)]
 pub enum NodeInput {
     Initialize {
-        best_block_number: BlockNumber,
+        /// Not known when connected to external node
+        best_block_number: Option<BlockNumber>,
         chain_info: ChainInfo,
         node_path: PathBuf,
+        node_pruning: NodePruning,
+        external_rpc_url: Option<String>,
     },
     NodeNotification(NodeNotification),
     OpenNodeFolder,
@@ -40,6 +48,7 @@ pub enum NodeInput {
 #[derive(Debug)]
 pub enum NodeCommandOutput {
     FreeDiskSpace(ByteSize),
+    NodeDiskUsage(ByteSize),
 }
 
 #[tracker::track]
@@ -49,7 +58,11 @@ pub struct NodeView {
     sync_state: SyncState,
     connected_peers: u32,
     free_disk_space: Option<ByteSize>,
+    node_disk_usage: Option<ByteSize>,
+    node_pruning: Option<NodePruning>,
     chain_name: String,
+    /// Farmer RPC URL of external node, best block and peers are not known in this case
+    external_rpc_url: Option<String>,
     #[no_eq]
     node_path: Arc<Mutex<PathBuf>>,
     #[no_eq]
@@ -90,6 +103,17 @@ impl Component for NodeView {
                     set_hexpand: true,
                     set_spacing: 10,
 
+                    gtk::Label {
+                        #[track = "model.changed_node_disk_usage()"]
+                        set_label: &model.node_disk_usage
+                            .map(|bytes| bytes.to_string_as(true))
+                            .unwrap_or_default(),
+                        #[track = "model.changed_node_disk_usage() || model.changed_node_pruning()"]
+                        set_tooltip: &model.node_disk_usage_tooltip(),
+                        #[track = "model.changed_node_disk_usage()"]
+                        set_visible: model.node_disk_usage.is_some(),
+                    },
+
                     gtk::Box {
                         set_spacing: 10,
                         #[track = "model.changed_free_disk_space()"]
@@ -145,6 +169,8 @@ impl Component for NodeView {
                         .as_str(),
                     set_uri: "https://docs.autonomys.xyz/farming/guides/port-config",
                     set_use_underline: false,
+                    #[track = "model.changed_external_rpc_url()"]
+                    set_visible: model.external_rpc_url.is_none(),
 
                     gtk::Box {
                         set_spacing: 5,
@@ -175,15 +201,16 @@ impl Component for NodeView {
             match model.sync_state {
                 SyncState::Unknown => gtk::Box {
                     gtk::Label {
-                        #[track = "model.changed_best_block_number()"]
-                        set_label: T
-                            .running_node_status_connecting(
-                                model.best_block_number
-                            )
-                            .as_str(),
+                        #[track = "model.changed_best_block_number() || model.changed_external_rpc_url()"]
+                        set_label: &match &model.external_rpc_url {
+                            Some(url) => T.running_node_status_external_syncing(url).to_string(),
+                            None => T
+                                .running_node_status_connecting(model.best_block_number)
+                                .to_string(),
+                        },
                     }
                 },
-                SyncState::Syncing { kind, target } => gtk::Box {
+                SyncState::Syncing { kind, mode, target } => gtk::Box {
                     set_orientation: gtk::Orientation::Vertical,
                     set_spacing: 10,
 
@@ -236,13 +263,22 @@ impl Component for NodeView {
                                     String::new()
                                 };
 
-                                T
-                                    .running_node_status_syncing(
-                                        kind.as_str(),
-                                        model.best_block_number,
-                                        target,
-                                        sync_speed,
-                                    )
+                                match mode {
+                                    SyncMode::Snap => T
+                                        .running_node_status_syncing(
+                                            kind.as_str(),
+                                            model.best_block_number,
+                                            target,
+                                            sync_speed,
+                                        ),
+                                    SyncMode::Full => T
+                                        .running_node_status_syncing_full(
+                                            kind.as_str(),
+                                            model.best_block_number,
+                                            target,
+                                            sync_speed,
+                                        ),
+                                }
                             }.as_str(),
                         },
 
@@ -258,8 +294,11 @@ impl Component for NodeView {
                 },
                 SyncState::Idle => gtk::Box {
                     gtk::Label {
-                        #[track = "model.changed_best_block_number()"]
-                        set_label: T.running_node_status_synced(model.best_block_number).as_str(),
+                        #[track = "model.changed_best_block_number() || model.changed_external_rpc_url()"]
+                        set_label: &match &model.external_rpc_url {
+                            Some(url) => T.running_node_status_external_synced(url).to_string(),
+                            None => T.running_node_status_synced(model.best_block_number).to_string(),
+                        },
                     }
                 },
             },
@@ -277,7 +316,10 @@ impl Component for NodeView {
             sync_state: SyncState::default(),
             connected_peers: 0,
             free_disk_space: None,
+            node_disk_usage: None,
+            node_pruning: None,
             chain_name: String::new(),
+            external_rpc_url: None,
             node_path: node_path.clone(),
             block_import_time: SingleSumSMA::from_zero(Duration::ZERO),
             last_block_import_time: None,
@@ -286,8 +328,15 @@ impl Component for NodeView {
 
         let widgets = view_output!();
 
+        sender.command({
+            let node_path = node_path.clone();
+
+            move |sender, shutdown_receiver| async move {
+                Self::check_free_disk_space(sender, shutdown_receiver, node_path).await;
+            }
+        });
         sender.command(move |sender, shutdown_receiver| async move {
-            Self::check_free_disk_space(sender, shutdown_receiver, node_path).await;
+            Self::check_node_disk_usage(sender, shutdown_receiver, node_path).await;
         });
 
         ComponentParts { model, widgets }
@@ -320,8 +369,13 @@ impl NodeView {
                 best_block_number,
                 chain_info,
                 node_path,
+                node_pruning,
+                external_rpc_url,
             } => {
-                self.set_best_block_number(best_block_number);
+                // Not shown when connected to external node
+                if let Some(best_block_number) = best_block_number {
+                    self.set_best_block_number(best_block_number);
+                }
                 self.set_chain_name(
                     chain_info
                         .chain_name
@@ -331,6 +385,8 @@ impl NodeView {
                         }),
                 );
                 *self.get_mut_node_path().lock() = node_path;
+                self.get_mut_node_pruning().replace(node_pruning);
+                self.set_external_rpc_url(external_rpc_url);
             }
             NodeInput::NodeNotification(node_notification) => match node_notification {
                 NodeNotification::SyncStateUpdate(mut new_sync_state) => {
@@ -394,6 +450,29 @@ impl NodeView {
             NodeCommandOutput::FreeDiskSpace(bytes) => {
                 self.get_mut_free_disk_space().replace(bytes);
             }
+            NodeCommandOutput::NodeDiskUsage(bytes) => {
+                self.get_mut_node_disk_usage().replace(bytes);
+            }
+        }
+    }
+
+    fn node_disk_usage_tooltip(&self) -> String {
+        let size = self
+            .node_disk_usage
+            .map(|bytes| bytes.to_string_as(true))
+            .unwrap_or_default();
+
+        match self.node_pruning {
+            Some(NodePruning::Pruned {
+                state_pruning,
+                blocks_pruning,
+            }) => T
+                .running_node_disk_usage_tooltip_pruned(size, state_pruning, blocks_pruning)
+                .to_string(),
+            Some(NodePruning::Archive) => {
+                T.running_node_disk_usage_tooltip_archive(size).to_string()
+            }
+            None => String::new(),
         }
     }
 
@@ -440,4 +519,47 @@ impl NodeView {
             .drop_on_shutdown()
             .await
     }
+
+    async fn check_node_disk_usage(
+        sender: Sender<NodeCommandOutput>,
+        shutdown_receiver: ShutdownReceiver,
+        node_path: Arc<Mutex<PathBuf>>,
+    ) {
+        shutdown_receiver
+            .register(async move {
+                loop {
+                    let node_path = node_path.lock().clone();
+
+                    if node_path == PathBuf::default() {
+                        tokio::time::sleep(FREE_DISK_SPACE_CHECK_INTERVAL).await;
+                        continue;
+                    }
+
+                    match tokio::task::spawn_blocking(move || disk_usage(&node_path)).await {
+                        Ok(Ok(node_disk_usage)) => {
+                            if sender
+                                .send(NodeCommandOutput::NodeDiskUsage(ByteSize::b(
+                                    node_disk_usage,
+                                )))
+                                .is_err()
+                            {
+                                break;
+                            }
+                        }
+                        Ok(Err(error)) => {
+                            error!(%error, "Failed to check node disk usage");
+                            break;
+                        }
+                        Err(error) => {
+                            error!(%error, "Node disk usage task panicked");
+                            break;
+                        }
+                    }
+
+                    tokio::time::sleep(NODE_DISK_USAGE_CHECK_INTERVAL).await;
+                }
+            })
+            .drop_on_shutdown()
+            .await
+    }
 }
@@ -0,0 +1,348 @@
+use crate::backend::FarmIndex;
+use crate::backend::config::RawConfig;
+use crate::backend::selective_wipe::{WipePlan, WipeTarget};
+use crate::frontend::translations::{AsDefaultStr, T};
+use bytesize::ByteSize;
+use gtk::prelude::*;
+use relm4::prelude::*;
+use tracing::warn;
+
+/// Order of targets in target selector
+const TARGET_FARM: u32 = 0;
+const TARGET_PIECE_CACHE: u32 = 1;
+const TARGET_NODE_DATABASE: u32 = 2;
+const TARGET_NODE_NETWORK: u32 = 3;
+
+#[derive(Debug)]
+pub enum WipeDialogInput {
+    Show { raw_config: RawConfig },
+    TargetSelected(u32),
+    FarmSelected(u32),
+    Confirm,
+    Cancel,
+}
+
+#[derive(Debug)]
+pub enum WipeDialogOutput {
+    /// User confirmed wiping everything in the plan
+    Wipe(WipePlan),
+}
+
+#[derive(Debug)]
+pub enum WipeDialogCommandOutput {
+    Plan {
+        target: WipeTarget,
+        result: Result<WipePlan, String>,
+    },
+}
+
+#[derive(Debug, Default)]
+enum PlanState {
+    #[default]
+    Calculating,
+    Ready(WipePlan),
+    Failed(String),
+}
+
+#[tracker::track]
+#[derive(Debug)]
+pub struct WipeDialog {
+    #[do_not_track]
+    raw_config: Option<RawConfig>,
+    #[do_not_track]
+    targets: gtk::StringList,
+    #[do_not_track]
+    farms: gtk::StringList,
+    selected_target: u32,
+    selected_farm: u32,
+    #[no_eq]
+    plan: PlanState,
+}
+
+#[relm4::component(pub)]
+impl Component for WipeDialog {
+    type Init = ();
+    type Input = WipeDialogInput;
+    type Output = WipeDialogOutput;
+    type CommandOutput = WipeDialogCommandOutput;
+
+    view! {
+        #[root]
+        gtk::Window {
+            set_default_width: 600,
+            set_hide_on_close: true,
+            set_modal: true,
+            set_title: Some(&T.wipe_dialog_title()),
+
+            gtk::Box {
+                set_margin_all: 10,
+                set_orientation: gtk::Orientation::Vertical,
+                set_spacing: 10,
+
+                gtk::Label {
+                    set_label: &T.wipe_dialog_description(),
+                    set_wrap: true,
+                    set_xalign: 0.0,
+                },
+
+                gtk::Box {
+                    set_spacing: 10,
+
+                    gtk::DropDown {
+                        set_model: Some(&model.targets),
+                        #[track = "model.changed_selected_target()"]
+                        set_selected: model.selected_target,
+                        connect_selected_notify[sender] => move |drop_down| {
+                            sender.input(WipeDialogInput::TargetSelected(drop_down.selected()));
+                        },
+                    },
+
+                    gtk::DropDown {
+                        set_hexpand: true,
+                        set_model: Some(&model.farms),
+                        #[track = "model.changed_selected_farm()"]
+                        set_selected: model.selected_farm,
+                        #[track = "model.changed_selected_target()"]
+                        set_visible: model.is_farm_target(),
+                        connect_selected_notify[sender] => move |drop_down| {
+                            sender.input(WipeDialogInput::FarmSelected(drop_down.selected()));
+                        },
+                    },
+                },
+
+                gtk::Label {
+                    #[track = "model.changed_selected_target()"]
+                    set_label: &model.target_effect(),
+                    set_wrap: true,
+                    set_xalign: 0.0,
+                },
+
+                gtk::Label {
+                    set_label: &T.wipe_dialog_paths(),
+                    set_xalign: 0.0,
+                },
+
+                gtk::ScrolledWindow {
+                    set_min_content_height: 100,
+                    set_vexpand: true,
+
+                    gtk::Label {
+                        #[track = "model.changed_plan()"]
+                        set_label: &model.plan_paths(),
+                        set_selectable: true,
+                        set_valign: gtk::Align::Start,
+                        set_xalign: 0.0,
+                    },
+                },
+
+                gtk::Label {
+                    #[track = "model.changed_plan()"]
+                    set_label: &model.plan_size(),
+                    set_xalign: 0.0,
+                },
+
+                gtk::Box {
+                    set_halign: gtk::Align::End,
+                    set_spacing: 10,
+
+                    gtk::Button {
+                        connect_clicked => WipeDialogInput::Cancel,
+
+                        gtk::Label {
+                            set_label: &T.wipe_dialog_button_cancel(),
+                            set_margin_all: 10,
+                        },
+                    },
+
+                    gtk::Button {
+                        add_css_class: "destructive-action",
+                        connect_clicked => WipeDialogInput::Confirm,
+                        #[track = "model.changed_plan()"]
+                        set_sensitive: matches!(
+                            &model.plan,
+                            PlanState::Ready(plan) if !plan.paths.is_empty()
+                        ),
+
+                        gtk::Label {
+                            set_label: &T.wipe_dialog_button_wipe(),
+                            set_margin_all: 10,
+                        },
+                    },
+                },
+            },
+        }
+    }
+
+    fn init(
+        _init: Self::Init,
+        _root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let targets = gtk::StringList::new(&[
+            &*T.wipe_dialog_target_farm(),
+            &*T.wipe_dialog_target_piece_cache(),
+            &*T.wipe_dialog_target_node_database(),
+            &*T.wipe_dialog_target_node_network(),
+        ]);
+
+        let model = Self {
+            raw_config: None,
+            targets,
+            farms: gtk::StringList::new(&[]),
+            selected_target: TARGET_FARM,
+            selected_farm: 0,
+            plan: PlanState::default(),
+            tracker: u8::MAX,
+        };
+
+        let widgets = view_output!();
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, input: Self::Input, sender: ComponentSender<Self>, root: &Self::Root) {
+        // Reset changes
+        self.reset();
+
+        match input {
+            WipeDialogInput::Show { raw_config } => {
+                let farm_paths = raw_config
+                    .farms()
+                    .iter()
+                    .map(|farm| farm.path.display().to_string())
+                    .collect::<Vec<_>>();
+                self.farms.splice(
+                    0,
+                    self.farms.n_items(),
+                    &farm_paths.iter().map(String::as_str).collect::<Vec<_>>(),
+                );
+                self.raw_config.replace(raw_config);
+                self.set_selected_farm(0);
+                self.update_plan(&sender);
+
+                root.present();
+            }
+            WipeDialogInput::TargetSelected(selected_target) => {
+                if self.selected_target != selected_target {
+                    self.set_selected_target(selected_target);
+                    self.update_plan(&sender);
+                }
+            }
+            WipeDialogInput::FarmSelected(selected_farm) => {
+                if self.selected_farm != selected_farm {
+                    self.set_selected_farm(selected_farm);
+                    self.update_plan(&sender);
+                }
+            }
+            WipeDialogInput::Confirm => {
+                if let PlanState::Ready(plan) = &self.plan {
+                    if sender.output(WipeDialogOutput::Wipe(plan.clone())).is_err() {
+                        warn!("Failed to send wipe confirmation");
+                    }
+                    root.set_visible(false);
+                }
+            }
+            WipeDialogInput::Cancel => {
+                root.set_visible(false);
+            }
+        }
+    }
+
+    fn update_cmd(
+        &mut self,
+        input: Self::CommandOutput,
+        _sender: ComponentSender<Self>,
+        _root: &Self::Root,
+    ) {
+        // Reset changes
+        self.reset();
+
+        match input {
+            WipeDialogCommandOutput::Plan { target, result } => {
+                // Selection might have changed while plan was being calculated
+                if target != self.target() {
+                    return;
+                }
+
+                self.set_plan(match result {
+                    Ok(plan) => PlanState::Ready(plan),
+                    Err(error) => PlanState::Failed(error),
+                });
+            }
+        }
+    }
+}
+
+impl WipeDialog {
+    fn target(&self) -> WipeTarget {
+        // There can't be more farms than farm index allows
+        let farm_index = self.selected_farm as FarmIndex;
+
+        match self.selected_target {
+            TARGET_PIECE_CACHE => WipeTarget::PieceCache { farm_index },
+            TARGET_NODE_DATABASE => WipeTarget::NodeDatabase,
+            TARGET_NODE_NETWORK => WipeTarget::NodeNetwork,
+            _ => WipeTarget::Farm { farm_index },
+        }
+    }
+
+    fn is_farm_target(&self) -> bool {
+        matches!(
+            self.target(),
+            WipeTarget::Farm { .. } | WipeTarget::PieceCache { .. }
+        )
+    }
+
+    fn target_effect(&self) -> String {
+        match self.target() {
+            WipeTarget::Farm { .. } => T.wipe_dialog_effect_farm(),
+            WipeTarget::PieceCache { .. } => T.wipe_dialog_effect_piece_cache(),
+            WipeTarget::NodeDatabase => T.wipe_dialog_effect_node_database(),
+            WipeTarget::NodeNetwork => T.wipe_dialog_effect_node_network(),
+        }
+        .to_string()
+    }
+
+    fn plan_paths(&self) -> String {
+        match &self.plan {
+            PlanState::Calculating => T.wipe_dialog_calculating().to_string(),
+            PlanState::Ready(plan) => {
+                if plan.paths.is_empty() {
+                    T.wipe_dialog_nothing_to_wipe().to_string()
+                } else {
+                    plan.paths
+                        .iter()
+                        .map(|path| path.display().to_string())
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                }
+            }
+            PlanState::Failed(error) => T.wipe_dialog_failed(error.as_str()).to_string(),
+        }
+    }
+
+    fn plan_size(&self) -> String {
+        match &self.plan {
+            PlanState::Ready(plan) => T
+                .wipe_dialog_size(ByteSize::b(plan.size).to_string_as(true))
+                .to_string(),
+            PlanState::Calculating | PlanState::Failed(_) => String::new(),
+        }
+    }
+
+    fn update_plan(&mut self, sender: &ComponentSender<Self>) {
+        let Some(raw_config) = self.raw_config.clone() else {
+            return;
+        };
+        let target = self.target();
+
+        self.set_plan(PlanState::Calculating);
+        sender.oneshot_command(async move {
+            let result = WipePlan::new(&raw_config, target)
+                .await
+                .map_err(|error| error.to_string());
+
+            WipeDialogCommandOutput::Plan { target, result }
+        });
+    }
+}
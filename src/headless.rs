@@ -0,0 +1,297 @@
+use crate::AppStatusCode;
+use crate::backend::farmer::FarmerNotification;
+use crate::backend::farmer::scrub::ScrubStep;
+use crate::backend::{
+    BackendAction, BackendNotification, ConfigSaveOutcome, FarmIndex, NodeNotification,
+};
+use futures::channel::mpsc;
+use futures::{FutureExt, StreamExt, select};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::pin::pin;
+use subspace_farmer::farm::{
+    FarmingNotification, ProvingResult, SectorPlottingDetails, SectorUpdate,
+};
+use subspace_farmer::utils::run_future_in_dedicated_thread;
+use tracing::{debug, error, info, warn};
+
+/// Runs backend without GUI, printing structured progress into logs instead. Farming history is
+/// persisted in `app_data_dir` if provided.
+///
+/// Returns status code that supervisor should act upon.
+pub(super) async fn run(
+    api_address: Option<SocketAddr>,
+    app_data_dir: Option<PathBuf>,
+) -> AppStatusCode {
+    // Sender is only used by local control API, but must be kept alive for backend to keep running
+    let (backend_action_sender, backend_action_receiver) = mpsc::channel::<BackendAction>(1);
+    let (backend_notification_sender, backend_notification_receiver) = mpsc::channel(100);
+    let mut backend_notification_receiver = crate::api::maybe_start(
+        api_address,
+        &backend_action_sender,
+        backend_notification_receiver,
+    );
+
+    // Create and run backend in dedicated thread
+    let backend_fut = match run_future_in_dedicated_thread(
+        move || {
+            crate::backend::create(
+                backend_action_receiver,
+                backend_notification_sender,
+                app_data_dir,
+            )
+        },
+        "backend".to_string(),
+    ) {
+        Ok(backend_fut) => backend_fut,
+        Err(error) => {
+            error!(%error, "Failed to spawn backend thread");
+            return AppStatusCode::Unknown(1);
+        }
+    };
+
+    let process_notifications_fut = async {
+        while let Some(notification) = backend_notification_receiver.next().await {
+            if let Some(exit_status_code) = process_backend_notification(notification) {
+                return Some(exit_status_code);
+            }
+        }
+
+        None
+    };
+
+    let process_notifications_fut = pin!(process_notifications_fut);
+    let ctrl_c_fut = pin!(tokio::signal::ctrl_c());
+
+    select! {
+        maybe_exit_status_code = process_notifications_fut.fuse() => {
+            if let Some(exit_status_code) = maybe_exit_status_code {
+                return exit_status_code;
+            }
+
+            // Notifications channel was closed, which means backend has exited
+            match backend_fut.await {
+                Ok(()) => {
+                    info!("Backend exited");
+                    AppStatusCode::Exit
+                }
+                Err(_) => {
+                    error!("Backend spawning failed");
+                    AppStatusCode::Unknown(1)
+                }
+            }
+        }
+        result = ctrl_c_fut.fuse() => {
+            if let Err(error) = result {
+                error!(%error, "Failed to listen for interrupt signal");
+            } else {
+                info!("Received interrupt signal, exiting");
+            }
+
+            AppStatusCode::Exit
+        }
+    }
+}
+
+/// Returns `Some(status_code)` if application needs to exit
+fn process_backend_notification(notification: BackendNotification) -> Option<AppStatusCode> {
+    match notification {
+        BackendNotification::Loading(step) => {
+            info!(progress = %step.progress(), ?step, "Loading");
+        }
+        BackendNotification::ConfigurationFound { raw_config } => {
+            debug!(?raw_config, "Configuration found");
+        }
+        BackendNotification::IncompatibleChain { compatible_chain } => {
+            error!(
+                %compatible_chain,
+                "Existing data is for a different chain, start Space Acres with GUI to upgrade"
+            );
+            return Some(AppStatusCode::IncompatibleChain);
+        }
+        BackendNotification::NotConfigured => {
+            error!(
+                "Space Acres is not configured yet, configuration is required to run in headless \
+                mode"
+            );
+            return Some(AppStatusCode::ConfigurationIsInvalid);
+        }
+        BackendNotification::ConfigurationIsInvalid { error } => {
+            error!(%error, "Configuration is invalid");
+            return Some(AppStatusCode::ConfigurationIsInvalid);
+        }
+        BackendNotification::ConfigSaveResult(result) => match result {
+            Ok(ConfigSaveOutcome::Applied) => {
+                info!("Configuration saved and applied");
+            }
+            Ok(ConfigSaveOutcome::RestartRequired) => {
+                info!("Configuration saved, restart is needed for changes to take effect");
+            }
+            Err(error) => {
+                error!(%error, "Failed to save configuration");
+            }
+        },
+        BackendNotification::Running {
+            config,
+            best_block_number,
+            reward_address_balance,
+            initial_farm_states,
+            chain_info,
+            ..
+        } => {
+            info!(
+                chain = %chain_info.chain_name,
+                ?best_block_number,
+                ?reward_address_balance,
+                token_symbol = %chain_info.token_symbol,
+                "Running"
+            );
+            for (farm_index, (farm, initial_farm_state)) in
+                config.farms.iter().zip(initial_farm_states).enumerate()
+            {
+                info!(
+                    %farm_index,
+                    directory = %farm.directory.display(),
+                    plotted_sectors = %initial_farm_state.plotted_sectors_count,
+                    total_sectors = %initial_farm_state.total_sectors_count,
+                    "Farm is running"
+                );
+            }
+        }
+        BackendNotification::Node(node_notification) => match node_notification {
+            NodeNotification::SyncStateUpdate(sync_state) => {
+                info!(?sync_state, "Sync state updated");
+            }
+            NodeNotification::ConnectedPeersUpdate(connected_peers) => {
+                debug!(%connected_peers, "Connected peers updated");
+            }
+            NodeNotification::BlockImported(imported_block) => {
+                debug!(
+                    block_number = %imported_block.number,
+                    reward_address_balance = %imported_block.reward_address_balance,
+                    "Block imported"
+                );
+            }
+        },
+        BackendNotification::Farmer(farmer_notification) => {
+            process_farmer_notification(farmer_notification);
+        }
+        BackendNotification::Stopped { error } => {
+            return Some(match error {
+                Some(error) => {
+                    error!(%error, "Backend stopped with error, restarting");
+                    AppStatusCode::Restart
+                }
+                None => {
+                    info!("Backend stopped");
+                    AppStatusCode::Exit
+                }
+            });
+        }
+        BackendNotification::IrrecoverableError { error } => {
+            error!(%error, "Irrecoverable error");
+            return Some(AppStatusCode::IrrecoverableError);
+        }
+    }
+
+    None
+}
+
+fn process_farmer_notification(farmer_notification: FarmerNotification<FarmIndex>) {
+    match farmer_notification {
+        FarmerNotification::SectorUpdate {
+            farm_index,
+            sector_index,
+            update,
+        } => match update {
+            SectorUpdate::Plotting(SectorPlottingDetails::Finished {
+                old_plotted_sector,
+                time,
+                ..
+            }) => {
+                info!(
+                    %farm_index,
+                    %sector_index,
+                    replotted = %old_plotted_sector.is_some(),
+                    ?time,
+                    "Sector plotted"
+                );
+            }
+            SectorUpdate::Plotting(SectorPlottingDetails::Error(error)) => {
+                warn!(%farm_index, %sector_index, %error, "Sector plotting failed");
+            }
+            update => {
+                debug!(%farm_index, %sector_index, ?update, "Sector update");
+            }
+        },
+        FarmerNotification::FarmingNotification {
+            farm_index,
+            notification,
+        } => match notification {
+            FarmingNotification::Proving(proving_details) => match proving_details.result {
+                ProvingResult::Success => {
+                    info!(%farm_index, time = ?proving_details.time, "Signed reward successfully");
+                }
+                ProvingResult::Timeout | ProvingResult::Rejected | ProvingResult::Failed => {
+                    warn!(
+                        %farm_index,
+                        result = ?proving_details.result,
+                        time = ?proving_details.time,
+                        "Missed reward"
+                    );
+                }
+            },
+            FarmingNotification::NonFatalError(error) => {
+                warn!(%farm_index, %error, "Non-fatal farming error");
+            }
+            notification => {
+                debug!(%farm_index, ?notification, "Farming notification");
+            }
+        },
+        FarmerNotification::FarmerCacheSyncProgress { progress } => {
+            info!(%progress, "Piece cache sync progress");
+        }
+        FarmerNotification::FarmError { farm_index, error } => {
+            error!(%farm_index, %error, "Farm error");
+        }
+        FarmerNotification::FarmAdded {
+            farm_index,
+            farm,
+            initial_farm_state,
+            plotting_paused,
+        } => {
+            info!(
+                %farm_index,
+                directory = %farm.directory.display(),
+                plotted_sectors = %initial_farm_state.plotted_sectors_count,
+                total_sectors = %initial_farm_state.total_sectors_count,
+                %plotting_paused,
+                "Farm added"
+            );
+        }
+        FarmerNotification::FarmRemoved { farm_index } => {
+            info!(%farm_index, "Farm removed");
+        }
+        FarmerNotification::PlottingScheduleUpdate { .. } => {
+            // Farmer logs plotting schedule changes itself
+        }
+        FarmerNotification::FarmScrub { farm_index, step } => match step {
+            ScrubStep::Finished { .. } | ScrubStep::Failed { .. } => {
+                // Farmer logs check results itself, issues are logged while checking
+            }
+            step => {
+                info!(%farm_index, ?step, "Farm check progress");
+            }
+        },
+        FarmerNotification::FarmRestart { .. } => {
+            // Farmer logs restarts itself
+        }
+        FarmerNotification::FarmResize {
+            farm_index,
+            progress,
+            ..
+        } => {
+            debug!(%farm_index, %progress, "Farm resize progress");
+        }
+    }
+}
@@ -13,12 +13,17 @@
 //  https://github.com/rust-lang/rust/issues/133199
 #![feature(generic_const_exprs)]
 
+mod api;
 mod backend;
+mod commands;
 mod frontend;
+mod headless;
 mod icon_names {
     include!(concat!(env!("OUT_DIR"), "/icon_names.rs"));
 }
 
+use crate::backend::farmer::scrub::scrub_issues_layer;
+use crate::commands::Command;
 use crate::frontend::{App, AppInit, GLOBAL_CSS, RunBackendResult};
 use bytesize::ByteSize;
 use clap::Parser;
@@ -33,6 +38,7 @@ use relm4::prelude::*;
 use std::borrow::Cow;
 use std::cell::Cell;
 use std::io::{Read, Write};
+use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
 use std::process::{ExitCode, Termination};
 use std::rc::Rc;
@@ -123,6 +129,12 @@ where
 enum AppStatusCode {
     Exit,
     Restart,
+    /// Existing data is for a different chain and needs to be upgraded, used in headless mode
+    IncompatibleChain,
+    /// Configuration is missing or invalid, used in headless mode
+    ConfigurationIsInvalid,
+    /// Backend failed with irrecoverable error, used in headless mode
+    IrrecoverableError,
     Unknown(i32),
 }
 
@@ -131,6 +143,9 @@ impl AppStatusCode {
         match status_code {
             0 => Self::Exit,
             100 => Self::Restart,
+            101 => Self::IncompatibleChain,
+            102 => Self::ConfigurationIsInvalid,
+            103 => Self::IrrecoverableError,
             code => Self::Unknown(code),
         }
     }
@@ -139,14 +154,20 @@ impl AppStatusCode {
         match self {
             AppStatusCode::Exit => 0,
             AppStatusCode::Restart => 100,
+            AppStatusCode::IncompatibleChain => 101,
+            AppStatusCode::ConfigurationIsInvalid => 102,
+            AppStatusCode::IrrecoverableError => 103,
             AppStatusCode::Unknown(code) => code,
         }
     }
 }
 
 #[derive(Debug, Parser)]
-#[clap(about, version)]
+#[clap(about, version, args_conflicts_with_subcommands = true)]
 struct Cli {
+    /// Command to run instead of starting the application
+    #[command(subcommand)]
+    command: Option<Command>,
     /// Used for startup to minimize the window
     #[arg(long)]
     startup: bool,
@@ -156,6 +177,14 @@ struct Cli {
     /// Used by child process such that supervisor parent process can control it
     #[arg(long)]
     child_process: bool,
+    /// Run without GUI, printing progress to logs instead (requires existing configuration)
+    #[arg(long)]
+    headless: bool,
+    /// Start local control API on specified loopback address (like `127.0.0.1:9955`) that streams
+    /// backend notifications and accepts actions as newline-delimited JSON, clients authenticate
+    /// with token from `api-token` file next to configuration file
+    #[arg(long, value_parser = parse_api_address)]
+    api_address: Option<SocketAddr>,
     /// Show uninstall dialog to delete configuration and logs, typically called from installer
     /// during package uninstallation
     #[arg(long)]
@@ -168,7 +197,11 @@ struct Cli {
 
 impl Cli {
     fn run(self) -> ExitCode {
-        if self.uninstall {
+        if let Some(command) = self.command {
+            Self::tracing_logger_init_simple();
+
+            command.run()
+        } else if self.uninstall {
             #[cfg(windows)]
             {
                 let dirs_to_remove = env::var_os("SystemDrive")
@@ -209,7 +242,12 @@ impl Cli {
 
             ExitCode::SUCCESS
         } else if self.child_process {
-            ExitCode::from(self.app().into_status_code() as u8)
+            let exit_status_code = if self.headless {
+                self.headless()
+            } else {
+                self.app()
+            };
+            ExitCode::from(exit_status_code.into_status_code() as u8)
         } else {
             self.supervisor().report()
         }
@@ -218,26 +256,7 @@ impl Cli {
     fn app(self) -> AppStatusCode {
         let maybe_app_data_dir = Self::app_data_dir();
 
-        if WINDOWS_SUBSYSTEM_WINDOWS {
-            let (layer, filter) = Self::tracing_logger_init_common();
-
-            if let Some(app_data_dir) = &maybe_app_data_dir {
-                let logger = Mutex::new(Self::new_logger(app_data_dir));
-                let layer = layer.with_writer(logger);
-
-                tracing_subscriber::registry()
-                    .with(layer.with_filter(filter))
-                    .init();
-            } else {
-                tracing_subscriber::registry()
-                    .with(layer.with_filter(filter))
-                    .init();
-            }
-            #[cfg(windows)]
-            std::panic::set_hook(Box::new(tracing_panic::panic_hook));
-        } else {
-            Self::tracing_logger_init_simple();
-        }
+        Self::tracing_logger_init_child(maybe_app_data_dir.as_deref());
 
         info!(
             "Starting {} {}",
@@ -315,20 +334,34 @@ impl Cli {
         }
 
         let exit_status_code = Rc::new(Cell::new(AppStatusCode::Exit));
+        let api_address = self.api_address;
+        let backend_app_data_dir = maybe_app_data_dir.clone();
 
         app.run_async::<App>(AppInit {
             app_data_dir: maybe_app_data_dir,
             exit_status_code: Rc::clone(&exit_status_code),
             minimize_on_start: self.startup,
             crash_notification: self.after_crash,
-            run_backend: || {
+            run_backend: Box::new(move || {
                 let (backend_action_sender, backend_action_receiver) = mpsc::channel(1);
                 let (backend_notification_sender, backend_notification_receiver) =
                     mpsc::channel(100);
+                let backend_notification_receiver = api::maybe_start(
+                    api_address,
+                    &backend_action_sender,
+                    backend_notification_receiver,
+                );
 
                 // Create and run backend in dedicated thread
+                let app_data_dir = backend_app_data_dir.clone();
                 let backend_fut = run_future_in_dedicated_thread(
-                    move || backend::create(backend_action_receiver, backend_notification_sender),
+                    move || {
+                        backend::create(
+                            backend_action_receiver,
+                            backend_notification_sender,
+                            app_data_dir,
+                        )
+                    },
                     "backend".to_string(),
                 )
                 .expect("Must be able to spawn a thread");
@@ -347,7 +380,7 @@ impl Cli {
                     backend_action_sender,
                     backend_notification_receiver,
                 }
-            },
+            }),
         });
 
         let exit_status_code = exit_status_code.get();
@@ -360,6 +393,42 @@ impl Cli {
         exit_status_code
     }
 
+    fn headless(self) -> AppStatusCode {
+        let maybe_app_data_dir = Self::app_data_dir();
+
+        Self::tracing_logger_init_child(maybe_app_data_dir.as_deref());
+
+        info!(
+            "Starting {} {} in headless mode",
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION")
+        );
+
+        let runtime = match tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+        {
+            Ok(runtime) => runtime,
+            Err(error) => {
+                error!(%error, "Failed to create Tokio runtime");
+                return AppStatusCode::Unknown(1);
+            }
+        };
+
+        let exit_status_code =
+            runtime.block_on(headless::run(self.api_address, maybe_app_data_dir));
+        // Backend might be stuck in blocking operations, don't wait for it
+        runtime.shutdown_background();
+
+        info!(
+            ?exit_status_code,
+            "Exiting {} {}",
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION")
+        );
+        exit_status_code
+    }
+
     fn supervisor(mut self) -> io::Result<()> {
         let maybe_app_data_dir = Self::app_data_dir();
 
@@ -370,6 +439,12 @@ impl Cli {
 
         loop {
             let mut args = vec!["--child-process".to_string()];
+            if self.headless {
+                args.push("--headless".to_string());
+            }
+            if let Some(api_address) = self.api_address {
+                args.push(format!("--api-address={api_address}"));
+            }
             if self.after_crash {
                 self.after_crash = false;
 
@@ -514,6 +589,16 @@ impl Cli {
                         error!("Restarting application");
                         continue;
                     }
+                    app_status_code @ (AppStatusCode::IncompatibleChain
+                    | AppStatusCode::ConfigurationIsInvalid
+                    | AppStatusCode::IrrecoverableError) => {
+                        error!(
+                            ?app_status_code,
+                            "Application exited with error that requires user intervention"
+                        );
+
+                        process::exit(app_status_code.into_status_code());
+                    }
                     AppStatusCode::Unknown(status_code) => {
                         error!(%status_code, "Application exited with unexpected status code");
 
@@ -570,11 +655,38 @@ impl Cli {
             })
     }
 
+    /// Logger initialization for child process, which on Windows needs to write logs to file itself
+    fn tracing_logger_init_child(maybe_app_data_dir: Option<&Path>) {
+        if WINDOWS_SUBSYSTEM_WINDOWS {
+            let (layer, filter) = Self::tracing_logger_init_common();
+
+            if let Some(app_data_dir) = maybe_app_data_dir {
+                let logger = Mutex::new(Self::new_logger(app_data_dir));
+                let layer = layer.with_writer(logger);
+
+                tracing_subscriber::registry()
+                    .with(layer.with_filter(filter))
+                    .with(scrub_issues_layer())
+                    .init();
+            } else {
+                tracing_subscriber::registry()
+                    .with(layer.with_filter(filter))
+                    .with(scrub_issues_layer())
+                    .init();
+            }
+            #[cfg(windows)]
+            std::panic::set_hook(Box::new(tracing_panic::panic_hook));
+        } else {
+            Self::tracing_logger_init_simple();
+        }
+    }
+
     fn tracing_logger_init_simple() {
         let (layer, filter) = Self::tracing_logger_init_common();
 
         tracing_subscriber::registry()
             .with(layer.with_filter(filter))
+            .with(scrub_issues_layer())
             .init();
     }
 
@@ -661,6 +773,19 @@ impl Cli {
     }
 }
 
+fn parse_api_address(s: &str) -> Result<SocketAddr, String> {
+    let address = s
+        .parse::<SocketAddr>()
+        .map_err(|error| format!("Invalid API address \"{s}\": {error}"))?;
+
+    // Authentication token is sent in plaintext, so API must not be reachable from other machines
+    if !address.ip().is_loopback() {
+        return Err(format!("API address \"{s}\" must be a loopback address"));
+    }
+
+    Ok(address)
+}
+
 fn main() -> ExitCode {
     raise_fd_limit();
 